@@ -0,0 +1,357 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use uuid::Uuid;
+use chrono::Utc;
+use image::{DynamicImage, ImageOutputFormat, Rgba};
+
+use crate::commands::photo_journal::{find_photo_journal_image, read_photo_journal, write_photo_journal};
+use crate::commands::references::crud::find_reference;
+use crate::commands::references::files::resolve_image_path;
+use crate::imaging::{
+    analyze_alignment as analyze_drawing_alignment, apply_rotation_and_crop, difference, encode_image,
+    fit_within, load_oriented, onion_skin, render_alignment_heatmap, render_value_chart, scale_to_height,
+    side_by_side, to_data_url, value_stats, ValueStats,
+};
+use crate::models::{JournalComparison, PhotoJournalImage, Reference};
+use crate::state::AppState;
+
+/// Default common height; large enough to judge proportions, small enough to stay fast.
+const DEFAULT_COMPARISON_HEIGHT: u32 = 1200;
+const COMPARISON_BACKGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const DEFAULT_HEATMAP_SIZE: u32 = 1024;
+/// Statistics don't need full resolution
+const VALUE_ANALYSIS_SIZE: u32 = 1024;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ComparisonMode {
+    SideBySide,
+    OnionSkin,
+    Difference,
+}
+
+impl ComparisonMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ComparisonMode::SideBySide => "sideBySide",
+            ComparisonMode::OnionSkin => "onionSkin",
+            ComparisonMode::Difference => "difference",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonResult {
+    pub data_url: String,
+    pub width: u32,
+    pub height: u32,
+    /// Set when the render was saved into the journal entry
+    pub comparison: Option<JournalComparison>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionProportionError {
+    /// "top-left", "top", ..., "bottom-right" (thirds of the reference)
+    pub region: String,
+    pub edge_points: usize,
+    /// Offset of the drawn region from where it should be, % of reference width/height
+    pub offset_x_percent: Option<f64>,
+    pub offset_y_percent: Option<f64>,
+    /// Positive when the region is drawn too large, negative when too small (%)
+    pub scale_error_percent: Option<f64>,
+    /// Mean distance from drawn edges to reference edges, % of reference height
+    pub mean_residual_percent: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignmentResult {
+    /// Residual heatmap over the reference (PNG data URL); green = on target, red = off
+    pub heatmap_data_url: String,
+    pub width: u32,
+    pub height: u32,
+    /// Similarity transform mapping the artwork onto the reference once both share a height
+    pub scale: f64,
+    pub rotation_degrees: f64,
+    /// % of reference height
+    pub translate_x_percent: f64,
+    pub translate_y_percent: f64,
+    /// Positive when the drawing is too wide for its height (%)
+    pub aspect_error_percent: f64,
+    /// Weighted share of drawn edges landing on reference edges (0-1)
+    pub match_score: f64,
+    pub mean_residual_percent: f64,
+    pub regions: Vec<RegionProportionError>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueStatsResult {
+    /// 256 luminance bins
+    pub histogram: Vec<u64>,
+    pub pixels: u64,
+    /// 1st / 99th percentile luminance
+    pub darkest: u8,
+    pub lightest: u8,
+    pub range: u8,
+    pub mean: f32,
+    pub median: u8,
+    pub std_dev: f32,
+    /// Share of the image (%) in five equal value bands, darkest first
+    pub distribution: Vec<f32>,
+}
+
+impl From<ValueStats> for ValueStatsResult {
+    fn from(stats: ValueStats) -> Self {
+        ValueStatsResult {
+            histogram: stats.histogram.to_vec(),
+            pixels: stats.pixels,
+            darkest: stats.darkest,
+            lightest: stats.lightest,
+            range: stats.lightest - stats.darkest,
+            mean: (stats.mean * 10.0).round() / 10.0,
+            median: stats.median,
+            std_dev: (stats.std_dev * 10.0).round() / 10.0,
+            distribution: stats.distribution.iter().map(|d| (d * 10.0).round() / 10.0).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueComparisonResult {
+    pub artwork: ValueStatsResult,
+    pub reference: ValueStatsResult,
+    /// Side-by-side value-scale chart (PNG data URL)
+    pub chart_data_url: String,
+}
+
+/// The journal artwork as displayed: EXIF orientation plus its stored rotation.
+pub(crate) fn render_journal_artwork(state: &AppState, image: &PhotoJournalImage) -> Result<DynamicImage, String> {
+    let full_path = resolve_image_path(state, &image.url);
+    if !full_path.exists() {
+        return Err(format!("Image file not found: {:?}", full_path));
+    }
+    Ok(apply_rotation_and_crop(load_oriented(&full_path)?, image.rotation, None))
+}
+
+/// The reference as framed in the viewer: EXIF orientation, rotation and crop.
+/// Adjustments are left out so the comparison shows the actual source.
+pub(crate) fn render_reference_framed(state: &AppState, reference: &Reference) -> Result<DynamicImage, String> {
+    let full_path = resolve_image_path(state, &reference.url);
+    if !full_path.exists() {
+        return Err(format!("Image file not found: {:?}", full_path));
+    }
+    Ok(apply_rotation_and_crop(load_oriented(&full_path)?, reference.rotation, reference.crop.as_ref()))
+}
+
+/// Journal entry, its artwork and the reference it is compared against
+/// (`reference_id` or the entry's linked reference).
+pub(crate) fn load_comparison_pair(
+    state: &AppState,
+    photo_id: &str,
+    reference_id: Option<&str>,
+) -> Result<(PhotoJournalImage, DynamicImage, Reference, DynamicImage), String> {
+    let entry = find_photo_journal_image(state, photo_id)?;
+    let reference_id = reference_id
+        .map(|s| s.to_string())
+        .or_else(|| entry.reference_id.clone())
+        .ok_or_else(|| "Journal entry has no linked reference".to_string())?;
+    let reference = find_reference(state, &reference_id)?;
+
+    let artwork = render_journal_artwork(state, &entry)?;
+    let reference_img = render_reference_framed(state, &reference)?;
+    Ok((entry, artwork, reference, reference_img))
+}
+
+fn save_comparison(
+    state: &AppState,
+    entry: &PhotoJournalImage,
+    reference_id: &str,
+    mode: ComparisonMode,
+    jpeg: &[u8],
+) -> Result<JournalComparison, String> {
+    let comparisons_dir = state.library_dir.join("Artwork Journal").join("Comparisons");
+    fs::create_dir_all(&comparisons_dir)
+        .map_err(|e| format!("Failed to create comparisons directory: {}", e))?;
+
+    let stem = Path::new(&entry.filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("artwork");
+    let created_at = Utc::now().timestamp_millis();
+    let filename = format!("{}-{}-{}.jpg", stem, mode.as_str(), created_at);
+    fs::write(comparisons_dir.join(&filename), jpeg)
+        .map_err(|e| format!("Failed to write comparison: {}", e))?;
+
+    let comparison = JournalComparison {
+        id: Uuid::new_v4().to_string(),
+        filename: filename.clone(),
+        url: format!("artwork_journal/Comparisons/{}", filename),
+        mode: mode.as_str().to_string(),
+        reference_id: reference_id.to_string(),
+        created_at,
+    };
+
+    let mut images = read_photo_journal(state)?;
+    let idx = images.iter().position(|img| img.id == entry.id)
+        .ok_or_else(|| "Image not found".to_string())?;
+    images[idx].comparisons.push(comparison.clone());
+    write_photo_journal(state, &images)?;
+
+    Ok(comparison)
+}
+
+/// Compare a journal artwork with its reference. Both are scaled to a common `height`
+/// after applying their own rotation (and the reference's crop).
+///
+/// - `sideBySide`: reference left, artwork right
+/// - `onionSkin`: artwork over the reference at `opacity` (default 0.5)
+/// - `difference`: absolute per-channel difference; matching areas are black
+///
+/// With `save`, the JPEG is stored under `Artwork Journal/Comparisons` and recorded
+/// in the entry's `comparisons`.
+#[tauri::command]
+pub async fn render_comparison(
+    state: State<'_, AppState>,
+    photo_id: String,
+    reference_id: Option<String>,
+    mode: ComparisonMode,
+    opacity: Option<f32>,
+    height: Option<u32>,
+    save: Option<bool>,
+) -> Result<ComparisonResult, String> {
+    if opacity.is_some_and(|o| !(0.0..=1.0).contains(&o)) {
+        return Err("Opacity must be within [0, 1]".to_string());
+    }
+    let (entry, artwork, reference, reference_img) =
+        load_comparison_pair(&state, &photo_id, reference_id.as_deref())?;
+
+    // Never upscale beyond the smaller of the two sources by default
+    let common_height = height
+        .unwrap_or_else(|| artwork.height().min(reference_img.height()).min(DEFAULT_COMPARISON_HEIGHT))
+        .clamp(16, 8000);
+    let artwork = scale_to_height(&artwork, common_height);
+    let reference_img = scale_to_height(&reference_img, common_height);
+
+    let rendered = match mode {
+        ComparisonMode::SideBySide => {
+            let gap = (common_height / 40).max(4);
+            side_by_side(&reference_img, &artwork, gap, COMPARISON_BACKGROUND)
+        }
+        ComparisonMode::OnionSkin => onion_skin(&reference_img, &artwork, opacity.unwrap_or(0.5), COMPARISON_BACKGROUND),
+        ComparisonMode::Difference => difference(&reference_img, &artwork, COMPARISON_BACKGROUND),
+    };
+
+    let (width, height) = rendered.dimensions();
+    let jpeg = encode_image(&DynamicImage::ImageRgba8(rendered), ImageOutputFormat::Jpeg(90))?;
+
+    let comparison = if save.unwrap_or(false) {
+        Some(save_comparison(&state, &entry, &reference.id, mode, &jpeg)?)
+    } else {
+        None
+    };
+
+    Ok(ComparisonResult {
+        data_url: to_data_url(&jpeg, "image/jpeg"),
+        width,
+        height,
+        comparison,
+    })
+}
+
+/// Estimate the similarity transform that best aligns a journal artwork to its linked
+/// (or a given) reference and report where the drawing's proportions drift: a residual
+/// heatmap over the reference, global aspect error and per-region (thirds) offsets
+/// and scale errors.
+#[tauri::command]
+pub async fn analyze_alignment(
+    state: State<'_, AppState>,
+    photo_id: String,
+    reference_id: Option<String>,
+    max_size: Option<u32>,
+) -> Result<AlignmentResult, String> {
+    let (_, artwork, _, reference_img) = load_comparison_pair(&state, &photo_id, reference_id.as_deref())?;
+
+    let analysis = analyze_drawing_alignment(&artwork, &reference_img)?;
+    let base = fit_within(reference_img, Some(max_size.unwrap_or(DEFAULT_HEATMAP_SIZE)));
+    let heatmap = render_alignment_heatmap(&base, &analysis);
+    let (width, height) = heatmap.dimensions();
+    let png = encode_image(&DynamicImage::ImageRgba8(heatmap), ImageOutputFormat::Png)?;
+
+    let percent = |v: f64| (v * 1000.0).round() / 10.0;
+    let t = analysis.similarity;
+    Ok(AlignmentResult {
+        heatmap_data_url: to_data_url(&png, "image/png"),
+        width,
+        height,
+        scale: (t.scale * 1000.0).round() / 1000.0,
+        rotation_degrees: (t.rotation.to_degrees() * 10.0).round() / 10.0,
+        translate_x_percent: percent(t.tx),
+        translate_y_percent: percent(t.ty),
+        aspect_error_percent: percent(analysis.aspect_error),
+        match_score: (analysis.match_score * 1000.0).round() / 1000.0,
+        mean_residual_percent: percent(analysis.mean_residual),
+        regions: analysis
+            .regions
+            .iter()
+            .map(|r| RegionProportionError {
+                region: r.name.to_string(),
+                edge_points: r.points,
+                offset_x_percent: r.offset_x.map(percent),
+                offset_y_percent: r.offset_y.map(percent),
+                scale_error_percent: r.scale_error.map(percent),
+                mean_residual_percent: percent(r.mean_residual),
+            })
+            .collect(),
+    })
+}
+
+/// Luminance histogram, value range and five-step value distribution of a journal
+/// artwork and its linked (or a given) reference, plus a rendered side-by-side chart,
+/// e.g. to show that a drawing is compressed into the midtones.
+#[tauri::command]
+pub async fn compare_values(
+    state: State<'_, AppState>,
+    photo_id: String,
+    reference_id: Option<String>,
+) -> Result<ValueComparisonResult, String> {
+    let (_, artwork, _, reference_img) = load_comparison_pair(&state, &photo_id, reference_id.as_deref())?;
+
+    let artwork_stats = value_stats(&fit_within(artwork, Some(VALUE_ANALYSIS_SIZE)));
+    let reference_stats = value_stats(&fit_within(reference_img, Some(VALUE_ANALYSIS_SIZE)));
+
+    let chart = render_value_chart(&reference_stats, &artwork_stats);
+    let png = encode_image(&DynamicImage::ImageRgba8(chart), ImageOutputFormat::Png)?;
+
+    Ok(ValueComparisonResult {
+        artwork: artwork_stats.into(),
+        reference: reference_stats.into(),
+        chart_data_url: to_data_url(&png, "image/png"),
+    })
+}
+
+/// Remove a saved comparison from a journal entry and delete its file.
+#[tauri::command]
+pub async fn delete_journal_comparison(
+    state: State<'_, AppState>,
+    photo_id: String,
+    comparison_id: String,
+) -> Result<PhotoJournalImage, String> {
+    let mut images = read_photo_journal(&state)?;
+    let idx = images.iter().position(|img| img.id == photo_id)
+        .ok_or_else(|| "Image not found".to_string())?;
+    let pos = images[idx].comparisons.iter().position(|c| c.id == comparison_id)
+        .ok_or_else(|| "Comparison not found".to_string())?;
+    let removed = images[idx].comparisons.remove(pos);
+    let _ = fs::remove_file(resolve_image_path(&state, &removed.url));
+
+    let updated = images[idx].clone();
+    write_photo_journal(&state, &images)?;
+
+    Ok(updated)
+}
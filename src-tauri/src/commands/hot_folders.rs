@@ -0,0 +1,652 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+use chrono::Utc;
+use image::ImageOutputFormat;
+use once_cell::sync::Lazy;
+
+use crate::imaging::encode_image;
+use crate::models::{Folder, HotFolder, HotFolderPolicy, HotFolderTarget};
+use crate::state::AppState;
+use crate::commands::journal_metadata::clean_list;
+use crate::commands::moodboard_upload::store_moodboard_image;
+use crate::commands::moodboards::{modify_moodboard, read_moodboards};
+use crate::commands::photo_journal::{read_photo_journal, store_photo_journal_image, write_photo_journal};
+use crate::commands::references::crud::{store_reference, update_reference};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Scanners and drawing apps often write a file in several passes; it is imported
+/// once its size and modification time have stayed the same for this long.
+const STABLE_FOR: Duration = Duration::from_secs(3);
+/// Gap between the two size checks of a manual pass
+const MANUAL_CHECK_DELAY: Duration = Duration::from_millis(1000);
+const IMPORTED_DIR: &str = "Imported";
+const IMAGE_EXTENSIONS: [&str; 5] = ["jpg", "jpeg", "png", "webp", "gif"];
+/// Common scanner formats, converted to PNG on import
+const CONVERTED_EXTENSIONS: [&str; 3] = ["bmp", "tif", "tiff"];
+
+static WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+/// Keeps the watcher and manual imports from importing the same file twice.
+static PASS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotFolderUpdate {
+    pub target: Option<HotFolderTarget>,
+    pub tags: Option<Vec<String>>,
+    pub after_import: Option<HotFolderPolicy>,
+    /// Empty string goes back to the `Imported` subfolder
+    pub move_to: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotFolderImport {
+    pub folder_id: String,
+    /// Name of the file in the hot folder
+    pub file: String,
+    pub target: HotFolderTarget,
+    /// Journal entry, reference or moodboard item created
+    pub item_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotFolderFailure {
+    pub file: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotFolderPass {
+    pub imported: Vec<HotFolderImport>,
+    pub failed: Vec<HotFolderFailure>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileSnapshot {
+    size: u64,
+    modified: SystemTime,
+}
+
+fn hot_folders_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("app_data").join("hot_folders.json")
+}
+
+/// Files left in place stay listed here (per folder) so they are imported once.
+fn ledger_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("app_data").join("hot_folder_ledger.json")
+}
+
+fn read_hot_folders(state: &AppState) -> Result<Vec<HotFolder>, String> {
+    let path = hot_folders_path(state);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read hot folders: {}", e))?;
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse hot folders: {}", e))
+}
+
+fn write_hot_folders(state: &AppState, folders: &[HotFolder]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(folders)
+        .map_err(|e| format!("Failed to serialize hot folders: {}", e))?;
+    fs::write(hot_folders_path(state), content)
+        .map_err(|e| format!("Failed to write hot folders: {}", e))
+}
+
+fn read_ledger(state: &AppState) -> HashMap<String, Vec<String>> {
+    fs::read_to_string(ledger_path(state))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn write_ledger(state: &AppState, ledger: &HashMap<String, Vec<String>>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(ledger)
+        .map_err(|e| format!("Failed to serialize hot folder ledger: {}", e))?;
+    fs::write(ledger_path(state), content)
+        .map_err(|e| format!("Failed to write hot folder ledger: {}", e))
+}
+
+fn find_hot_folder(folders: &[HotFolder], id: &str) -> Result<usize, String> {
+    folders
+        .iter()
+        .position(|f| f.id == id)
+        .ok_or_else(|| "Hot folder not found".to_string())
+}
+
+fn validate_target(state: &AppState, target: &HotFolderTarget) -> Result<(), String> {
+    match target {
+        HotFolderTarget::Journal | HotFolderTarget::References => Ok(()),
+        HotFolderTarget::ReferenceFolder { folder_id } => {
+            let folders: Vec<Folder> = fs::read_to_string(state.data_dir.join("app_data").join("folders.json"))
+                .ok()
+                .and_then(|c| serde_json::from_str(&c).ok())
+                .unwrap_or_default();
+            if folders.iter().any(|f| f.id == *folder_id) {
+                Ok(())
+            } else {
+                Err(format!("Folder with id {} not found", folder_id))
+            }
+        }
+        HotFolderTarget::Moodboard { moodboard_id } => {
+            if read_moodboards(state)?.iter().any(|m| m.id == *moodboard_id) {
+                Ok(())
+            } else {
+                Err("Moodboard not found".to_string())
+            }
+        }
+    }
+}
+
+/// Whether `dir` (canonical) is inside the app's library or data, or with `containing`,
+/// holds one of them.
+fn touches_app_dirs(state: &AppState, dir: &Path, containing: bool) -> bool {
+    [&state.library_dir, &state.data_dir].into_iter().any(|own| {
+        own.canonicalize()
+            .is_ok_and(|own| dir.starts_with(&own) || (containing && own.starts_with(dir)))
+    })
+}
+
+/// Canonical watched directory; the app's own library and cache can't be watched.
+fn validate_watch_path(state: &AppState, path: &str) -> Result<PathBuf, String> {
+    let dir = PathBuf::from(path.trim());
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+    let dir = dir.canonicalize()
+        .map_err(|e| format!("Failed to resolve hot folder path: {}", e))?;
+    if touches_app_dirs(state, &dir, true) {
+        return Err("Hot folders can't be inside (or contain) the app's library or data".to_string());
+    }
+    Ok(dir)
+}
+
+/// Canonical `moveTo` folder for `watched`: an absolute path outside the app's library
+/// and data, and not the hot folder itself. It may not exist yet; then the deepest
+/// existing parent is resolved and the rest appended.
+fn validate_move_to(state: &AppState, move_to: &str, watched: &Path) -> Result<PathBuf, String> {
+    let path = Path::new(move_to);
+    if !path.is_absolute() {
+        return Err(format!("Move-to folder must be an absolute path: {}", move_to));
+    }
+    let mut existing = path;
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        rest.push(existing.file_name().ok_or_else(|| format!("Invalid move-to folder: {}", move_to))?);
+        existing = existing.parent().ok_or_else(|| format!("Invalid move-to folder: {}", move_to))?;
+    }
+    let mut dir = existing.canonicalize()
+        .map_err(|e| format!("Failed to resolve move-to folder: {}", e))?;
+    dir.extend(rest.into_iter().rev());
+    if dir.exists() && !dir.is_dir() {
+        return Err(format!("Not a directory: {}", move_to));
+    }
+    let watched = watched.canonicalize().unwrap_or_else(|_| watched.to_path_buf());
+    if dir == watched {
+        return Err("Files can't be moved into the hot folder itself".to_string());
+    }
+    if touches_app_dirs(state, &dir, false) {
+        return Err("Files can't be moved into the app's library or data".to_string());
+    }
+    Ok(dir)
+}
+
+fn snapshot(path: &Path) -> Option<FileSnapshot> {
+    let meta = fs::metadata(path).ok()?;
+    Some(FileSnapshot { size: meta.len(), modified: meta.modified().ok()? })
+}
+
+fn ledger_key(name: &str, snap: &FileSnapshot) -> String {
+    let modified = snap.modified.duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    format!("{}|{}|{}", name, snap.size, modified)
+}
+
+fn extension_of(path: &Path) -> Option<String> {
+    path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase())
+}
+
+/// Top-level image files of a hot folder, skipping hidden and temporary files.
+fn list_candidates(dir: &Path) -> Result<Vec<(PathBuf, FileSnapshot)>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read hot folder {:?}: {}", dir, e))?;
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name.starts_with('~') || !path.is_file() {
+            continue;
+        }
+        let supported = extension_of(&path)
+            .map(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()) || CONVERTED_EXTENSIONS.contains(&ext.as_str()))
+            .unwrap_or(false);
+        if !supported {
+            continue;
+        }
+        if let Some(snap) = snapshot(&path) {
+            files.push((path, snap));
+        }
+    }
+    files.sort_by_key(|a| a.1.modified);
+    Ok(files)
+}
+
+fn sanitize_stem(stem: &str) -> String {
+    let cleaned: String = stem
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let cleaned = cleaned.trim_matches('-').to_string();
+    if cleaned.is_empty() { "import".to_string() } else { cleaned }
+}
+
+/// Import one file into the folder's target and tell the frontend. Returns the id of
+/// the created entry, reference or moodboard item.
+fn import_file(app: &AppHandle, state: &AppState, folder: &HotFolder, path: &Path) -> Result<String, String> {
+    let original_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("import").to_string();
+    let stem = sanitize_stem(path.file_stem().and_then(|s| s.to_str()).unwrap_or("import"));
+    let mut ext = extension_of(path).unwrap_or_else(|| "jpg".to_string());
+    let mut data = fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {}", original_name, e))?;
+    if CONVERTED_EXTENSIONS.contains(&ext.as_str()) {
+        let img = image::load_from_memory(&data)
+            .map_err(|e| format!("Failed to decode {}: {}", original_name, e))?;
+        data = encode_image(&img, ImageOutputFormat::Png)?;
+        ext = "png".to_string();
+    }
+    let filename = format!("{}-{}.{}", stem, Utc::now().timestamp_millis(), ext);
+
+    match &folder.target {
+        HotFolderTarget::Journal => {
+            // Scanner output is already flat, as with scan_to_journal
+            let mut image = store_photo_journal_image(state, filename, original_name, &data, None, Some(false))?;
+            if !folder.tags.is_empty() {
+                let mut images = read_photo_journal(state)?;
+                if let Some(entry) = images.iter_mut().find(|img| img.id == image.id) {
+                    entry.tags = folder.tags.clone();
+                }
+                write_photo_journal(state, &images)?;
+                image.tags = folder.tags.clone();
+            }
+            let _ = app.emit_all("photo_journal_updated", &image);
+            Ok(image.id)
+        }
+        HotFolderTarget::References | HotFolderTarget::ReferenceFolder { .. } => {
+            let folder_id = match &folder.target {
+                HotFolderTarget::ReferenceFolder { folder_id } => Some(folder_id.clone()),
+                _ => None,
+            };
+            let mut reference = store_reference(state, filename, original_name, &data, folder_id, None)?;
+            if !folder.tags.is_empty() {
+                reference = update_reference(state, &reference.id, |r| r.tags = folder.tags.clone())?;
+            }
+            let _ = app.emit_all("references_updated", &reference);
+            Ok(reference.id)
+        }
+        HotFolderTarget::Moodboard { moodboard_id } => {
+            if !read_moodboards(state)?.iter().any(|m| m.id == *moodboard_id) {
+                return Err("Moodboard not found".to_string());
+            }
+            // Sized, analysed and placed clear of earlier imports
+            let item = store_moodboard_image(state, moodboard_id, filename, original_name, &data, &[])?;
+            let item_id = item.id.clone();
+            let updated = modify_moodboard(state, moodboard_id, |board| {
+                board.items.push(item);
+                Ok(())
+            })?;
+            let _ = app.emit_all("moodboard_updated", &updated);
+            Ok(item_id)
+        }
+    }
+}
+
+/// A name in `dir` that is not taken yet ("scan.png", "scan (1).png", ...).
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    let ext = path.extension().and_then(|e| e.to_str()).map(|e| format!(".{}", e)).unwrap_or_default();
+    (1..)
+        .map(|i| dir.join(format!("{} ({}){}", stem, i, ext)))
+        .find(|p| !p.exists())
+        .unwrap_or(candidate)
+}
+
+/// Apply the folder's after-import policy. Returns whether the file is gone from the
+/// hot folder.
+fn finish_file(folder: &HotFolder, path: &Path) -> Result<bool, String> {
+    match folder.after_import {
+        HotFolderPolicy::Leave => Ok(false),
+        HotFolderPolicy::Delete => fs::remove_file(path)
+            .map(|_| true)
+            .map_err(|e| format!("Imported, but failed to delete the file: {}", e)),
+        HotFolderPolicy::Move => {
+            let dir = match folder.move_to.as_deref() {
+                // Checked when set; a relative path from an older version isn't used
+                Some(dir) if Path::new(dir).is_absolute() => PathBuf::from(dir),
+                Some(dir) => return Err(format!("Imported, but the move-to folder is not an absolute path: {}", dir)),
+                None => Path::new(&folder.path).join(IMPORTED_DIR),
+            };
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Imported, but failed to create {:?}: {}", dir, e))?;
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("import");
+            let dest = unique_path(&dir, name);
+            // Rename fails across drives; fall back to copy + delete
+            if fs::rename(path, &dest).is_err() {
+                fs::copy(path, &dest)
+                    .map_err(|e| format!("Imported, but failed to move the file: {}", e))?;
+                fs::remove_file(path)
+                    .map_err(|e| format!("Imported, but failed to remove the moved file: {}", e))?;
+            }
+            Ok(true)
+        }
+    }
+}
+
+/// One import pass over a hot folder. `ready` decides which files are complete.
+/// Imported files that stay in place are remembered in the ledger; a file whose
+/// policy step fails is remembered too, so it is not imported again.
+fn run_pass(
+    app: &AppHandle,
+    state: &AppState,
+    folder_id: &str,
+    ready: &mut dyn FnMut(&Path, &FileSnapshot) -> bool,
+) -> Result<(HotFolderPass, Vec<(PathBuf, FileSnapshot)>), String> {
+    let _guard = PASS_LOCK.lock().map_err(|_| "Hot folder lock poisoned".to_string())?;
+    let folders = read_hot_folders(state)?;
+    let folder = folders[find_hot_folder(&folders, folder_id)?].clone();
+
+    let mut ledger = read_ledger(state);
+    let mut known = ledger.get(folder_id).cloned().unwrap_or_default();
+    let candidates = list_candidates(Path::new(&folder.path))?;
+
+    let mut pass = HotFolderPass::default();
+    let mut failed_files = Vec::new();
+    for (path, snap) in &candidates {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        let key = ledger_key(&name, snap);
+        if known.contains(&key) || !ready(path, snap) {
+            continue;
+        }
+        match import_file(app, state, &folder, path) {
+            Ok(item_id) => {
+                let gone = match finish_file(&folder, path) {
+                    Ok(gone) => gone,
+                    Err(error) => {
+                        pass.failed.push(HotFolderFailure { file: name.clone(), error });
+                        false
+                    }
+                };
+                if !gone {
+                    known.push(key);
+                }
+                pass.imported.push(HotFolderImport {
+                    folder_id: folder.id.clone(),
+                    file: name,
+                    target: folder.target.clone(),
+                    item_id,
+                });
+            }
+            Err(error) => {
+                failed_files.push((path.clone(), *snap));
+                pass.failed.push(HotFolderFailure { file: name, error });
+            }
+        }
+    }
+
+    // Forget files that are no longer there
+    let present: Vec<String> = candidates
+        .iter()
+        .map(|(path, snap)| ledger_key(path.file_name().and_then(|n| n.to_str()).unwrap_or_default(), snap))
+        .collect();
+    known.retain(|key| present.contains(key));
+    if ledger.get(folder_id) != Some(&known) {
+        ledger.insert(folder_id.to_string(), known);
+        write_ledger(state, &ledger)?;
+    }
+
+    if !pass.imported.is_empty() || !pass.failed.is_empty() {
+        let mut folders = read_hot_folders(state)?;
+        if let Ok(idx) = find_hot_folder(&folders, folder_id) {
+            let entry = &mut folders[idx];
+            entry.imported_count += pass.imported.len() as u32;
+            if !pass.imported.is_empty() {
+                entry.last_import_at = Some(Utc::now().timestamp_millis());
+            }
+            entry.last_error = pass.failed.last().map(|f| format!("{}: {}", f.file, f.error));
+            let updated = entry.clone();
+            write_hot_folders(state, &folders)?;
+            let _ = app.emit_all("hot_folder_updated", &updated);
+        }
+        for import in &pass.imported {
+            let _ = app.emit_all("hot_folder_imported", import);
+        }
+    }
+    Ok((pass, failed_files))
+}
+
+/// Change a folder's `lastError` with `f` (which returns whether it changed) and
+/// tell the UI.
+fn update_last_error(
+    app: &AppHandle,
+    state: &AppState,
+    folder_id: &str,
+    f: impl FnOnce(&mut Option<String>) -> bool,
+) -> Result<(), String> {
+    let mut folders = read_hot_folders(state)?;
+    let idx = find_hot_folder(&folders, folder_id)?;
+    if !f(&mut folders[idx].last_error) {
+        return Ok(());
+    }
+    let updated = folders[idx].clone();
+    write_hot_folders(state, &folders)?;
+    let _ = app.emit_all("hot_folder_updated", &updated);
+    Ok(())
+}
+
+fn is_settled(snap: &FileSnapshot) -> bool {
+    SystemTime::now()
+        .duration_since(snap.modified)
+        .map(|age| age >= STABLE_FOR)
+        .unwrap_or(false)
+}
+
+/// Poll enabled hot folders in the background. A file is imported once two polls see
+/// the same size and modification time and it hasn't been modified for `STABLE_FOR`.
+/// Files that fail to import are retried only after they change. A folder that can't
+/// be read is reported once, on its `lastError`, until it can be read again.
+pub fn start_hot_folder_watcher(app: AppHandle) {
+    if WATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    thread::spawn(move || {
+        let mut seen: HashMap<PathBuf, FileSnapshot> = HashMap::new();
+        let mut failed: HashMap<PathBuf, FileSnapshot> = HashMap::new();
+        // Folder id -> error of its last pass, while it keeps failing
+        let mut broken: HashMap<String, String> = HashMap::new();
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let state = app.state::<AppState>();
+            let folders = match read_hot_folders(&state) {
+                Ok(folders) => folders,
+                Err(e) => {
+                    eprintln!("Hot folders unavailable: {}", e);
+                    continue;
+                }
+            };
+
+            for folder in folders.iter().filter(|f| f.enabled) {
+                let mut ready = |path: &Path, snap: &FileSnapshot| {
+                    let previous = seen.insert(path.to_path_buf(), *snap);
+                    previous == Some(*snap) && is_settled(snap) && failed.get(path) != Some(snap)
+                };
+                match run_pass(&app, &state, &folder.id, &mut ready) {
+                    Ok((_, failed_files)) => {
+                        failed.extend(failed_files);
+                        // Readable again: drop the error if it is still the one shown
+                        if let Some(error) = broken.remove(&folder.id) {
+                            let _ = update_last_error(&app, &state, &folder.id, |last| {
+                                last.as_ref() == Some(&error) && last.take().is_some()
+                            });
+                        }
+                    }
+                    Err(e) if broken.get(&folder.id) != Some(&e) => {
+                        eprintln!("Hot folder {} skipped: {}", folder.path, e);
+                        let shown = e.clone();
+                        if let Err(err) = update_last_error(&app, &state, &folder.id, |last| last.replace(shown.clone()) != Some(shown)) {
+                            eprintln!("Failed recording hot folder error: {}", err);
+                        }
+                        broken.insert(folder.id.clone(), e);
+                    }
+                    Err(_) => {}
+                }
+            }
+            seen.retain(|path, _| path.exists());
+            failed.retain(|path, _| path.exists());
+            broken.retain(|id, _| folders.iter().any(|f| f.id == *id && f.enabled));
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_hot_folders(state: State<'_, AppState>) -> Result<Vec<HotFolder>, String> {
+    read_hot_folders(&state)
+}
+
+/// Watch `path` and import new images into `target`. `afterImport` defaults to moving
+/// them into an `Imported` subfolder (or `moveTo`).
+#[tauri::command]
+pub async fn add_hot_folder(
+    state: State<'_, AppState>,
+    path: String,
+    target: HotFolderTarget,
+    tags: Option<Vec<String>>,
+    after_import: Option<HotFolderPolicy>,
+    move_to: Option<String>,
+) -> Result<HotFolder, String> {
+    let dir = validate_watch_path(&state, &path)?;
+    validate_target(&state, &target)?;
+    let move_to = match move_to.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        Some(m) => Some(validate_move_to(&state, m, &dir)?.to_string_lossy().to_string()),
+        None => None,
+    };
+
+    let mut folders = read_hot_folders(&state)?;
+    let path = dir.to_string_lossy().to_string();
+    if folders.iter().any(|f| f.path == path) {
+        return Err("This folder is already a hot folder".to_string());
+    }
+    let folder = HotFolder {
+        id: Uuid::new_v4().to_string(),
+        path,
+        target,
+        tags: clean_list(tags.unwrap_or_default(), false),
+        after_import: after_import.unwrap_or_default(),
+        move_to,
+        enabled: true,
+        created_at: Utc::now().timestamp_millis(),
+        imported_count: 0,
+        last_import_at: None,
+        last_error: None,
+    };
+    folders.push(folder.clone());
+    write_hot_folders(&state, &folders)?;
+    Ok(folder)
+}
+
+#[tauri::command]
+pub async fn update_hot_folder(
+    state: State<'_, AppState>,
+    id: String,
+    update: HotFolderUpdate,
+) -> Result<HotFolder, String> {
+    if let Some(target) = update.target.as_ref() {
+        validate_target(&state, target)?;
+    }
+    let mut folders = read_hot_folders(&state)?;
+    let idx = find_hot_folder(&folders, &id)?;
+    let folder = &mut folders[idx];
+
+    if let Some(target) = update.target {
+        folder.target = target;
+    }
+    if let Some(tags) = update.tags {
+        folder.tags = clean_list(tags, false);
+    }
+    if let Some(policy) = update.after_import {
+        folder.after_import = policy;
+    }
+    if let Some(move_to) = update.move_to {
+        folder.move_to = match move_to.trim() {
+            "" => None,
+            m => Some(validate_move_to(&state, m, Path::new(&folder.path))?.to_string_lossy().to_string()),
+        };
+    }
+    if let Some(enabled) = update.enabled {
+        folder.enabled = enabled;
+        if enabled {
+            folder.last_error = None;
+        }
+    }
+    let updated = folder.clone();
+
+    write_hot_folders(&state, &folders)?;
+    Ok(updated)
+}
+
+/// Stop watching a folder. Files in it are left alone.
+#[tauri::command]
+pub async fn remove_hot_folder(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let mut folders = read_hot_folders(&state)?;
+    let idx = find_hot_folder(&folders, &id)?;
+    folders.remove(idx);
+    write_hot_folders(&state, &folders)?;
+
+    let mut ledger = read_ledger(&state);
+    if ledger.remove(&id).is_some() {
+        write_ledger(&state, &ledger)?;
+    }
+    Ok(())
+}
+
+/// Import a hot folder's pending files now (also when disabled). Files still being
+/// written (size or time changing across a short check) are left for later.
+#[tauri::command]
+pub async fn import_hot_folder_now(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<HotFolderPass, String> {
+    let folders = read_hot_folders(&state)?;
+    let folder = &folders[find_hot_folder(&folders, &id)?];
+    let before: HashMap<PathBuf, FileSnapshot> = list_candidates(Path::new(&folder.path))?
+        .into_iter()
+        .collect();
+    tokio::time::sleep(MANUAL_CHECK_DELAY).await;
+
+    // Decoding and copying happen on a blocking thread, like the background poller
+    tokio::task::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let mut ready = |path: &Path, snap: &FileSnapshot| before.get(path) == Some(snap);
+        run_pass(&app, &state, &id, &mut ready).map(|(pass, _)| pass)
+    })
+    .await
+    .map_err(|e| format!("Hot folder import failed: {}", e))?
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::models::PhotoJournalImage;
+use crate::state::AppState;
+use crate::commands::journal_stats::{bump, sorted_counts, NamedCount};
+use crate::commands::photo_journal::{read_photo_journal, write_photo_journal};
+use crate::commands::references::ratings::bump_study_counters;
+
+/// Editable details of a journal entry. Saved as a whole, so clearing a field means
+/// sending it empty.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalMetadata {
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub medium: Option<String>,
+    pub paper_size: Option<String>,
+    pub time_spent_minutes: Option<u32>,
+    /// 1-5
+    pub self_rating: Option<u8>,
+    pub notes: Option<String>,
+}
+
+/// Journal filter; every set field must match. Text matches are case-insensitive.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalFilter {
+    /// Substring of the prompt, notes, medium or original file name
+    pub query: Option<String>,
+    /// Entries must carry all of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub medium: Option<String>,
+    pub paper_size: Option<String>,
+    pub reference_id: Option<String>,
+    pub master_study_path: Option<String>,
+    /// true = linked to at least one reference or master study, false = linked to none
+    pub linked: Option<bool>,
+    pub min_rating: Option<u8>,
+    pub max_rating: Option<u8>,
+    pub min_minutes: Option<u32>,
+    pub max_minutes: Option<u32>,
+    /// Local dates, YYYY-MM-DD, inclusive
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalFacets {
+    pub tags: Vec<NamedCount>,
+    pub mediums: Vec<NamedCount>,
+    pub paper_sizes: Vec<NamedCount>,
+}
+
+fn clean_text(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Trimmed, non-empty, without case-insensitive duplicates (first spelling wins).
+pub(crate) fn clean_list(values: Vec<String>, case_sensitive: bool) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for value in values {
+        let value = value.trim().to_string();
+        let duplicate = cleaned.iter().any(|v| {
+            if case_sensitive { *v == value } else { v.eq_ignore_ascii_case(&value) }
+        });
+        if !value.is_empty() && !duplicate {
+            cleaned.push(value);
+        }
+    }
+    cleaned
+}
+
+fn same_text(a: &Option<String>, b: &str) -> bool {
+    a.as_deref().is_some_and(|a| a.trim().eq_ignore_ascii_case(b.trim()))
+}
+
+fn parse_date(value: &Option<String>, field: &str) -> Result<Option<NaiveDate>, String> {
+    match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|e| format!("Invalid {} '{}': {}", field, v, e)),
+        None => Ok(None),
+    }
+}
+
+fn matches_filter(
+    image: &PhotoJournalImage,
+    filter: &JournalFilter,
+    query: &Option<String>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> bool {
+    if let Some(query) = query {
+        let found = [&image.prompt, &image.notes, &image.medium]
+            .iter()
+            .filter_map(|field| field.as_deref())
+            .chain(std::iter::once(image.original_name.as_str()))
+            .any(|text| text.to_lowercase().contains(query));
+        if !found {
+            return false;
+        }
+    }
+    if !filter.tags.iter().all(|tag| image.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))) {
+        return false;
+    }
+    if let Some(medium) = filter.medium.as_deref() {
+        if !same_text(&image.medium, medium) {
+            return false;
+        }
+    }
+    if let Some(paper_size) = filter.paper_size.as_deref() {
+        if !same_text(&image.paper_size, paper_size) {
+            return false;
+        }
+    }
+    if let Some(reference_id) = filter.reference_id.as_ref() {
+        if !image.reference_ids.contains(reference_id) {
+            return false;
+        }
+    }
+    if let Some(path) = filter.master_study_path.as_ref() {
+        if !image.master_study_paths.contains(path) {
+            return false;
+        }
+    }
+    if let Some(linked) = filter.linked {
+        let has_links = !image.reference_ids.is_empty() || !image.master_study_paths.is_empty();
+        if has_links != linked {
+            return false;
+        }
+    }
+
+    // Range filters exclude entries that don't have the value at all
+    if filter.min_rating.is_some() || filter.max_rating.is_some() {
+        match image.self_rating {
+            Some(r) if filter.min_rating.is_none_or(|m| r >= m) && filter.max_rating.is_none_or(|m| r <= m) => {}
+            _ => return false,
+        }
+    }
+    if filter.min_minutes.is_some() || filter.max_minutes.is_some() {
+        match image.time_spent_minutes {
+            Some(t) if filter.min_minutes.is_none_or(|m| t >= m) && filter.max_minutes.is_none_or(|m| t <= m) => {}
+            _ => return false,
+        }
+    }
+    if from.is_some() || to.is_some() {
+        let date = match DateTime::parse_from_rfc3339(&image.upload_date) {
+            Ok(dt) => dt.with_timezone(&Local).date_naive(),
+            Err(_) => return false,
+        };
+        if from.is_some_and(|f| date < f) || to.is_some_and(|t| date > t) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Replace the prompt, tags, medium, paper size, time spent, self-rating and notes
+/// of a journal entry.
+#[tauri::command]
+pub async fn set_photo_journal_metadata(
+    state: State<'_, AppState>,
+    id: String,
+    metadata: JournalMetadata,
+) -> Result<PhotoJournalImage, String> {
+    if let Some(rating) = metadata.self_rating {
+        if !(1..=5).contains(&rating) {
+            return Err("Self-rating must be between 1 and 5".to_string());
+        }
+    }
+
+    let mut images = read_photo_journal(&state)?;
+    let image = images.iter_mut().find(|img| img.id == id)
+        .ok_or_else(|| "Image not found".to_string())?;
+
+    image.prompt = clean_text(metadata.prompt);
+    image.tags = clean_list(metadata.tags, false);
+    image.medium = clean_text(metadata.medium);
+    image.paper_size = clean_text(metadata.paper_size);
+    image.time_spent_minutes = metadata.time_spent_minutes.filter(|&m| m > 0);
+    image.self_rating = metadata.self_rating;
+    image.notes = clean_text(metadata.notes);
+    let updated = image.clone();
+
+    write_photo_journal(&state, &images)?;
+    Ok(updated)
+}
+
+/// Replace the linked references (primary first) and master studies of a journal
+/// entry. Newly linked references count as one study each.
+#[tauri::command]
+pub async fn set_photo_journal_links(
+    state: State<'_, AppState>,
+    id: String,
+    reference_ids: Vec<String>,
+    master_study_paths: Vec<String>,
+) -> Result<PhotoJournalImage, String> {
+    let mut images = read_photo_journal(&state)?;
+    let image = images.iter_mut().find(|img| img.id == id)
+        .ok_or_else(|| "Image not found".to_string())?;
+
+    let previous = std::mem::take(&mut image.reference_ids);
+    image.reference_ids = clean_list(reference_ids, true);
+    image.reference_id = None;
+    image.normalize_links();
+    image.master_study_paths = clean_list(master_study_paths, true);
+
+    let added: Vec<String> = image
+        .reference_ids
+        .iter()
+        .filter(|id| !previous.contains(id))
+        .cloned()
+        .collect();
+    let updated = image.clone();
+
+    write_photo_journal(&state, &images)?;
+
+    for reference_id in &added {
+        bump_study_counters(&state.data_dir, reference_id)?;
+    }
+
+    Ok(updated)
+}
+
+/// Journal entries matching `filter`, newest first (the journal's own order).
+#[tauri::command]
+pub async fn filter_photo_journal(
+    state: State<'_, AppState>,
+    filter: JournalFilter,
+) -> Result<Vec<PhotoJournalImage>, String> {
+    let from = parse_date(&filter.from_date, "from date")?;
+    let to = parse_date(&filter.to_date, "to date")?;
+    let query = clean_text(filter.query.clone()).map(|q| q.to_lowercase());
+
+    Ok(read_photo_journal(&state)?
+        .into_iter()
+        .filter(|image| matches_filter(image, &filter, &query, from, to))
+        .collect())
+}
+
+/// Tags, mediums and paper sizes in use, with entry counts, for filter pickers.
+#[tauri::command]
+pub async fn get_photo_journal_facets(state: State<'_, AppState>) -> Result<JournalFacets, String> {
+    let mut tags: HashMap<String, (String, u32)> = HashMap::new();
+    let mut mediums: HashMap<String, (String, u32)> = HashMap::new();
+    let mut paper_sizes: HashMap<String, (String, u32)> = HashMap::new();
+
+    for image in read_photo_journal(&state)? {
+        for tag in &image.tags {
+            bump(&mut tags, tag);
+        }
+        if let Some(medium) = image.medium.as_deref() {
+            bump(&mut mediums, medium);
+        }
+        if let Some(paper_size) = image.paper_size.as_deref() {
+            bump(&mut paper_sizes, paper_size);
+        }
+    }
+
+    Ok(JournalFacets {
+        tags: sorted_counts(tags),
+        mediums: sorted_counts(mediums),
+        paper_sizes: sorted_counts(paper_sizes),
+    })
+}
@@ -0,0 +1,325 @@
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+use uuid::Uuid;
+use chrono::Utc;
+
+use crate::models::{JournalPiece, PieceStage};
+use crate::state::AppState;
+use crate::commands::photo_journal::{read_photo_journal, write_photo_journal};
+
+fn pieces_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("app_data").join("journal_pieces.json")
+}
+
+/// All pieces; a missing file just means none have been created yet.
+pub(crate) fn read_journal_pieces(state: &AppState) -> Result<Vec<JournalPiece>, String> {
+    let path = pieces_path(state);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read journal pieces: {}", e))?;
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse journal pieces: {}", e))
+}
+
+fn write_journal_pieces(state: &AppState, pieces: &[JournalPiece]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(pieces)
+        .map_err(|e| format!("Failed to serialize journal pieces: {}", e))?;
+    fs::write(pieces_path(state), content)
+        .map_err(|e| format!("Failed to write journal pieces: {}", e))
+}
+
+fn find_piece(pieces: &[JournalPiece], id: &str) -> Result<usize, String> {
+    pieces
+        .iter()
+        .position(|p| p.id == id)
+        .ok_or_else(|| "Piece not found".to_string())
+}
+
+/// An entry is a stage of one piece at most: drop it from any piece holding it.
+fn detach_image(pieces: &mut [JournalPiece], image_id: &str, now: i64) {
+    for piece in pieces.iter_mut() {
+        let before = piece.stages.len();
+        piece.stages.retain(|s| s.image_id != image_id);
+        if piece.stages.len() != before {
+            if piece.cover_image_id.as_deref() == Some(image_id) {
+                piece.cover_image_id = None;
+            }
+            piece.updated_at = now;
+        }
+    }
+}
+
+fn clean_label(label: Option<String>) -> Option<String> {
+    label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty())
+}
+
+/// Point journal entries at `piece_id` (or clear it).
+pub(crate) fn set_entries_piece(state: &AppState, image_ids: &[String], piece_id: Option<&str>) -> Result<(), String> {
+    let mut images = read_photo_journal(state)?;
+    for image in images.iter_mut().filter(|img| image_ids.contains(&img.id)) {
+        image.piece_id = piece_id.map(str::to_string);
+    }
+    write_photo_journal(state, &images)
+}
+
+fn ensure_entries_exist(state: &AppState, image_ids: &[String]) -> Result<(), String> {
+    let images = read_photo_journal(state)?;
+    match image_ids.iter().find(|id| !images.iter().any(|img| img.id == **id)) {
+        Some(missing) => Err(format!("Journal entry not found: {}", missing)),
+        None => Ok(()),
+    }
+}
+
+/// Append stages to a piece without touching the journal file; used by the phone
+/// upload server, which writes `pieceId` on the new entries itself.
+pub(crate) fn append_piece_stages(
+    state: &AppState,
+    piece_id: &str,
+    image_ids: &[String],
+    label: Option<String>,
+) -> Result<JournalPiece, String> {
+    let mut pieces = read_journal_pieces(state)?;
+    let idx = find_piece(&pieces, piece_id)?;
+    let now = Utc::now().timestamp_millis();
+    let label = clean_label(label);
+
+    for image_id in image_ids {
+        detach_image(&mut pieces, image_id, now);
+        pieces[idx].stages.push(PieceStage {
+            image_id: image_id.clone(),
+            label: label.clone(),
+            added_at: now,
+        });
+    }
+    pieces[idx].updated_at = now;
+    let updated = pieces[idx].clone();
+
+    write_journal_pieces(state, &pieces)?;
+    Ok(updated)
+}
+
+/// Drop a deleted journal entry from its piece.
+pub(crate) fn remove_image_from_pieces(state: &AppState, image_id: &str) -> Result<(), String> {
+    let mut pieces = read_journal_pieces(state)?;
+    if !pieces.iter().any(|p| p.stages.iter().any(|s| s.image_id == image_id)) {
+        return Ok(());
+    }
+    detach_image(&mut pieces, image_id, Utc::now().timestamp_millis());
+    write_journal_pieces(state, &pieces)
+}
+
+#[tauri::command]
+pub async fn get_journal_pieces(state: State<'_, AppState>) -> Result<Vec<JournalPiece>, String> {
+    read_journal_pieces(&state)
+}
+
+/// Create a piece, optionally from existing journal entries (in stage order).
+#[tauri::command]
+pub async fn create_journal_piece(
+    state: State<'_, AppState>,
+    title: String,
+    image_ids: Vec<String>,
+) -> Result<JournalPiece, String> {
+    let title = title.trim().to_string();
+    if title.is_empty() {
+        return Err("Piece title cannot be empty".to_string());
+    }
+    ensure_entries_exist(&state, &image_ids)?;
+
+    let now = Utc::now().timestamp_millis();
+    let mut pieces = read_journal_pieces(&state)?;
+    pieces.push(JournalPiece {
+        id: Uuid::new_v4().to_string(),
+        title,
+        stages: Vec::new(),
+        cover_image_id: None,
+        created_at: now,
+        updated_at: now,
+    });
+    let piece_id = pieces[pieces.len() - 1].id.clone();
+    write_journal_pieces(&state, &pieces)?;
+
+    let mut stage_ids: Vec<String> = Vec::new();
+    for id in image_ids {
+        if !stage_ids.contains(&id) {
+            stage_ids.push(id);
+        }
+    }
+    let piece = append_piece_stages(&state, &piece_id, &stage_ids, None)?;
+    set_entries_piece(&state, &stage_ids, Some(&piece_id))?;
+    Ok(piece)
+}
+
+#[tauri::command]
+pub async fn rename_journal_piece(
+    state: State<'_, AppState>,
+    id: String,
+    title: String,
+) -> Result<JournalPiece, String> {
+    let title = title.trim().to_string();
+    if title.is_empty() {
+        return Err("Piece title cannot be empty".to_string());
+    }
+    let mut pieces = read_journal_pieces(&state)?;
+    let idx = find_piece(&pieces, &id)?;
+    pieces[idx].title = title;
+    pieces[idx].updated_at = Utc::now().timestamp_millis();
+    let updated = pieces[idx].clone();
+
+    write_journal_pieces(&state, &pieces)?;
+    Ok(updated)
+}
+
+/// Delete a piece. Its stages stay in the journal as standalone entries.
+#[tauri::command]
+pub async fn delete_journal_piece(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    let mut pieces = read_journal_pieces(&state)?;
+    let idx = find_piece(&pieces, &id)?;
+    let piece = pieces.remove(idx);
+    write_journal_pieces(&state, &pieces)?;
+
+    let stage_ids: Vec<String> = piece.stages.into_iter().map(|s| s.image_id).collect();
+    set_entries_piece(&state, &stage_ids, None)
+}
+
+/// Add a journal entry as a stage, at `position` or at the end. An entry that is
+/// already a stage of another piece moves to this one.
+#[tauri::command]
+pub async fn add_journal_piece_stage(
+    state: State<'_, AppState>,
+    piece_id: String,
+    image_id: String,
+    label: Option<String>,
+    position: Option<usize>,
+) -> Result<JournalPiece, String> {
+    ensure_entries_exist(&state, std::slice::from_ref(&image_id))?;
+
+    let mut pieces = read_journal_pieces(&state)?;
+    let idx = find_piece(&pieces, &piece_id)?;
+    let now = Utc::now().timestamp_millis();
+    let previous_label = pieces[idx]
+        .stages
+        .iter()
+        .find(|s| s.image_id == image_id)
+        .and_then(|s| s.label.clone());
+
+    detach_image(&mut pieces, &image_id, now);
+    let piece = &mut pieces[idx];
+    let at = position.unwrap_or(piece.stages.len()).min(piece.stages.len());
+    piece.stages.insert(at, PieceStage {
+        image_id: image_id.clone(),
+        label: clean_label(label).or(previous_label),
+        added_at: now,
+    });
+    piece.updated_at = now;
+    let updated = piece.clone();
+
+    write_journal_pieces(&state, &pieces)?;
+    set_entries_piece(&state, std::slice::from_ref(&image_id), Some(&piece_id))?;
+    Ok(updated)
+}
+
+/// Take a stage out of a piece; the journal entry itself is kept.
+#[tauri::command]
+pub async fn remove_journal_piece_stage(
+    state: State<'_, AppState>,
+    piece_id: String,
+    image_id: String,
+) -> Result<JournalPiece, String> {
+    let mut pieces = read_journal_pieces(&state)?;
+    let idx = find_piece(&pieces, &piece_id)?;
+    if !pieces[idx].stages.iter().any(|s| s.image_id == image_id) {
+        return Err("Stage not found".to_string());
+    }
+    detach_image(&mut pieces[idx..=idx], &image_id, Utc::now().timestamp_millis());
+    let updated = pieces[idx].clone();
+
+    write_journal_pieces(&state, &pieces)?;
+    set_entries_piece(&state, std::slice::from_ref(&image_id), None)?;
+    Ok(updated)
+}
+
+/// Reorder stages; `image_ids` must list exactly the piece's current stages.
+#[tauri::command]
+pub async fn reorder_journal_piece_stages(
+    state: State<'_, AppState>,
+    piece_id: String,
+    image_ids: Vec<String>,
+) -> Result<JournalPiece, String> {
+    let mut pieces = read_journal_pieces(&state)?;
+    let idx = find_piece(&pieces, &piece_id)?;
+    let piece = &mut pieces[idx];
+
+    let mut current: Vec<&str> = piece.stages.iter().map(|s| s.image_id.as_str()).collect();
+    let mut requested: Vec<&str> = image_ids.iter().map(String::as_str).collect();
+    current.sort_unstable();
+    requested.sort_unstable();
+    if current != requested {
+        return Err("Stage order must list each stage of the piece exactly once".to_string());
+    }
+
+    let mut stages = std::mem::take(&mut piece.stages);
+    for id in &image_ids {
+        if let Some(pos) = stages.iter().position(|s| s.image_id == *id) {
+            piece.stages.push(stages.remove(pos));
+        }
+    }
+    piece.updated_at = Utc::now().timestamp_millis();
+    let updated = piece.clone();
+
+    write_journal_pieces(&state, &pieces)?;
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn set_journal_piece_stage_label(
+    state: State<'_, AppState>,
+    piece_id: String,
+    image_id: String,
+    label: Option<String>,
+) -> Result<JournalPiece, String> {
+    let mut pieces = read_journal_pieces(&state)?;
+    let idx = find_piece(&pieces, &piece_id)?;
+    let stage = pieces[idx]
+        .stages
+        .iter_mut()
+        .find(|s| s.image_id == image_id)
+        .ok_or_else(|| "Stage not found".to_string())?;
+    stage.label = clean_label(label);
+    pieces[idx].updated_at = Utc::now().timestamp_millis();
+    let updated = pieces[idx].clone();
+
+    write_journal_pieces(&state, &pieces)?;
+    Ok(updated)
+}
+
+/// Choose the cover stage; `None` falls back to the latest stage.
+#[tauri::command]
+pub async fn set_journal_piece_cover(
+    state: State<'_, AppState>,
+    piece_id: String,
+    image_id: Option<String>,
+) -> Result<JournalPiece, String> {
+    let mut pieces = read_journal_pieces(&state)?;
+    let idx = find_piece(&pieces, &piece_id)?;
+    if let Some(image_id) = image_id.as_ref() {
+        if !pieces[idx].stages.iter().any(|s| s.image_id == *image_id) {
+            return Err("Cover must be one of the piece's stages".to_string());
+        }
+    }
+    pieces[idx].cover_image_id = image_id;
+    pieces[idx].updated_at = Utc::now().timestamp_millis();
+    let updated = pieces[idx].clone();
+
+    write_journal_pieces(&state, &pieces)?;
+    Ok(updated)
+}
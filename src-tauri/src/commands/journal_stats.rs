@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use serde::Serialize;
+use tauri::State;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike};
+
+use crate::models::{JournalSettings, Reference};
+use crate::commands::photo_journal::read_photo_journal;
+use crate::state::AppState;
+
+/// Weeks shown in the calendar heatmap (a year, GitHub-style).
+const CALENDAR_WEEKS: i64 = 53;
+/// Window for the goal success rate.
+const GOAL_WINDOW_DAYS: i64 = 30;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayCount {
+    /// Local date, YYYY-MM-DD
+    pub date: String,
+    pub count: u32,
+    pub goal_met: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekCount {
+    /// Monday of the week, YYYY-MM-DD
+    pub week_start: String,
+    pub count: u32,
+    pub active_days: u32,
+    pub goal_days: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarDay {
+    pub date: String,
+    pub count: u32,
+    /// 0 = nothing, 1-4 = quartiles of the busiest day in the window
+    pub level: u8,
+    /// Monday = 0
+    pub weekday: u8,
+    pub week: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedCount {
+    pub name: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeOfDay {
+    /// Entries per local hour, 0-23
+    pub hours: Vec<u32>,
+    /// 05-12
+    pub morning: u32,
+    /// 12-17
+    pub afternoon: u32,
+    /// 17-22
+    pub evening: u32,
+    /// 22-05
+    pub night: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProgress {
+    pub daily_goal: u32,
+    pub today_count: u32,
+    pub met_today: bool,
+    pub remaining_today: u32,
+    /// Days in the last 30 (including today) that reached the goal
+    pub days_met_last_30: u32,
+    /// Share of those 30 days (0-100)
+    pub success_rate_last_30: f32,
+    pub current_goal_streak: u32,
+    pub longest_goal_streak: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalStats {
+    pub total_entries: u32,
+    pub active_days: u32,
+    pub first_entry_date: Option<String>,
+    pub last_entry_date: Option<String>,
+    /// Days that have entries, oldest first
+    pub daily: Vec<DayCount>,
+    /// Weeks that have entries, oldest first
+    pub weekly: Vec<WeekCount>,
+    /// Dense grid of the last 53 weeks ending today
+    pub calendar: Vec<CalendarDay>,
+    /// Consecutive days with at least one entry, ending today (or yesterday)
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub per_prompt: Vec<NamedCount>,
+    pub without_prompt: u32,
+    /// Entries per tag, from the entry itself and its linked references
+    pub per_tag: Vec<NamedCount>,
+    pub time_of_day: TimeOfDay,
+    pub goal: GoalProgress,
+}
+
+fn settings_path(state: &AppState) -> std::path::PathBuf {
+    state.data_dir.join("app_data").join("journal_settings.json")
+}
+
+pub(crate) fn read_journal_settings(state: &AppState) -> JournalSettings {
+    fs::read_to_string(settings_path(state))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn write_journal_settings(state: &AppState, settings: &JournalSettings) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize journal settings: {}", e))?;
+    fs::write(settings_path(state), content)
+        .map_err(|e| format!("Failed to write journal settings: {}", e))
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Longest and current (ending today, or yesterday so an unfinished day doesn't
+/// break it) runs of consecutive days satisfying `qualifies`.
+fn streaks(days: &BTreeMap<NaiveDate, u32>, today: NaiveDate, qualifies: impl Fn(u32) -> bool) -> (u32, u32) {
+    let mut longest = 0u32;
+    let mut run = 0u32;
+    let mut previous: Option<NaiveDate> = None;
+    for (&date, &count) in days {
+        if !qualifies(count) {
+            run = 0;
+            previous = None;
+            continue;
+        }
+        run = match previous {
+            Some(p) if date - p == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(date);
+    }
+
+    let mut current = 0u32;
+    let mut day = if days.get(&today).is_some_and(|&c| qualifies(c)) { today } else { today - Duration::days(1) };
+    while days.get(&day).is_some_and(|&c| qualifies(c)) {
+        current += 1;
+        day -= Duration::days(1);
+    }
+    (current, longest)
+}
+
+pub(crate) fn sorted_counts(counts: HashMap<String, (String, u32)>) -> Vec<NamedCount> {
+    let mut list: Vec<NamedCount> = counts
+        .into_values()
+        .map(|(name, count)| NamedCount { name, count })
+        .collect();
+    list.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    list
+}
+
+/// Case-insensitive count that keeps the first spelling seen.
+pub(crate) fn bump(counts: &mut HashMap<String, (String, u32)>, name: &str) {
+    counts
+        .entry(name.to_lowercase())
+        .or_insert_with(|| (name.to_string(), 0))
+        .1 += 1;
+}
+
+/// Aggregate the artwork journal by local date: daily/weekly counts, streaks, a
+/// calendar heatmap, per-prompt and per-tag totals, time of day and progress against
+/// the daily goal (`daily_goal` overrides the saved one).
+#[tauri::command]
+pub async fn journal_stats(
+    state: State<'_, AppState>,
+    daily_goal: Option<u32>,
+) -> Result<JournalStats, String> {
+    let images = read_photo_journal(&state)?;
+
+    // Reference tags come from references.json; a missing references file just means no tags
+    let references_path = state.data_dir.join("app_data").join("references.json");
+    let references: Vec<Reference> = fs::read_to_string(&references_path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default();
+    let tags_by_reference: HashMap<&str, &Vec<String>> =
+        references.iter().map(|r| (r.id.as_str(), &r.tags)).collect();
+
+    let goal = daily_goal.unwrap_or_else(|| read_journal_settings(&state).daily_goal).max(1);
+    let today = Local::now().date_naive();
+
+    let mut days: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    let mut hours = vec![0u32; 24];
+    let mut prompts: HashMap<String, (String, u32)> = HashMap::new();
+    let mut tags: HashMap<String, (String, u32)> = HashMap::new();
+    let mut without_prompt = 0u32;
+
+    for image in &images {
+        let uploaded = match DateTime::parse_from_rfc3339(&image.upload_date) {
+            Ok(dt) => dt.with_timezone(&Local),
+            Err(_) => continue,
+        };
+        *days.entry(uploaded.date_naive()).or_insert(0) += 1;
+        hours[uploaded.hour() as usize] += 1;
+
+        match image.prompt.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+            Some(prompt) => bump(&mut prompts, prompt),
+            None => without_prompt += 1,
+        }
+
+        // Each tag counts once per entry, whether it came from the entry or a reference
+        let mut entry_tags: Vec<String> = Vec::new();
+        let reference_tags = image
+            .reference_ids
+            .iter()
+            .filter_map(|id| tags_by_reference.get(id.as_str()))
+            .flat_map(|t| t.iter());
+        for tag in image.tags.iter().chain(reference_tags) {
+            if !entry_tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                entry_tags.push(tag.clone());
+            }
+        }
+        for tag in &entry_tags {
+            bump(&mut tags, tag);
+        }
+    }
+
+    let daily: Vec<DayCount> = days
+        .iter()
+        .map(|(&date, &count)| DayCount { date: format_date(date), count, goal_met: count >= goal })
+        .collect();
+
+    let mut weeks: BTreeMap<NaiveDate, WeekCount> = BTreeMap::new();
+    for (&date, &count) in &days {
+        let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        let week = weeks.entry(monday).or_insert_with(|| WeekCount {
+            week_start: format_date(monday),
+            count: 0,
+            active_days: 0,
+            goal_days: 0,
+        });
+        week.count += count;
+        week.active_days += 1;
+        if count >= goal {
+            week.goal_days += 1;
+        }
+    }
+
+    // Calendar: full weeks (Monday first) ending with the current week
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let calendar_start = this_monday - Duration::weeks(CALENDAR_WEEKS - 1);
+    let busiest = days.range(calendar_start..=today).map(|(_, &c)| c).max().unwrap_or(0);
+    let mut calendar: Vec<CalendarDay> = Vec::new();
+    let mut date = calendar_start;
+    while date <= today {
+        let count = days.get(&date).copied().unwrap_or(0);
+        let level = if count == 0 || busiest == 0 {
+            0
+        } else {
+            ((count as f32 / busiest as f32 * 4.0).ceil() as u8).clamp(1, 4)
+        };
+        calendar.push(CalendarDay {
+            date: format_date(date),
+            count,
+            level,
+            weekday: date.weekday().num_days_from_monday() as u8,
+            week: ((date - calendar_start).num_days() / 7) as u32,
+        });
+        date += Duration::days(1);
+    }
+
+    let (current_streak, longest_streak) = streaks(&days, today, |c| c > 0);
+    let (current_goal_streak, longest_goal_streak) = streaks(&days, today, |c| c >= goal);
+
+    let today_count = days.get(&today).copied().unwrap_or(0);
+    let window_start = today - Duration::days(GOAL_WINDOW_DAYS - 1);
+    let days_met_last_30 = days.range(window_start..=today).filter(|(_, &c)| c >= goal).count() as u32;
+
+    let bucket = |range: std::ops::Range<usize>| range.map(|h| hours[h % 24]).sum::<u32>();
+    let time_of_day = TimeOfDay {
+        morning: bucket(5..12),
+        afternoon: bucket(12..17),
+        evening: bucket(17..22),
+        night: bucket(22..29),
+        hours: hours.clone(),
+    };
+
+    Ok(JournalStats {
+        total_entries: days.values().sum(),
+        active_days: days.len() as u32,
+        first_entry_date: days.keys().next().map(|d| format_date(*d)),
+        last_entry_date: days.keys().next_back().map(|d| format_date(*d)),
+        daily,
+        weekly: weeks.into_values().collect(),
+        calendar,
+        current_streak,
+        longest_streak,
+        per_prompt: sorted_counts(prompts),
+        without_prompt,
+        per_tag: sorted_counts(tags),
+        time_of_day,
+        goal: GoalProgress {
+            daily_goal: goal,
+            today_count,
+            met_today: today_count >= goal,
+            remaining_today: goal.saturating_sub(today_count),
+            days_met_last_30,
+            success_rate_last_30: (days_met_last_30 as f32 / GOAL_WINDOW_DAYS as f32 * 1000.0).round() / 10.0,
+            current_goal_streak,
+            longest_goal_streak,
+        },
+    })
+}
+
+#[tauri::command]
+pub async fn get_journal_settings(state: State<'_, AppState>) -> Result<JournalSettings, String> {
+    Ok(read_journal_settings(&state))
+}
+
+#[tauri::command]
+pub async fn set_journal_daily_goal(
+    state: State<'_, AppState>,
+    daily_goal: u32,
+) -> Result<JournalSettings, String> {
+    if !(1..=100).contains(&daily_goal) {
+        return Err("Daily goal must be between 1 and 100".to_string());
+    }
+    let mut settings = read_journal_settings(&state);
+    settings.daily_goal = daily_goal;
+    write_journal_settings(&state, &settings)?;
+    Ok(settings)
+}
+
+/// Turn automatic paper straightening / white balance of new journal photos on or off.
+#[tauri::command]
+pub async fn set_journal_auto_correct(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<JournalSettings, String> {
+    let mut settings = read_journal_settings(&state);
+    settings.auto_correct_photos = enabled;
+    write_journal_settings(&state, &settings)?;
+    Ok(settings)
+}
@@ -1,26 +1,57 @@
 // Commands module - organizes all Tauri commands by domain
 
 pub mod photo_journal;
+pub mod comparison;
+pub mod journal_stats;
+pub mod journal_metadata;
+pub mod journal_pieces;
+pub mod timelapse;
+pub mod paper_correction;
 pub mod references;
 pub mod notes;
 pub mod moodboards;
 pub mod moodboard_upload;
+pub mod moodboard_export;
+pub mod moodboard_layers;
+pub mod moodboard_store;
+pub mod moodboard_templates;
+pub mod moodboard_references;
+pub mod moodboard_gc;
+pub mod moodboard_snapshots;
+pub mod moodboard_archive;
+pub mod moodboard_layout;
 pub mod storage;
 pub mod system;
+pub mod scanner;
+pub mod hot_folders;
 pub mod phone_upload;
-pub use system::scan_artwork;
-pub use system::list_scanners;
-pub use system::scan_with_device;
 pub use system::list_warmups;
 pub use system::get_warmup_image_data;
 pub use photo_journal::set_photo_journal_rotation;
 
 // Re-export all commands for easy access
 pub use photo_journal::*;
+pub use comparison::*;
+pub use journal_stats::*;
+pub use journal_metadata::*;
+pub use journal_pieces::*;
+pub use timelapse::*;
+pub use paper_correction::*;
 pub use references::*;
 pub use notes::*;
 pub use moodboards::*;
 pub use moodboard_upload::*;
+pub use moodboard_export::*;
+pub use moodboard_layers::*;
+pub use moodboard_store::flush_pending_moodboards;
+pub use moodboard_templates::*;
+pub use moodboard_references::*;
+pub use moodboard_gc::*;
+pub use moodboard_snapshots::*;
+pub use moodboard_archive::*;
+pub use moodboard_layout::*;
 pub use storage::*;
 pub use system::*;
+pub use scanner::*;
+pub use hot_folders::*;
 pub use phone_upload::*;
\ No newline at end of file
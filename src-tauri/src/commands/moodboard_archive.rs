@@ -0,0 +1,362 @@
+//! Portable moodboard archives for handing boards to other users.
+//!
+//! An archive is a zip with `manifest.json` (the board and its items) and the images
+//! under `images/`. Item urls in the manifest point into the archive; they are
+//! rewritten to the new board's folder on import.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use image::ImageOutputFormat;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::imaging::encode_image;
+use crate::models::{Moodboard, MoodboardItem};
+use crate::state::AppState;
+use crate::commands::moodboards::{copy_items, create_board_dir, insert_new_moodboard, read_moodboards};
+use crate::commands::references::adjustments::render_reference;
+use crate::commands::references::crud::find_reference;
+use crate::commands::references::resolve_image_path;
+
+const ARCHIVE_FORMAT: &str = "moodboard-archive";
+const ARCHIVE_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+const IMAGES_DIR: &str = "images/";
+/// Larger entries are refused on import
+const MAX_ENTRY_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveManifest {
+    format: String,
+    version: u32,
+    exported_at: i64,
+    moodboard: Moodboard,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardArchiveExportResult {
+    pub path: String,
+    pub size: u64,
+    pub item_count: usize,
+    pub file_count: usize,
+    /// Items whose image was not found; they are packed as placeholders
+    pub missing_item_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardArchiveImportResult {
+    pub moodboard: Moodboard,
+    pub file_count: usize,
+    /// Images identical to a file already in the library, copied from it
+    pub reused_file_count: usize,
+}
+
+/// `name`, or `name-2`, `name-3`, ... when it is taken.
+fn unique_name(taken: &HashSet<String>, name: &str) -> String {
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}-{}{}", stem, n, ext);
+        n += 1;
+    }
+    candidate
+}
+
+/// The bytes to pack for an item and a file name for them. Reference items are
+/// rendered as shown on the board, since the recipient doesn't have the library.
+fn item_file(state: &AppState, item: &MoodboardItem) -> Result<(String, Vec<u8>), String> {
+    if let Some(reference_id) = item.reference_id.as_deref().filter(|_| item.item_type == "reference") {
+        if item.reference_missing {
+            return Err("Reference was deleted".to_string());
+        }
+        let reference = find_reference(state, reference_id)?;
+        let data = encode_image(&render_reference(state, &reference)?, ImageOutputFormat::Png)?;
+        let stem = reference.filename.rsplit_once('.').map_or(reference.filename.as_str(), |(stem, _)| stem);
+        return Ok((format!("{}.png", stem), data));
+    }
+    let url = item.url.as_deref().ok_or_else(|| "Image item has no url".to_string())?;
+    let path = resolve_image_path(state, url);
+    let data = fs::read(&path).map_err(|e| format!("Failed to read image: {}", e))?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "image".to_string());
+    Ok((name, data))
+}
+
+fn write_archive(path: &Path, manifest: &ArchiveManifest, files: &[(String, Vec<u8>)]) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let manifest_json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize archive manifest: {}", e))?;
+    zip.start_file(MANIFEST_NAME, FileOptions::default().compression_method(CompressionMethod::Deflated))
+        .and_then(|_| zip.write_all(&manifest_json).map_err(Into::into))
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    // Images are compressed already
+    for (name, data) in files {
+        zip.start_file(format!("{}{}", IMAGES_DIR, name), FileOptions::default().compression_method(CompressionMethod::Stored))
+            .and_then(|_| zip.write_all(data).map_err(Into::into))
+            .map_err(|e| format!("Failed to write archive: {}", e))?;
+    }
+    zip.finish().map_err(|e| format!("Failed to write archive: {}", e))?;
+    Ok(())
+}
+
+/// Pack a board and its images into one file. Identical images are stored once;
+/// items whose image can't be found become placeholders.
+#[tauri::command]
+pub async fn export_moodboard_archive(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    target_path: String,
+) -> Result<MoodboardArchiveExportResult, String> {
+    let mut board = read_moodboards(&state)?
+        .into_iter()
+        .find(|m| m.id == moodboard_id)
+        .ok_or_else(|| "Moodboard not found".to_string())?;
+
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut names = HashSet::new();
+    let mut missing_item_ids = Vec::new();
+    for item in board.items.iter_mut() {
+        if !matches!(item.item_type.as_str(), "image" | "reference") {
+            continue;
+        }
+        let name = match item_file(&state, item) {
+            Ok((name, data)) => match files.iter().find(|(_, d)| *d == data) {
+                Some((existing, _)) => existing.clone(),
+                None => {
+                    let name = unique_name(&names, &name);
+                    names.insert(name.clone());
+                    files.push((name.clone(), data));
+                    name
+                }
+            },
+            Err(e) => {
+                eprintln!("Warning: moodboard archive is missing the image of item {}: {}", item.id, e);
+                missing_item_ids.push(item.id.clone());
+                item.item_type = "placeholder".to_string();
+                item.url = None;
+                item.filename = None;
+                item.reference_id = None;
+                item.reference_missing = false;
+                continue;
+            }
+        };
+        item.item_type = "image".to_string();
+        item.url = Some(format!("{}{}", IMAGES_DIR, name));
+        item.is_webp = Some(name.ends_with(".webp"));
+        item.filename = Some(name);
+        item.reference_id = None;
+    }
+
+    let manifest = ArchiveManifest {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at: chrono::Utc::now().timestamp_millis(),
+        moodboard: board,
+    };
+    let path = PathBuf::from(&target_path);
+    if let Err(e) = write_archive(&path, &manifest, &files) {
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(MoodboardArchiveExportResult {
+        path: target_path,
+        size,
+        item_count: manifest.moodboard.items.len(),
+        file_count: files.len(),
+        missing_item_ids,
+    })
+}
+
+fn read_entry<R: Read + std::io::Seek>(zip: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>, String> {
+    let mut entry = zip
+        .by_name(name)
+        .map_err(|_| format!("Archive is missing {}", name))?;
+    if entry.size() > MAX_ENTRY_BYTES {
+        return Err(format!("Archive entry is too large: {}", name));
+    }
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+    Ok(data)
+}
+
+/// Key for finding candidate identical images: the size and a hash of the bytes.
+/// A match is only a candidate; compare the bytes with `has_content` before use.
+fn content_key(data: &[u8]) -> (u64, u64) {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    (data.len() as u64, hasher.finish())
+}
+
+/// Whether the file at `path` holds exactly `data`.
+fn has_content(path: &Path, data: &[u8]) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.len() == data.len() as u64) && fs::read(path).is_ok_and(|d| d == data)
+}
+
+/// Files already under `Library/Moodboards/<board>/` by content. Each file is read
+/// once, and only when its size matches one of `sizes`.
+fn library_images(root: &Path, sizes: &HashSet<u64>) -> HashMap<(u64, u64), PathBuf> {
+    let mut files = HashMap::new();
+    for board in fs::read_dir(root).into_iter().flatten().flatten() {
+        for file in fs::read_dir(board.path()).into_iter().flatten().flatten() {
+            let is_candidate = file.metadata().is_ok_and(|m| m.is_file() && sizes.contains(&m.len()));
+            if let Some(data) = is_candidate.then(|| fs::read(file.path()).ok()).flatten() {
+                files.entry(content_key(&data)).or_insert_with(|| file.path());
+            }
+        }
+    }
+    files
+}
+
+/// Unpack the images of `items` into `board_dir` and point the items at them.
+/// Returns how many files were written and how many of those were copied from an
+/// identical library file.
+fn unpack_items<R: Read + std::io::Seek>(
+    zip: &mut ZipArchive<R>,
+    items: &mut [MoodboardItem],
+    root: &Path,
+    board_id: &str,
+) -> Result<(usize, usize), String> {
+    let entries: HashSet<String> = zip.file_names().map(str::to_string).collect();
+    let mut sizes = HashSet::new();
+    for item in items.iter() {
+        if let Some(entry) = item.url.as_deref().filter(|u| entries.contains(*u)) {
+            if let Ok(file) = zip.by_name(entry) {
+                sizes.insert(file.size());
+            }
+        }
+    }
+    let library = library_images(root, &sizes);
+    let board_dir = root.join(board_id);
+
+    // Archive entry -> stored file name, and content -> stored file name, so
+    // entries used by several items (or with the same bytes) are stored once
+    let mut stored: HashMap<String, String> = HashMap::new();
+    let mut by_content: HashMap<(u64, u64), String> = HashMap::new();
+    let mut names = HashSet::new();
+    let (mut file_count, mut reused_file_count) = (0, 0);
+    for item in items.iter_mut() {
+        // Urls from the manifest are never kept: they could point at other boards'
+        // or library files, which deleting the item would then remove
+        let entry = item.url.take().filter(|u| u.starts_with(IMAGES_DIR) && entries.contains(u));
+        item.filename = None;
+        item.reference_id = None;
+        item.reference_missing = false;
+        let entry = match entry {
+            Some(entry) => entry,
+            None => {
+                if matches!(item.item_type.as_str(), "image" | "reference") {
+                    item.item_type = "placeholder".to_string();
+                }
+                continue;
+            }
+        };
+        let name = match stored.get(&entry) {
+            Some(name) => name.clone(),
+            None => {
+                let data = read_entry(zip, &entry)?;
+                let key = content_key(&data);
+                let same = by_content.get(&key).filter(|name| has_content(&board_dir.join(name), &data));
+                let name = match same {
+                    Some(name) => name.clone(),
+                    None => {
+                        // Only the last path component is used, so names can't leave the board folder
+                        let base = Path::new(&entry)
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .filter(|n| !n.starts_with('.'))
+                            .unwrap_or_else(|| "image".to_string());
+                        let name = unique_name(&names, &base);
+                        let target = board_dir.join(&name);
+                        // A copy of the library file, not a link: the boards stay independent,
+                        // and copy-on-write file systems (APFS, Btrfs) share the blocks
+                        let copied = library
+                            .get(&key)
+                            .filter(|existing| has_content(existing, &data))
+                            .is_some_and(|existing| fs::copy(existing, &target).is_ok());
+                        if copied {
+                            reused_file_count += 1;
+                        } else {
+                            fs::write(&target, &data)
+                                .map_err(|e| format!("Failed to write image: {}", e))?;
+                        }
+                        file_count += 1;
+                        names.insert(name.clone());
+                        by_content.insert(key, name.clone());
+                        name
+                    }
+                };
+                stored.insert(entry, name.clone());
+                name
+            }
+        };
+        item.item_type = "image".to_string();
+        item.url = Some(format!("moodboards/{}/{}", board_id, name));
+        item.filename = Some(name);
+    }
+    Ok((file_count, reused_file_count))
+}
+
+/// Unpack an archive as a new board with new ids. Images identical to ones already
+/// in the library are copied from them. Items whose url is not an image in the
+/// archive become placeholders.
+#[tauri::command]
+pub async fn import_moodboard_archive(
+    state: State<'_, AppState>,
+    archive_path: String,
+    title: Option<String>,
+) -> Result<MoodboardArchiveImportResult, String> {
+    let file = fs::File::open(&archive_path)
+        .map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+    let manifest: ArchiveManifest = serde_json::from_slice(&read_entry(&mut zip, MANIFEST_NAME)?)
+        .map_err(|e| format!("Failed to parse archive manifest: {}", e))?;
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err("Not a moodboard archive".to_string());
+    }
+    if manifest.version > ARCHIVE_VERSION {
+        return Err("Moodboard archive was made by a newer version of the app".to_string());
+    }
+
+    // Images are unpacked before the board is added, so the cache isn't locked meanwhile
+    let (new_id, dir) = create_board_dir(&state)?;
+    let mut items = copy_items(&manifest.moodboard.items, "", &new_id);
+    let result = unpack_items(&mut zip, &mut items, &state.library_dir.join("Moodboards"), &new_id)
+        .and_then(|(file_count, reused_file_count)| {
+            let now = chrono::Utc::now().timestamp_millis();
+            let moodboard = insert_new_moodboard(&state, Moodboard {
+                id: new_id.clone(),
+                title: title
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or_else(|| manifest.moodboard.title.clone()),
+                items,
+                created_at: now,
+                updated_at: Some(now),
+            })?;
+            Ok(MoodboardArchiveImportResult { moodboard, file_count, reused_file_count })
+        });
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&dir);
+    }
+    result
+}
@@ -0,0 +1,405 @@
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use image::{DynamicImage, ImageOutputFormat, RgbImage, Rgba, RgbaImage};
+use image::imageops::FilterType;
+
+use crate::imaging::{
+    draw_text, encode_image, encode_pdf, fill_rect, flatten_onto, load_oriented, parse_hex_color, rotate_to_bounds,
+    wrap_text, PdfPage,
+};
+use crate::imaging::font::GLYPH_HEIGHT;
+use crate::models::{Moodboard, MoodboardItem};
+use crate::state::AppState;
+use crate::commands::moodboards::read_moodboards;
+use crate::commands::moodboard_layers::sort_layers;
+use crate::commands::references::resolve_image_path;
+use crate::commands::references::adjustments::render_reference;
+use crate::commands::references::crud::find_reference;
+
+/// Board coordinates are CSS pixels
+const BOARD_DPI: f64 = 96.0;
+const DEFAULT_DPI: u32 = 150;
+const DEFAULT_MARGIN: f64 = 40.0;
+const MAX_PIXELS: f64 = 150_000_000.0;
+const PDF_JPEG_QUALITY: u8 = 90;
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+// Text boxes as drawn by the editor
+const TEXT_PADDING: f64 = 20.0;
+const TEXT_LINE_HEIGHT: f64 = 1.4;
+const TEXT_BORDER: Rgba<u8> = Rgba([224, 224, 224, 255]);
+const DEFAULT_TEXT_COLOR: Rgba<u8> = Rgba([51, 51, 51, 255]);
+const DEFAULT_FONT_SIZE: f64 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MoodboardExportFormat {
+    Png,
+    Pdf,
+}
+
+impl MoodboardExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            MoodboardExportFormat::Png => "png",
+            MoodboardExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardExportOptions {
+    /// Defaults to the target path's extension, then PNG
+    pub format: Option<MoodboardExportFormat>,
+    /// Output resolution (24-600, default 150); the board itself is 96 dpi
+    pub dpi: Option<u32>,
+    /// Fit the board into this pixel size instead of using `dpi`
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Hex colour, or "transparent" (PNG only). Default white
+    pub background: Option<String>,
+    /// Space around the items, in board pixels (default 40)
+    pub margin: Option<f64>,
+    /// PDF: spread each board over `pageSize` pages instead of one page sized to the board
+    #[serde(default)]
+    pub tiled: bool,
+    /// "A3", "A4" (default), "A5", "Letter" or "Tabloid"
+    pub page_size: Option<String>,
+    #[serde(default)]
+    pub landscape: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardExportResult {
+    pub path: String,
+    pub format: String,
+    pub page_count: usize,
+    /// Pixel size of the (first) rendered board
+    pub width: u32,
+    pub height: u32,
+    pub size: u64,
+    /// Items left out: missing or unreadable images, deleted references, unknown colours
+    pub skipped_item_ids: Vec<String>,
+}
+
+/// Page size in millimetres, portrait.
+fn page_size_mm(name: &str) -> Option<(f64, f64)> {
+    match name.trim().to_ascii_uppercase().as_str() {
+        "A3" => Some((297.0, 420.0)),
+        "A4" => Some((210.0, 297.0)),
+        "A5" => Some((148.0, 210.0)),
+        "LETTER" => Some((215.9, 279.4)),
+        "TABLOID" => Some((279.4, 431.8)),
+        _ => None,
+    }
+}
+
+/// Template placeholders are editing aids and stay out of exports.
+fn is_drawn(item: &MoodboardItem) -> bool {
+    item.width > 0.0 && item.height > 0.0 && item.opacity > 0.0 && item.item_type != "placeholder"
+}
+
+/// Box around the item as rotated on the board: (left, top, right, bottom).
+pub(crate) fn item_extent(item: &MoodboardItem) -> (f64, f64, f64, f64) {
+    let (sin, cos) = item.rotation.to_radians().sin_cos();
+    let half_w = (item.width * cos.abs() + item.height * sin.abs()) / 2.0;
+    let half_h = (item.width * sin.abs() + item.height * cos.abs()) / 2.0;
+    let (cx, cy) = (item.x + item.width / 2.0, item.y + item.height / 2.0);
+    (cx - half_w, cy - half_h, cx + half_w, cy + half_h)
+}
+
+/// Area covered by the items plus `margin`, in board pixels: (x, y, width, height).
+fn board_bounds(board: &Moodboard, margin: f64) -> Result<(f64, f64, f64, f64), String> {
+    let mut extents = board.items.iter().filter(|i| is_drawn(i)).map(item_extent);
+    let (mut x0, mut y0, mut x1, mut y1) = extents
+        .next()
+        .ok_or_else(|| format!("Moodboard \"{}\" is empty", board.title))?;
+    for (left, top, right, bottom) in extents {
+        x0 = x0.min(left);
+        y0 = y0.min(top);
+        x1 = x1.max(right);
+        y1 = y1.max(bottom);
+    }
+    Ok((x0 - margin, y0 - margin, x1 - x0 + margin * 2.0, y1 - y0 + margin * 2.0))
+}
+
+/// Item rectangle on the canvas, in whole pixels.
+fn item_rect(item: &MoodboardItem, origin: (f64, f64), scale: f64) -> (i64, i64, u32, u32) {
+    let x = ((item.x - origin.0) * scale).round();
+    let y = ((item.y - origin.1) * scale).round();
+    let right = ((item.x + item.width - origin.0) * scale).round();
+    let bottom = ((item.y + item.height - origin.1) * scale).round();
+    (x as i64, y as i64, (right - x).max(1.0) as u32, (bottom - y).max(1.0) as u32)
+}
+
+/// Image shown by an image item, or by a reference item (with the reference's
+/// rotation, crop and adjustments).
+fn load_item_image(state: &AppState, item: &MoodboardItem) -> Result<DynamicImage, String> {
+    if let Some(reference_id) = item.reference_id.as_deref().filter(|_| item.item_type == "reference") {
+        if item.reference_missing {
+            return Err("Reference was deleted".to_string());
+        }
+        return render_reference(state, &find_reference(state, reference_id)?);
+    }
+    let url = item.url.as_deref().ok_or_else(|| "Image item has no url".to_string())?;
+    load_oriented(&resolve_image_path(state, url))
+}
+
+/// Draw an image item like the editor does (`object-fit: contain`).
+fn draw_image_item(state: &AppState, layer: &mut RgbaImage, item: &MoodboardItem) -> Result<(), String> {
+    let img = load_item_image(state, item)?;
+    let (w, h) = layer.dimensions();
+    let fitted = img.resize(w, h, FilterType::Lanczos3).to_rgba8();
+    let dx = (w - fitted.width().min(w)) / 2;
+    let dy = (h - fitted.height().min(h)) / 2;
+    image::imageops::overlay(layer, &fitted, dx as i64, dy as i64);
+    Ok(())
+}
+
+fn draw_text_item(layer: &mut RgbaImage, item: &MoodboardItem, scale: f64) {
+    let (w, h) = layer.dimensions();
+    fill_rect(layer, 0, 0, w, h, TEXT_BORDER);
+    let border = (scale.round() as u32).max(1);
+    fill_rect(layer, border as i64, border as i64, w.saturating_sub(border * 2), h.saturating_sub(border * 2), WHITE);
+
+    let text = match item.content.as_deref() {
+        Some(text) if !text.trim().is_empty() => text,
+        _ => return,
+    };
+    let color = item.color.as_deref().and_then(|c| parse_hex_color(c).ok()).unwrap_or(DEFAULT_TEXT_COLOR);
+    let font_px = item.font_size.filter(|s| *s > 0.0).unwrap_or(DEFAULT_FONT_SIZE) * scale;
+    // The built-in font's capitals are 7 units tall, about 0.7 em
+    let glyph_scale = ((font_px * 0.7 / GLYPH_HEIGHT as f64).round() as u32).max(1);
+    let line_height = (font_px * TEXT_LINE_HEIGHT).round().max((GLYPH_HEIGHT * glyph_scale) as f64) as i64;
+    let padding = (TEXT_PADDING * scale).round() as i64;
+    let inner_width = (w as i64 - padding * 2).max(1) as u32;
+    let bottom = h as i64 - padding;
+
+    let mut line_y = padding;
+    for line in wrap_text(text, glyph_scale, inner_width) {
+        if line_y + (GLYPH_HEIGHT * glyph_scale) as i64 > bottom {
+            break;
+        }
+        draw_text(layer, padding, line_y, &line, glyph_scale, color);
+        line_y += line_height;
+    }
+}
+
+/// Draw one item unrotated at its output size. `None` when it can't be drawn.
+fn render_item(state: &AppState, item: &MoodboardItem, width: u32, height: u32, scale: f64) -> Option<RgbaImage> {
+    let mut layer = RgbaImage::new(width, height);
+    match item.item_type.as_str() {
+        "image" | "reference" => {
+            if let Err(e) = draw_image_item(state, &mut layer, item) {
+                eprintln!("Moodboard export skipped image {}: {}", item.id, e);
+                return None;
+            }
+        }
+        "color" => {
+            let value = item.content.as_deref()
+                .or_else(|| item.colors.as_ref().and_then(|c| c.first()).map(String::as_str));
+            let color = parse_hex_color(value?).ok()?;
+            fill_rect(&mut layer, 0, 0, width, height, color);
+        }
+        "text" => draw_text_item(&mut layer, item, scale),
+        _ => return None,
+    }
+    Some(layer)
+}
+
+/// Composite a board at `scale` output pixels per board pixel, in layer order with
+/// each item's flip, rotation and opacity. Returns the canvas and the ids of items
+/// that could not be drawn.
+fn render_board(
+    state: &AppState,
+    board: &Moodboard,
+    bounds: (f64, f64, f64, f64),
+    scale: f64,
+    background: Rgba<u8>,
+) -> Result<(RgbaImage, Vec<String>), String> {
+    let width = (bounds.2 * scale).ceil().max(1.0) as u32;
+    let height = (bounds.3 * scale).ceil().max(1.0) as u32;
+    let mut canvas = RgbaImage::from_pixel(width, height, background);
+    let origin = (bounds.0, bounds.1);
+    let mut skipped = Vec::new();
+
+    let mut items: Vec<MoodboardItem> = board.items.iter().filter(|i| is_drawn(i)).cloned().collect();
+    sort_layers(&mut items);
+    for item in &items {
+        let (x, y, w, h) = item_rect(item, origin, scale);
+        let layer = match render_item(state, item, w, h, scale) {
+            Some(layer) => layer,
+            None => {
+                skipped.push(item.id.clone());
+                continue;
+            }
+        };
+        let mut layer = DynamicImage::ImageRgba8(layer);
+        if item.flip.horizontal() {
+            layer = layer.fliph();
+        }
+        if item.flip.vertical() {
+            layer = layer.flipv();
+        }
+        let mut layer = rotate_to_bounds(layer, item.rotation.round() as i32).to_rgba8();
+        if item.opacity < 1.0 {
+            for pixel in layer.pixels_mut() {
+                pixel[3] = (pixel[3] as f64 * item.opacity).round() as u8;
+            }
+        }
+        // Rotation is around the item's centre
+        let left = x + w as i64 / 2 - layer.width() as i64 / 2;
+        let top = y + h as i64 / 2 - layer.height() as i64 / 2;
+        image::imageops::overlay(&mut canvas, &layer, left, top);
+    }
+    Ok((canvas, skipped))
+}
+
+/// Split a render into page-sized tiles (left to right, top to bottom), padding the
+/// last row and column with `background`.
+fn tile(canvas: &RgbImage, tile_w: u32, tile_h: u32, background: [u8; 3]) -> Vec<RgbImage> {
+    let mut tiles = Vec::new();
+    for ty in (0..canvas.height()).step_by(tile_h as usize) {
+        for tx in (0..canvas.width()).step_by(tile_w as usize) {
+            let mut page = RgbImage::from_pixel(tile_w, tile_h, image::Rgb(background));
+            let view = image::imageops::crop_imm(canvas, tx, ty, tile_w.min(canvas.width() - tx), tile_h.min(canvas.height() - ty));
+            image::imageops::replace(&mut page, &view.to_image(), 0, 0);
+            tiles.push(page);
+        }
+    }
+    tiles
+}
+
+/// Render one or more moodboards to a PNG (one board) or a PDF (one page per board,
+/// or each board tiled across printable pages). Image, colour and text items are
+/// drawn at their `x/y/width/height`; text uses the built-in label font.
+#[tauri::command]
+pub async fn export_moodboard(
+    state: State<'_, AppState>,
+    moodboard_ids: Vec<String>,
+    target_path: String,
+    options: Option<MoodboardExportOptions>,
+) -> Result<MoodboardExportResult, String> {
+    let options = options.unwrap_or_default();
+    if moodboard_ids.is_empty() {
+        return Err("No moodboards selected".to_string());
+    }
+    let dpi = options.dpi.unwrap_or(DEFAULT_DPI);
+    if !(24..=600).contains(&dpi) {
+        return Err("DPI must be between 24 and 600".to_string());
+    }
+    if options.width == Some(0) || options.height == Some(0) {
+        return Err("Width and height must be positive".to_string());
+    }
+    let margin = options.margin.unwrap_or(DEFAULT_MARGIN);
+    if !(0.0..=2000.0).contains(&margin) {
+        return Err("Margin must be between 0 and 2000".to_string());
+    }
+
+    let mut path = PathBuf::from(&target_path);
+    let format = options.format.unwrap_or_else(|| {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()) {
+            Some(ext) if ext == "pdf" => MoodboardExportFormat::Pdf,
+            _ => MoodboardExportFormat::Png,
+        }
+    });
+    path.set_extension(format.extension());
+    if format == MoodboardExportFormat::Png && moodboard_ids.len() > 1 {
+        return Err("PNG export takes one moodboard; use PDF for several".to_string());
+    }
+
+    let background = match options.background.as_deref().map(str::trim) {
+        None | Some("") => WHITE,
+        Some(value) if value.eq_ignore_ascii_case("transparent") => {
+            if format == MoodboardExportFormat::Pdf {
+                WHITE
+            } else {
+                Rgba([255, 255, 255, 0])
+            }
+        }
+        Some(value) => parse_hex_color(value)?,
+    };
+    let page_mm = match options.page_size.as_deref() {
+        Some(name) => page_size_mm(name).ok_or_else(|| format!("Unknown page size: {}", name))?,
+        None => (210.0, 297.0),
+    };
+    let page_mm = if options.landscape { (page_mm.1, page_mm.0) } else { page_mm };
+
+    let all = read_moodboards(&state)?;
+    let mut boards: Vec<&Moodboard> = Vec::new();
+    for id in &moodboard_ids {
+        boards.push(all.iter().find(|m| m.id == *id).ok_or_else(|| "Moodboard not found".to_string())?);
+    }
+
+    let mut pages: Vec<PdfPage> = Vec::new();
+    let mut png: Option<Vec<u8>> = None;
+    let mut first_size = (0, 0);
+    let mut skipped_item_ids = Vec::new();
+    for board in boards {
+        let bounds = board_bounds(board, margin)?;
+        let scale = match (options.width, options.height) {
+            (None, None) => dpi as f64 / BOARD_DPI,
+            (w, h) => {
+                let sx = w.map(|w| w as f64 / bounds.2).unwrap_or(f64::INFINITY);
+                let sy = h.map(|h| h as f64 / bounds.3).unwrap_or(f64::INFINITY);
+                sx.min(sy)
+            }
+        };
+        if bounds.2 * scale * bounds.3 * scale > MAX_PIXELS {
+            return Err(format!("\"{}\" is too large at this resolution; lower the DPI or pixel size", board.title));
+        }
+
+        let (canvas, skipped) = render_board(&state, board, bounds, scale, background)?;
+        skipped_item_ids.extend(skipped);
+        if first_size == (0, 0) {
+            first_size = canvas.dimensions();
+        }
+
+        match format {
+            MoodboardExportFormat::Png => {
+                png = Some(encode_image(&DynamicImage::ImageRgba8(canvas), ImageOutputFormat::Png)?);
+            }
+            MoodboardExportFormat::Pdf => {
+                let bg = [background[0], background[1], background[2]];
+                let rgb = flatten_onto(&DynamicImage::ImageRgba8(canvas), bg);
+                // Points per output pixel
+                let pt_per_px = 72.0 / (scale * BOARD_DPI);
+                if options.tiled {
+                    let tile_w = (page_mm.0 / 25.4 * scale * BOARD_DPI).round().max(1.0) as u32;
+                    let tile_h = (page_mm.1 / 25.4 * scale * BOARD_DPI).round().max(1.0) as u32;
+                    for image in tile(&rgb, tile_w, tile_h, bg) {
+                        pages.push(PdfPage::new(image, page_mm.0 / 25.4 * 72.0, page_mm.1 / 25.4 * 72.0, PDF_JPEG_QUALITY)?);
+                    }
+                } else {
+                    let (w, h) = rgb.dimensions();
+                    pages.push(PdfPage::new(rgb, w as f64 * pt_per_px, h as f64 * pt_per_px, PDF_JPEG_QUALITY)?);
+                }
+            }
+        }
+    }
+
+    let (bytes, page_count) = match format {
+        MoodboardExportFormat::Png => (png.unwrap_or_default(), 1),
+        MoodboardExportFormat::Pdf => (encode_pdf(&pages)?, pages.len()),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    fs::write(&path, &bytes)
+        .map_err(|e| format!("Failed to write moodboard export: {}", e))?;
+
+    Ok(MoodboardExportResult {
+        path: path.to_string_lossy().to_string(),
+        format: format.extension().to_string(),
+        page_count,
+        width: first_size.0,
+        height: first_size.1,
+        size: bytes.len() as u64,
+        skipped_item_ids,
+    })
+}
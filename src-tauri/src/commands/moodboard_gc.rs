@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::state::AppState;
+use crate::commands::moodboards::read_moodboards;
+use crate::commands::moodboard_snapshots::read_snapshots;
+
+/// Files this new may belong to an upload whose item isn't saved yet
+const GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MoodboardGcMode {
+    /// Move into `Library/Trash/Moodboards/<time>`
+    #[default]
+    Trash,
+    Delete,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardOrphan {
+    /// Relative to `Library/Moodboards`
+    pub path: String,
+    /// "file" (not used by any item) or "board" (folder of a deleted board)
+    pub kind: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardGcReport {
+    pub dry_run: bool,
+    pub orphans: Vec<MoodboardOrphan>,
+    pub reclaimable_bytes: u64,
+    pub removed: usize,
+    /// Where orphans were moved, in trash mode
+    pub trash_path: Option<String>,
+    /// Orphans that could not be removed
+    pub errors: Vec<String>,
+}
+
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| match e.file_type() {
+                    Ok(t) if t.is_dir() => dir_size(&e.path()),
+                    _ => e.metadata().map(|m| m.len()).unwrap_or(0),
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Changed within the grace period (anything inside, for folders).
+fn is_recent(path: &Path, now: SystemTime) -> bool {
+    let recent = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| now.duration_since(t).unwrap_or_default() < GRACE_PERIOD)
+        .unwrap_or(false);
+    recent
+        || (path.is_dir()
+            && fs::read_dir(path)
+                .map(|entries| entries.flatten().any(|e| is_recent(&e.path(), now)))
+                .unwrap_or(false))
+}
+
+/// Board ids and `<board>/<file>` paths still used by a board or a snapshot.
+fn referenced_moodboard_files(state: &AppState) -> Result<(HashSet<String>, HashSet<String>), String> {
+    let moodboards = read_moodboards(state)?;
+    let snapshots = read_snapshots(state)?;
+    let boards = moodboards
+        .iter()
+        .map(|m| m.id.clone())
+        .chain(snapshots.iter().map(|s| s.moodboard_id.clone()))
+        .collect();
+    let files = moodboards
+        .iter()
+        .flat_map(|m| m.items.iter())
+        .chain(snapshots.iter().flat_map(|s| s.items.iter()))
+        .filter_map(|i| i.url.as_deref()?.strip_prefix("moodboards/").map(str::to_string))
+        .collect();
+    Ok((boards, files))
+}
+
+fn find_orphans(state: &AppState, root: &Path) -> Result<Vec<(PathBuf, MoodboardOrphan)>, String> {
+    let (boards, files) = referenced_moodboard_files(state)?;
+    let now = SystemTime::now();
+    let mut orphans = Vec::new();
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(orphans),
+    };
+    for board_dir in entries.flatten() {
+        let path = board_dir.path();
+        let board_id = board_dir.file_name().to_string_lossy().to_string();
+        if !path.is_dir() {
+            continue;
+        }
+        if !boards.contains(&board_id) {
+            if is_recent(&path, now) {
+                continue;
+            }
+            orphans.push((path.clone(), MoodboardOrphan { size: dir_size(&path), path: board_id, kind: "board".to_string() }));
+            continue;
+        }
+        // Only the board's own image files; anything else in its folder is left alone
+        for file in fs::read_dir(&path).map_err(|e| format!("Failed to read moodboard directory: {}", e))?.flatten() {
+            let relative = format!("{}/{}", board_id, file.file_name().to_string_lossy());
+            let file_path = file.path();
+            if !file_path.is_file() || files.contains(&relative) || is_recent(&file_path, now) {
+                continue;
+            }
+            let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+            orphans.push((file_path, MoodboardOrphan { path: relative, kind: "file".to_string(), size }));
+        }
+    }
+    orphans.sort_by(|a, b| a.1.path.cmp(&b.1.path));
+    Ok(orphans)
+}
+
+/// Find files under `Library/Moodboards` that no item uses, and folders of deleted
+/// boards, then move them to the trash or delete them. `dryRun` (the default) only
+/// reports them. Files changed in the last hour are skipped.
+#[tauri::command]
+pub async fn gc_moodboards(
+    state: State<'_, AppState>,
+    dry_run: Option<bool>,
+    mode: Option<MoodboardGcMode>,
+) -> Result<MoodboardGcReport, String> {
+    let dry_run = dry_run.unwrap_or(true);
+    let mode = mode.unwrap_or_default();
+    let root = state.library_dir.join("Moodboards");
+    let orphans = find_orphans(&state, &root)?;
+    let reclaimable_bytes = orphans.iter().map(|(_, o)| o.size).sum();
+
+    let mut removed = 0;
+    let mut errors = Vec::new();
+    let mut trash_path = None;
+    if !dry_run && !orphans.is_empty() {
+        let trash = state.library_dir
+            .join("Trash")
+            .join("Moodboards")
+            .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string());
+        for (path, orphan) in &orphans {
+            let result = match mode {
+                MoodboardGcMode::Delete if path.is_dir() => fs::remove_dir_all(path),
+                MoodboardGcMode::Delete => fs::remove_file(path),
+                MoodboardGcMode::Trash => {
+                    let target = trash.join(&orphan.path);
+                    target.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::rename(path, &target))
+                }
+            };
+            match result {
+                Ok(()) => removed += 1,
+                Err(e) => errors.push(format!("{}: {}", orphan.path, e)),
+            }
+        }
+        if mode == MoodboardGcMode::Trash && removed > 0 {
+            trash_path = Some(trash.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(MoodboardGcReport {
+        dry_run,
+        orphans: orphans.into_iter().map(|(_, o)| o).collect(),
+        reclaimable_bytes,
+        removed,
+        trash_path,
+        errors,
+    })
+}
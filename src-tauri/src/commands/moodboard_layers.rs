@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use serde::Deserialize;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::models::{Moodboard, MoodboardFlip, MoodboardItem};
+use crate::state::AppState;
+use crate::commands::moodboards::{modify_moodboard, modify_moodboard_deferred};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LayerMove {
+    BringToFront,
+    BringForward,
+    SendBackward,
+    SendToBack,
+}
+
+/// Properties to set on every listed item; unset fields are left alone.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardItemProperties {
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub rotation: Option<f64>,
+    pub opacity: Option<f64>,
+    pub locked: Option<bool>,
+    pub flip: Option<MoodboardFlip>,
+    pub z_index: Option<i64>,
+    pub content: Option<String>,
+    pub font_size: Option<f64>,
+    pub color: Option<String>,
+}
+
+impl MoodboardItemProperties {
+    /// Whether these change where or how an item sits on the board.
+    fn moves_items(&self) -> bool {
+        self.x.is_some()
+            || self.y.is_some()
+            || self.width.is_some()
+            || self.height.is_some()
+            || self.rotation.is_some()
+            || self.flip.is_some()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        for value in [self.x, self.y, self.rotation].into_iter().flatten() {
+            if !value.is_finite() {
+                return Err("Position and rotation must be finite numbers".to_string());
+            }
+        }
+        for value in [self.width, self.height].into_iter().flatten() {
+            if !(value.is_finite() && value > 0.0) {
+                return Err("Width and height must be positive".to_string());
+            }
+        }
+        if let Some(opacity) = self.opacity {
+            if !(0.0..=1.0).contains(&opacity) {
+                return Err("Opacity must be between 0 and 1".to_string());
+            }
+        }
+        if let Some(size) = self.font_size {
+            if !(size.is_finite() && size > 0.0) {
+                return Err("Font size must be positive".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the properties on `item`. Locked items only accept changes that don't
+    /// move them, unless the same update unlocks them.
+    pub(crate) fn apply_to(&self, item: &mut MoodboardItem) -> Result<(), String> {
+        if item.locked && self.moves_items() && self.locked != Some(false) {
+            return Err("Item is locked".to_string());
+        }
+        if let Some(v) = self.x { item.x = v; }
+        if let Some(v) = self.y { item.y = v; }
+        if let Some(v) = self.width { item.width = v; }
+        if let Some(v) = self.height { item.height = v; }
+        if let Some(v) = self.rotation { item.rotation = v.rem_euclid(360.0); }
+        if let Some(v) = self.opacity { item.opacity = v; }
+        if let Some(v) = self.locked { item.locked = v; }
+        if let Some(v) = self.flip { item.flip = v; }
+        if let Some(v) = self.z_index { item.z_index = v; }
+        if let Some(v) = &self.content { item.content = Some(v.clone()); }
+        if let Some(v) = self.font_size { item.font_size = Some(v); }
+        if let Some(v) = &self.color { item.color = Some(v.clone()); }
+        Ok(())
+    }
+}
+
+/// Put items in paint order (bottom first). Equal `zIndex` values, as on boards
+/// saved before layers, keep their list order.
+pub(crate) fn sort_layers(items: &mut [MoodboardItem]) {
+    items.sort_by_key(|item| item.z_index);
+}
+
+/// Make `zIndex` follow the current list order, from 0.
+fn renumber_layers(items: &mut [MoodboardItem]) {
+    for (i, item) in items.iter_mut().enumerate() {
+        item.z_index = i as i64;
+    }
+}
+
+/// The listed items plus everything grouped with them. Errors on unknown ids.
+pub(crate) fn expand_selection(items: &[MoodboardItem], item_ids: &[String]) -> Result<HashSet<String>, String> {
+    if item_ids.is_empty() {
+        return Err("No items selected".to_string());
+    }
+    let mut groups = HashSet::new();
+    for id in item_ids {
+        let item = items
+            .iter()
+            .find(|i| i.id == *id)
+            .ok_or_else(|| format!("Item not found: {}", id))?;
+        if let Some(group_id) = &item.group_id {
+            groups.insert(group_id.clone());
+        }
+    }
+    Ok(items
+        .iter()
+        .filter(|i| item_ids.contains(&i.id) || i.group_id.as_ref().is_some_and(|g| groups.contains(g)))
+        .map(|i| i.id.clone())
+        .collect())
+}
+
+/// Clear group ids that no longer have at least two members.
+fn prune_groups(items: &mut [MoodboardItem]) {
+    let mut counts = std::collections::HashMap::new();
+    for group_id in items.iter().filter_map(|i| i.group_id.clone()) {
+        *counts.entry(group_id).or_insert(0usize) += 1;
+    }
+    for item in items.iter_mut() {
+        if item.group_id.as_ref().is_some_and(|g| counts[g] < 2) {
+            item.group_id = None;
+        }
+    }
+}
+
+/// Move items (and the rest of their groups) within the stacking order. Items are
+/// stored in paint order afterwards, with `zIndex` renumbered from 0.
+#[tauri::command]
+pub async fn reorder_moodboard_items(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    item_ids: Vec<String>,
+    action: LayerMove,
+) -> Result<Moodboard, String> {
+    modify_moodboard(&state, &moodboard_id, |board| {
+        let selected = expand_selection(&board.items, &item_ids)?;
+        sort_layers(&mut board.items);
+        let items = &mut board.items;
+        let is_selected = |item: &MoodboardItem| selected.contains(&item.id);
+        match action {
+            LayerMove::BringToFront => items.sort_by_key(|item| is_selected(item)),
+            LayerMove::SendToBack => items.sort_by_key(|item| !is_selected(item)),
+            // Step each selected run past the neighbour above (or below) it
+            LayerMove::BringForward => {
+                for i in (0..items.len().saturating_sub(1)).rev() {
+                    if is_selected(&items[i]) && !is_selected(&items[i + 1]) {
+                        items.swap(i, i + 1);
+                    }
+                }
+            }
+            LayerMove::SendBackward => {
+                for i in 1..items.len() {
+                    if is_selected(&items[i]) && !is_selected(&items[i - 1]) {
+                        items.swap(i, i - 1);
+                    }
+                }
+            }
+        }
+        renumber_layers(items);
+        Ok(())
+    })
+}
+
+/// Group two or more items. Items already in another group are moved into the new one.
+#[tauri::command]
+pub async fn group_moodboard_items(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    item_ids: Vec<String>,
+) -> Result<Moodboard, String> {
+    let unique: HashSet<&String> = item_ids.iter().collect();
+    if unique.len() < 2 {
+        return Err("Select at least two items to group".to_string());
+    }
+    modify_moodboard(&state, &moodboard_id, |board| {
+        if let Some(missing) = item_ids.iter().find(|id| !board.items.iter().any(|i| i.id == **id)) {
+            return Err(format!("Item not found: {}", missing));
+        }
+        let group_id = Uuid::new_v4().to_string();
+        for item in board.items.iter_mut().filter(|i| unique.contains(&i.id)) {
+            item.group_id = Some(group_id.clone());
+        }
+        prune_groups(&mut board.items);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn ungroup_moodboard_items(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    group_id: String,
+) -> Result<Moodboard, String> {
+    modify_moodboard(&state, &moodboard_id, |board| {
+        let mut found = false;
+        for item in board.items.iter_mut().filter(|i| i.group_id.as_deref() == Some(group_id.as_str())) {
+            item.group_id = None;
+            found = true;
+        }
+        if found { Ok(()) } else { Err("Group not found".to_string()) }
+    })
+}
+
+/// Set several properties on one or more items at once (see `apply_to` for locked
+/// items). Saved with the same delay as drags, for sliders.
+#[tauri::command]
+pub async fn set_moodboard_item_properties(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    item_ids: Vec<String>,
+    properties: MoodboardItemProperties,
+) -> Result<Moodboard, String> {
+    if item_ids.is_empty() {
+        return Err("No items selected".to_string());
+    }
+    properties.validate()?;
+    modify_moodboard_deferred(&state, &moodboard_id, |board| {
+        for id in &item_ids {
+            let item = board
+                .items
+                .iter_mut()
+                .find(|i| i.id == *id)
+                .ok_or_else(|| format!("Item not found: {}", id))?;
+            properties.apply_to(item)?;
+        }
+        Ok(())
+    })
+}
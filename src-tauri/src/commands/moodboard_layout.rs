@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+use serde::Deserialize;
+use tauri::State;
+
+use crate::models::{Moodboard, MoodboardItem};
+use crate::state::AppState;
+use crate::commands::moodboards::{modify_moodboard, read_moodboards};
+use crate::commands::moodboard_layers::expand_selection;
+use crate::commands::moodboard_snapshots::auto_snapshot;
+use crate::commands::moodboard_upload::{DROP_GAP, INITIAL_SIZE};
+use crate::commands::moodboard_export::item_extent;
+
+/// Same width that uploads fill before wrapping to a new row
+const DEFAULT_TARGET_WIDTH: f64 = 1200.0;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MoodboardLayoutStrategy {
+    /// Equal square cells; items are fitted inside and centred
+    Grid,
+    /// Equal-width columns; items are scaled to the column width
+    Masonry,
+    /// Items keep their size and are packed into the width (skyline)
+    Pack,
+}
+
+/// A box laid out as one piece: a single item or a whole group, around the items
+/// as rotated on the board.
+struct Unit {
+    indices: Vec<usize>,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+fn units_of(items: &[MoodboardItem], selected: impl Fn(&MoodboardItem) -> bool) -> Vec<Unit> {
+    let mut units: Vec<Unit> = Vec::new();
+    for (index, item) in items.iter().enumerate().filter(|(_, i)| selected(i)) {
+        let unit = item
+            .group_id
+            .as_ref()
+            .and_then(|g| units.iter_mut().find(|u| items[u.indices[0]].group_id.as_ref() == Some(g)));
+        let (left, top, right, bottom) = item_extent(item);
+        match unit {
+            Some(unit) => {
+                let right = (unit.x + unit.width).max(right);
+                let bottom = (unit.y + unit.height).max(bottom);
+                unit.x = unit.x.min(left);
+                unit.y = unit.y.min(top);
+                unit.width = right - unit.x;
+                unit.height = bottom - unit.y;
+                unit.indices.push(index);
+            }
+            None => units.push(Unit { indices: vec![index], x: left, y: top, width: right - left, height: bottom - top }),
+        }
+    }
+    // Locked items stay put, and so does the rest of their group
+    units.retain(|u| !u.indices.iter().any(|&i| items[i].locked) && u.width > 0.0 && u.height > 0.0);
+    units
+}
+
+/// First top edge at or below `y` where a `width` x `height` block at `x` clears every
+/// item that isn't being moved (by `spacing`).
+fn clear_of(obstacles: &[(f64, f64, f64, f64)], x: f64, mut y: f64, width: f64, height: f64, spacing: f64) -> f64 {
+    // Each step moves below one obstacle, so this ends
+    while let Some(&(_, _, _, bottom)) = obstacles.iter().find(|&&(left, top, right, bottom)| {
+        x < right + spacing && left < x + width + spacing && y < bottom + spacing && top < y + height + spacing
+    }) {
+        y = bottom + spacing;
+    }
+    y
+}
+
+/// Columns of about `INITIAL_SIZE` that fit in `target_width`, and their width.
+fn columns(target_width: f64, spacing: f64) -> (usize, f64) {
+    let count = (((target_width + spacing) / (INITIAL_SIZE + spacing)).floor() as usize).max(1);
+    (count, ((target_width - spacing * (count - 1) as f64) / count as f64).max(1.0))
+}
+
+fn grid(units: &[Unit], spacing: f64, target_width: f64) -> Vec<(f64, f64, f64)> {
+    let (count, cell) = columns(target_width, spacing);
+    units
+        .iter()
+        .enumerate()
+        .map(|(i, u)| {
+            let scale = (cell / u.width).min(cell / u.height);
+            let (col, row) = ((i % count) as f64, (i / count) as f64);
+            let x = col * (cell + spacing) + (cell - u.width * scale) / 2.0;
+            let y = row * (cell + spacing) + (cell - u.height * scale) / 2.0;
+            (x, y, scale)
+        })
+        .collect()
+}
+
+fn masonry(units: &[Unit], spacing: f64, target_width: f64) -> Vec<(f64, f64, f64)> {
+    let (count, column_width) = columns(target_width, spacing);
+    let mut heights = vec![0.0f64; count];
+    units
+        .iter()
+        .map(|u| {
+            let scale = column_width / u.width;
+            // Shortest column, leftmost on ties
+            let col = (0..count).fold(0, |best, c| if heights[c] < heights[best] { c } else { best });
+            let y = heights[col];
+            heights[col] += u.height * scale + spacing;
+            (col as f64 * (column_width + spacing), y, scale)
+        })
+        .collect()
+}
+
+fn pack(units: &[Unit], spacing: f64, target_width: f64) -> Vec<(f64, f64, f64)> {
+    // Tallest first packs tighter
+    let mut order: Vec<usize> = (0..units.len()).collect();
+    order.sort_by(|&a, &b| units[b].height.total_cmp(&units[a].height).then(units[b].width.total_cmp(&units[a].width)));
+
+    // Skyline: (x, width, top) segments covering the whole width, left to right
+    let mut skyline = vec![(0.0, target_width, 0.0)];
+    let mut placed = vec![(0.0, 0.0, 1.0); units.len()];
+    for index in order {
+        let (w, h) = (units[index].width + spacing, units[index].height + spacing);
+        // Lowest spot where the box fits, leftmost on ties; too-wide boxes go at x = 0
+        let mut best: Option<(f64, f64)> = None;
+        for start in 0..skyline.len() {
+            let x = skyline[start].0;
+            if x > 0.0 && x + w > target_width + spacing {
+                break;
+            }
+            let top = skyline[start..]
+                .iter()
+                .take_while(|s| s.0 < x + w)
+                .fold(0.0f64, |top, s| top.max(s.2));
+            if best.is_none_or(|(_, y)| top < y) {
+                best = Some((x, top));
+            }
+        }
+        let (x, y) = best.unwrap_or((0.0, 0.0));
+        placed[index] = (x, y, 1.0);
+
+        // Raise the skyline under the box
+        let right = x + w;
+        let mut next = Vec::with_capacity(skyline.len() + 2);
+        for &(sx, sw, top) in &skyline {
+            let end = sx + sw;
+            if end <= x || sx >= right {
+                next.push((sx, sw, top));
+                continue;
+            }
+            if sx < x {
+                next.push((sx, x - sx, top));
+            }
+            if end > right {
+                next.push((right, end - right, top));
+            }
+        }
+        next.push((x, w, y + h));
+        next.sort_by(|a, b| a.0.total_cmp(&b.0));
+        skyline = next;
+    }
+    placed
+}
+
+/// Arrange items automatically: a uniform `grid`, `masonry` columns, or `pack`
+/// (sizes kept, packed into the width). `spacing` defaults to 16 and `targetWidth`
+/// to 1200 board pixels. With `itemIds` only those items (and their groups) move;
+/// groups are laid out as one block and locked items stay where they are. Sizes are
+/// taken as rotated on the board. The layout starts at the top-left of the items it
+/// arranges, moved down past any item it would cover that stays put, and is saved
+/// in one write, after an automatic snapshot.
+#[tauri::command]
+pub async fn auto_layout_moodboard(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    strategy: MoodboardLayoutStrategy,
+    spacing: Option<f64>,
+    target_width: Option<f64>,
+    item_ids: Option<Vec<String>>,
+) -> Result<Moodboard, String> {
+    let spacing = spacing.unwrap_or(DROP_GAP);
+    let target_width = target_width.unwrap_or(DEFAULT_TARGET_WIDTH);
+    if !spacing.is_finite() || spacing < 0.0 {
+        return Err("Spacing must be zero or more".to_string());
+    }
+    if !target_width.is_finite() || target_width <= 0.0 {
+        return Err("Target width must be greater than zero".to_string());
+    }
+
+    let board = read_moodboards(&state)?
+        .into_iter()
+        .find(|m| m.id == moodboard_id)
+        .ok_or_else(|| "Moodboard not found".to_string())?;
+    let selected = match &item_ids {
+        Some(ids) => Some(expand_selection(&board.items, ids)?),
+        None => None,
+    };
+    let is_selected = |item: &MoodboardItem| selected.as_ref().is_none_or(|s| s.contains(&item.id));
+    if units_of(&board.items, is_selected).is_empty() {
+        return Ok(board);
+    }
+
+    auto_snapshot(&state, &moodboard_id, "Before auto layout")?;
+    modify_moodboard(&state, &moodboard_id, |board| {
+        let mut units = units_of(&board.items, is_selected);
+        // Reading order, so rows follow roughly where things were
+        units.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+        let placements = match strategy {
+            MoodboardLayoutStrategy::Grid => grid(&units, spacing, target_width),
+            MoodboardLayoutStrategy::Masonry => masonry(&units, spacing, target_width),
+            MoodboardLayoutStrategy::Pack => pack(&units, spacing, target_width),
+        };
+
+        // Locked and unselected items stay where they are; keep the layout off them
+        let moving: HashSet<usize> = units.iter().flat_map(|u| u.indices.iter().copied()).collect();
+        let obstacles: Vec<(f64, f64, f64, f64)> = board
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(i, item)| !moving.contains(i) && item.width > 0.0 && item.height > 0.0)
+            .map(|(_, item)| item_extent(item))
+            .collect();
+        let layout_width = units.iter().zip(&placements).map(|(u, p)| p.0 + u.width * p.2).fold(0.0, f64::max);
+        let layout_height = units.iter().zip(&placements).map(|(u, p)| p.1 + u.height * p.2).fold(0.0, f64::max);
+        let origin_x = units.iter().map(|u| u.x).fold(f64::INFINITY, f64::min);
+        let origin_y = units.iter().map(|u| u.y).fold(f64::INFINITY, f64::min);
+        let origin_y = clear_of(&obstacles, origin_x, origin_y, layout_width, layout_height, spacing);
+
+        for (unit, (x, y, scale)) in units.iter().zip(placements) {
+            // Members keep their place within the group, scaled with it (by centre,
+            // so rotated items stay inside the unit's box)
+            for &i in &unit.indices {
+                let item = &mut board.items[i];
+                let cx = origin_x + x + (item.x + item.width / 2.0 - unit.x) * scale;
+                let cy = origin_y + y + (item.y + item.height / 2.0 - unit.y) * scale;
+                item.width *= scale;
+                item.height *= scale;
+                item.x = cx - item.width / 2.0;
+                item.y = cy - item.height / 2.0;
+            }
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,84 @@
+use tauri::State;
+
+use crate::models::{MoodboardItem, Reference};
+use crate::state::AppState;
+use crate::commands::moodboards::{modify_moodboard, modify_moodboards, read_moodboards};
+use crate::commands::references::adjustments::render_reference;
+use crate::commands::references::crud::find_reference;
+
+/// Longest side of a newly placed reference item, in board pixels
+const REFERENCE_ITEM_SIZE: f64 = 200.0;
+
+/// Apply `f` to every item showing one of `reference_ids`, saving only when a
+/// board has such items.
+fn update_reference_items(
+    state: &AppState,
+    reference_ids: &[String],
+    f: impl Fn(&mut MoodboardItem),
+) -> Result<(), String> {
+    let shows = |item: &MoodboardItem| item.reference_id.as_ref().is_some_and(|id| reference_ids.contains(id));
+    if !read_moodboards(state)?.iter().any(|m| m.items.iter().any(shows)) {
+        return Ok(());
+    }
+    modify_moodboards(state, false, |moodboards| {
+        let now = chrono::Utc::now().timestamp_millis();
+        for moodboard in moodboards.iter_mut() {
+            let mut changed = false;
+            for item in moodboard.items.iter_mut().filter(|i| shows(i)) {
+                f(item);
+                changed = true;
+            }
+            if changed {
+                moodboard.updated_at = Some(now);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Point items showing `reference` at its current file (after a move).
+pub(crate) fn sync_reference_items(state: &AppState, reference: &Reference) -> Result<(), String> {
+    update_reference_items(state, std::slice::from_ref(&reference.id), |item| {
+        item.url = Some(reference.url.clone());
+        item.filename = Some(reference.filename.clone());
+        item.reference_missing = false;
+    })
+}
+
+/// Flag items whose reference was deleted. They keep their place on the board.
+pub(crate) fn mark_reference_items_missing(state: &AppState, reference_ids: &[String]) -> Result<(), String> {
+    update_reference_items(state, reference_ids, |item| item.reference_missing = true)
+}
+
+/// Place a library reference on a board without copying its file. The item is drawn
+/// from the reference, so later crops and rotations show up on the board too.
+#[tauri::command]
+pub async fn add_reference_to_moodboard(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    reference_id: String,
+    x: Option<f64>,
+    y: Option<f64>,
+) -> Result<MoodboardItem, String> {
+    let reference = find_reference(&state, &reference_id)?;
+    // Size from the image as displayed (rotation and crop applied)
+    let rendered = render_reference(&state, &reference)?;
+    let (w, h) = (rendered.width() as f64, rendered.height() as f64);
+    let fit = REFERENCE_ITEM_SIZE / w.max(h).max(1.0);
+
+    let mut item = MoodboardItem::new("reference", x.unwrap_or(100.0), y.unwrap_or(100.0), w * fit, h * fit);
+    item.content = Some(reference.original_name.clone());
+    item.url = Some(reference.url.clone());
+    item.filename = Some(reference.filename.clone());
+    item.original_width = Some(w);
+    item.original_height = Some(h);
+    item.aspect_ratio = Some(w / h.max(1.0));
+    item.reference_id = Some(reference.id.clone());
+
+    modify_moodboard(&state, &moodboard_id, |board| {
+        item.z_index = board.items.iter().map(|i| i.z_index + 1).max().unwrap_or(0);
+        board.items.push(item.clone());
+        Ok(())
+    })?;
+    Ok(item)
+}
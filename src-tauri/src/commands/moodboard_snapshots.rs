@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::models::{Moodboard, MoodboardItem, MoodboardSnapshot};
+use crate::state::AppState;
+use crate::commands::moodboards::{modify_moodboard, read_moodboards};
+
+/// Automatic snapshots kept per board; named ones are never pruned
+const MAX_AUTOMATIC_SNAPSHOTS: usize = 20;
+
+fn snapshots_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("app_data").join("moodboard_snapshots.json")
+}
+
+/// All boards' snapshots, newest first.
+pub(crate) fn read_snapshots(state: &AppState) -> Result<Vec<MoodboardSnapshot>, String> {
+    let path = snapshots_path(state);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read moodboard snapshots: {}", e))?;
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse moodboard snapshots: {}", e))
+}
+
+fn write_snapshots(state: &AppState, snapshots: &[MoodboardSnapshot]) -> Result<(), String> {
+    let path = snapshots_path(state);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app_data directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(snapshots)
+        .map_err(|e| format!("Failed to serialize moodboard snapshots: {}", e))?;
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write moodboard snapshots: {}", e))
+}
+
+fn find_board(state: &AppState, moodboard_id: &str) -> Result<Moodboard, String> {
+    read_moodboards(state)?
+        .into_iter()
+        .find(|m| m.id == moodboard_id)
+        .ok_or_else(|| "Moodboard not found".to_string())
+}
+
+fn find_snapshot(state: &AppState, moodboard_id: &str, snapshot_id: &str) -> Result<MoodboardSnapshot, String> {
+    read_snapshots(state)?
+        .into_iter()
+        .find(|s| s.id == snapshot_id && s.moodboard_id == moodboard_id)
+        .ok_or_else(|| "Snapshot not found".to_string())
+}
+
+fn same_items(a: &[MoodboardItem], b: &[MoodboardItem]) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn save_snapshot(state: &AppState, board: &Moodboard, name: String, automatic: bool) -> Result<MoodboardSnapshot, String> {
+    let mut snapshots = read_snapshots(state)?;
+    if automatic {
+        // Nothing changed since the board's last snapshot
+        if let Some(latest) = snapshots.iter().find(|s| s.moodboard_id == board.id) {
+            if same_items(&latest.items, &board.items) {
+                return Ok(latest.clone());
+            }
+        }
+    }
+    let snapshot = MoodboardSnapshot {
+        id: Uuid::new_v4().to_string(),
+        moodboard_id: board.id.clone(),
+        name,
+        automatic,
+        items: board.items.clone(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    snapshots.insert(0, snapshot.clone());
+    let mut kept = 0;
+    snapshots.retain(|s| {
+        if s.moodboard_id != board.id || !s.automatic {
+            return true;
+        }
+        kept += 1;
+        kept <= MAX_AUTOMATIC_SNAPSHOTS
+    });
+    write_snapshots(state, &snapshots)?;
+    Ok(snapshot)
+}
+
+/// Snapshot a board before a destructive edit. Failing to save it stops the edit.
+pub(crate) fn auto_snapshot(state: &AppState, moodboard_id: &str, name: &str) -> Result<(), String> {
+    let board = find_board(state, moodboard_id)?;
+    save_snapshot(state, &board, name.to_string(), true).map(|_| ())
+}
+
+/// Drop the snapshots of a deleted board.
+pub(crate) fn delete_board_snapshots(state: &AppState, moodboard_id: &str) -> Result<(), String> {
+    let mut snapshots = read_snapshots(state)?;
+    let before = snapshots.len();
+    snapshots.retain(|s| s.moodboard_id != moodboard_id);
+    if snapshots.len() == before {
+        return Ok(());
+    }
+    write_snapshots(state, &snapshots)
+}
+
+/// Whether any snapshot still shows the file at `url`.
+pub(crate) fn snapshot_uses_file(state: &AppState, url: &str) -> Result<bool, String> {
+    Ok(read_snapshots(state)?
+        .iter()
+        .any(|s| s.items.iter().any(|i| i.url.as_deref() == Some(url))))
+}
+
+/// A snapshot without its items, for listing.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardSnapshotInfo {
+    pub id: String,
+    pub moodboard_id: String,
+    pub name: String,
+    pub automatic: bool,
+    pub item_count: usize,
+    pub created_at: i64,
+}
+
+impl From<MoodboardSnapshot> for MoodboardSnapshotInfo {
+    fn from(s: MoodboardSnapshot) -> Self {
+        Self {
+            item_count: s.items.len(),
+            id: s.id,
+            moodboard_id: s.moodboard_id,
+            name: s.name,
+            automatic: s.automatic,
+            created_at: s.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardItemBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub rotation: f64,
+}
+
+impl MoodboardItemBox {
+    fn of(item: &MoodboardItem) -> Self {
+        Self { x: item.x, y: item.y, width: item.width, height: item.height, rotation: item.rotation }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardItemMove {
+    pub id: String,
+    pub before: MoodboardItemBox,
+    pub after: MoodboardItemBox,
+}
+
+/// Changes from a snapshot to the board (or to a later snapshot). Items are matched
+/// by id; `moved` covers position, size and rotation, `changed` lists items whose
+/// other properties (content, colour, layer, opacity, ...) differ.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodboardSnapshotDiff {
+    pub added: Vec<MoodboardItem>,
+    pub removed: Vec<MoodboardItem>,
+    pub moved: Vec<MoodboardItemMove>,
+    pub changed: Vec<String>,
+}
+
+fn diff_items(before: &[MoodboardItem], after: &[MoodboardItem]) -> MoodboardSnapshotDiff {
+    let old: HashMap<&str, &MoodboardItem> = before.iter().map(|i| (i.id.as_str(), i)).collect();
+    let new: HashMap<&str, &MoodboardItem> = after.iter().map(|i| (i.id.as_str(), i)).collect();
+    let mut diff = MoodboardSnapshotDiff {
+        added: after.iter().filter(|i| !old.contains_key(i.id.as_str())).cloned().collect(),
+        removed: before.iter().filter(|i| !new.contains_key(i.id.as_str())).cloned().collect(),
+        moved: Vec::new(),
+        changed: Vec::new(),
+    };
+    for item in after {
+        let Some(prev) = old.get(item.id.as_str()) else {
+            continue;
+        };
+        let (from, to) = (MoodboardItemBox::of(prev), MoodboardItemBox::of(item));
+        if (from.x, from.y, from.width, from.height, from.rotation) != (to.x, to.y, to.width, to.height, to.rotation) {
+            diff.moved.push(MoodboardItemMove { id: item.id.clone(), before: from, after: to });
+        }
+        // Compare everything else with the box taken out
+        let mut a = (*prev).clone();
+        let mut b = item.clone();
+        for i in [&mut a, &mut b] {
+            (i.x, i.y, i.width, i.height, i.rotation) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        }
+        if !same_items(std::slice::from_ref(&a), std::slice::from_ref(&b)) {
+            diff.changed.push(item.id.clone());
+        }
+    }
+    diff
+}
+
+/// Save the board's current items under `name`.
+#[tauri::command]
+pub async fn create_moodboard_snapshot(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    name: String,
+) -> Result<MoodboardSnapshotInfo, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Snapshot name cannot be empty".to_string());
+    }
+    let board = find_board(&state, &moodboard_id)?;
+    save_snapshot(&state, &board, name, false).map(MoodboardSnapshotInfo::from)
+}
+
+/// A board's snapshots, newest first.
+#[tauri::command]
+pub async fn get_moodboard_snapshots(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+) -> Result<Vec<MoodboardSnapshotInfo>, String> {
+    Ok(read_snapshots(&state)?
+        .into_iter()
+        .filter(|s| s.moodboard_id == moodboard_id)
+        .map(MoodboardSnapshotInfo::from)
+        .collect())
+}
+
+/// What changed since `snapshot_id`: compared with the board as it is now, or with
+/// snapshot `compare_to`.
+#[tauri::command]
+pub async fn diff_moodboard_snapshot(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    snapshot_id: String,
+    compare_to: Option<String>,
+) -> Result<MoodboardSnapshotDiff, String> {
+    let snapshot = find_snapshot(&state, &moodboard_id, &snapshot_id)?;
+    let after = match compare_to {
+        Some(other) => find_snapshot(&state, &moodboard_id, &other)?.items,
+        None => find_board(&state, &moodboard_id)?.items,
+    };
+    Ok(diff_items(&snapshot.items, &after))
+}
+
+/// The board as it was at the snapshot, for display only; nothing is saved.
+#[tauri::command]
+pub async fn preview_moodboard_snapshot(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    snapshot_id: String,
+) -> Result<Moodboard, String> {
+    let snapshot = find_snapshot(&state, &moodboard_id, &snapshot_id)?;
+    let mut board = find_board(&state, &moodboard_id)?;
+    board.items = snapshot.items;
+    board.updated_at = Some(snapshot.created_at);
+    Ok(board)
+}
+
+/// Put the snapshot's items back on the board. The current items are snapshotted
+/// first, so a restore can be undone.
+#[tauri::command]
+pub async fn restore_moodboard_snapshot(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    snapshot_id: String,
+) -> Result<Moodboard, String> {
+    let snapshot = find_snapshot(&state, &moodboard_id, &snapshot_id)?;
+    auto_snapshot(&state, &moodboard_id, &format!("Before restoring \"{}\"", snapshot.name))?;
+    modify_moodboard(&state, &moodboard_id, |board| {
+        board.items = snapshot.items;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn delete_moodboard_snapshot(
+    state: State<'_, AppState>,
+    snapshot_id: String,
+) -> Result<(), String> {
+    let mut snapshots = read_snapshots(&state)?;
+    let before = snapshots.len();
+    snapshots.retain(|s| s.id != snapshot_id);
+    if snapshots.len() == before {
+        return Err("Snapshot not found".to_string());
+    }
+    write_snapshots(&state, &snapshots)
+}
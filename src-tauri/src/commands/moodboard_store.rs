@@ -0,0 +1,170 @@
+//! Write-behind cache for `moodboards.json`.
+//!
+//! Every moodboard read and write goes through here. Immediate writes save right
+//! away; deferred writes (drags, nudges) only update the cache and are saved by a
+//! background flusher once edits pause, so a burst of updates costs one write.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+
+use crate::models::Moodboard;
+use crate::state::AppState;
+
+/// Save once edits have been quiet this long
+const QUIET_PERIOD: Duration = Duration::from_millis(500);
+/// ...but never hold unsaved edits longer than this during a continuous drag
+const MAX_DELAY: Duration = Duration::from_secs(3);
+const FLUSH_TICK: Duration = Duration::from_millis(100);
+
+struct MoodboardCache {
+    path: PathBuf,
+    moodboards: Vec<Moodboard>,
+    /// First and latest deferred change not yet on disk
+    dirty: Option<(Instant, Instant)>,
+}
+
+static CACHE: Lazy<Mutex<Option<MoodboardCache>>> = Lazy::new(|| Mutex::new(None));
+static FLUSHER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn moodboards_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("app_data").join("moodboards.json")
+}
+
+// Helper function to safely write moodboards with backup
+fn write_moodboards_with_backup(file_path: &Path, moodboards: &[Moodboard]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(moodboards)
+        .map_err(|e| format!("Failed to serialize moodboards: {}", e))?;
+
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app_data directory: {}", e))?;
+    }
+
+    // Create backup before writing
+    let backup_path = file_path.with_extension("json.backup");
+    if file_path.exists() {
+        fs::copy(file_path, &backup_path)
+            .map_err(|e| format!("Failed to create backup: {}", e))?;
+    }
+
+    // Write new content
+    fs::write(file_path, content)
+        .map_err(|e| format!("Failed to write moodboards: {}", e))?;
+
+    Ok(())
+}
+
+/// A missing or empty file means no moodboards yet.
+fn load_moodboards(file_path: &Path) -> Result<Vec<Moodboard>, String> {
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Failed to read moodboards: {}", e))?;
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse moodboards: {}", e))
+}
+
+fn flush_cache(cache: &mut MoodboardCache) -> Result<(), String> {
+    if cache.dirty.is_some() {
+        write_moodboards_with_backup(&cache.path, &cache.moodboards)?;
+        cache.dirty = None;
+    }
+    Ok(())
+}
+
+/// Run `f` on the cached moodboards of `state`, loading them first. A cache for
+/// another data directory is saved and replaced.
+fn with_cache<T>(state: &AppState, f: impl FnOnce(&mut MoodboardCache) -> Result<T, String>) -> Result<T, String> {
+    let path = moodboards_path(state);
+    let mut guard = CACHE.lock().map_err(|_| "Moodboard cache is poisoned".to_string())?;
+    if guard.as_ref().is_none_or(|c| c.path != path) {
+        if let Some(old) = guard.as_mut() {
+            flush_cache(old)?;
+        }
+        *guard = Some(MoodboardCache { moodboards: load_moodboards(&path)?, path, dirty: None });
+    }
+    f(guard.as_mut().expect("cache loaded above"))
+}
+
+/// Start the background flusher unless it is running. It exits once nothing is
+/// pending; the flag is cleared under the cache lock, so a deferred write made
+/// after that starts a new one.
+fn start_flusher() {
+    if FLUSHER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    thread::spawn(|| loop {
+        thread::sleep(FLUSH_TICK);
+        let mut guard = match CACHE.lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                FLUSHER_RUNNING.store(false, Ordering::SeqCst);
+                break;
+            }
+        };
+        let dirty = guard.as_ref().and_then(|c| c.dirty);
+        let (first, last) = match dirty {
+            Some(dirty) => dirty,
+            None => {
+                FLUSHER_RUNNING.store(false, Ordering::SeqCst);
+                break;
+            }
+        };
+        if last.elapsed() >= QUIET_PERIOD || first.elapsed() >= MAX_DELAY {
+            if let Some(cache) = guard.as_mut() {
+                if let Err(e) = flush_cache(cache) {
+                    // Stays dirty and is retried on the next tick
+                    eprintln!("Failed to save moodboards: {}", e);
+                }
+            }
+        }
+    });
+}
+
+pub(crate) fn read_moodboards(state: &AppState) -> Result<Vec<Moodboard>, String> {
+    with_cache(state, |cache| Ok(cache.moodboards.clone()))
+}
+
+/// Apply `f` to the cached moodboards as one step: nothing changes when it fails.
+/// `deferred` leaves saving to the flusher.
+pub(crate) fn modify_moodboards<T>(
+    state: &AppState,
+    deferred: bool,
+    f: impl FnOnce(&mut Vec<Moodboard>) -> Result<T, String>,
+) -> Result<T, String> {
+    let result = with_cache(state, |cache| {
+        let mut moodboards = cache.moodboards.clone();
+        let result = f(&mut moodboards)?;
+        if deferred {
+            let now = Instant::now();
+            cache.dirty = Some((cache.dirty.map_or(now, |(first, _)| first), now));
+        } else {
+            write_moodboards_with_backup(&cache.path, &moodboards)?;
+            cache.dirty = None;
+        }
+        cache.moodboards = moodboards;
+        Ok(result)
+    })?;
+    if deferred {
+        start_flusher();
+    }
+    Ok(result)
+}
+
+/// Save deferred edits now (drag end, app exit).
+pub fn flush_pending_moodboards() -> Result<(), String> {
+    let mut guard = CACHE.lock().map_err(|_| "Moodboard cache is poisoned".to_string())?;
+    match guard.as_mut() {
+        Some(cache) => flush_cache(cache),
+        None => Ok(()),
+    }
+}
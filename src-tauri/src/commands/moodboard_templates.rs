@@ -0,0 +1,279 @@
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::models::{Moodboard, MoodboardItem, MoodboardTemplate};
+use crate::state::AppState;
+use crate::commands::moodboard_upload::{image_dimensions, store_moodboard_image};
+use crate::commands::moodboards::{copy_items, modify_moodboard, modify_moodboards, new_moodboard_id, read_moodboards};
+
+fn templates_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("app_data").join("moodboard_templates.json")
+}
+
+/// User-saved templates; built-ins are not stored.
+fn read_templates(state: &AppState) -> Result<Vec<MoodboardTemplate>, String> {
+    let path = templates_path(state);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read moodboard templates: {}", e))?;
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse moodboard templates: {}", e))
+}
+
+fn write_templates(state: &AppState, templates: &[MoodboardTemplate]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(templates)
+        .map_err(|e| format!("Failed to serialize moodboard templates: {}", e))?;
+    fs::write(templates_path(state), content)
+        .map_err(|e| format!("Failed to write moodboard templates: {}", e))
+}
+
+fn placeholder(label: &str, x: f64, y: f64, width: f64, height: f64) -> MoodboardItem {
+    let mut item = MoodboardItem::new("placeholder", x, y, width, height);
+    item.content = Some(label.to_string());
+    item
+}
+
+fn text(content: &str, font_size: f64, x: f64, y: f64, width: f64, height: f64) -> MoodboardItem {
+    let mut item = MoodboardItem::new("text", x, y, width, height);
+    item.content = Some(content.to_string());
+    item.font_size = Some(font_size);
+    item
+}
+
+fn swatch(color: &str, x: f64, y: f64, width: f64, height: f64) -> MoodboardItem {
+    let mut item = MoodboardItem::new("color", x, y, width, height);
+    item.content = Some(color.to_string());
+    item
+}
+
+fn built_in(id: &str, name: &str, description: &str, mut items: Vec<MoodboardItem>) -> MoodboardTemplate {
+    for (i, item) in items.iter_mut().enumerate() {
+        item.z_index = i as i64;
+        item.created_at = None;
+    }
+    MoodboardTemplate {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        built_in: true,
+        items,
+        created_at: 0,
+    }
+}
+
+/// Layouts that ship with the app.
+fn built_in_templates() -> Vec<MoodboardTemplate> {
+    let mut character = vec![text("Character name", 28.0, 40.0, 40.0, 520.0, 70.0)];
+    for (i, view) in ["Front", "Side", "Back", "Three-quarter"].iter().enumerate() {
+        character.push(placeholder(view, 40.0 + i as f64 * 280.0, 130.0, 260.0, 420.0));
+    }
+    for (i, expression) in ["Neutral", "Happy", "Angry", "Surprised"].iter().enumerate() {
+        character.push(placeholder(expression, 40.0 + i as f64 * 220.0, 580.0, 200.0, 200.0));
+    }
+    for (i, color) in ["#e8b796", "#4a3226", "#2f4858", "#86bbd8", "#f6ae2d"].iter().enumerate() {
+        character.push(swatch(color, 940.0 + i as f64 * 56.0, 580.0, 48.0, 48.0));
+    }
+    character.push(text("Notes: personality, props, silhouette", 14.0, 940.0, 650.0, 272.0, 130.0));
+
+    let mut environment = vec![placeholder("Key image", 40.0, 40.0, 720.0, 405.0)];
+    for (i, mood) in ["Lighting", "Materials", "Atmosphere"].iter().enumerate() {
+        environment.push(placeholder(mood, 40.0 + i as f64 * 244.0, 465.0, 232.0, 160.0));
+    }
+    for (i, color) in ["#9fc5e8", "#6d8a5b", "#3e4f3a", "#c2a878", "#7a6a58", "#2b2d42"].iter().enumerate() {
+        environment.push(swatch(color, 800.0, 40.0 + i as f64 * 76.0, 160.0, 64.0));
+    }
+    environment.push(text("Time of day, weather, mood", 14.0, 800.0, 505.0, 160.0, 120.0));
+
+    let mut grid = Vec::new();
+    for row in 0..3 {
+        for col in 0..3 {
+            let n = row * 3 + col + 1;
+            grid.push(placeholder(&n.to_string(), 40.0 + col as f64 * 256.0, 40.0 + row as f64 * 256.0, 240.0, 240.0));
+        }
+    }
+
+    vec![
+        built_in("builtin-character-sheet", "Character sheet", "Turnaround, expressions, palette and notes", character),
+        built_in("builtin-environment-palette", "Environment palette", "Key image, mood shots and colour swatches", environment),
+        built_in("builtin-grid-3x3", "3×3 grid", "Nine equal image slots", grid),
+    ]
+}
+
+fn find_template(state: &AppState, template_id: &str) -> Result<MoodboardTemplate, String> {
+    built_in_templates()
+        .into_iter()
+        .chain(read_templates(state)?)
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| "Template not found".to_string())
+}
+
+/// Built-in templates first, then saved ones (newest first).
+#[tauri::command]
+pub async fn get_moodboard_templates(
+    state: State<'_, AppState>,
+) -> Result<Vec<MoodboardTemplate>, String> {
+    let mut templates = built_in_templates();
+    templates.extend(read_templates(&state)?);
+    Ok(templates)
+}
+
+/// Save a board's layout as a template. Images become placeholders labelled with
+/// their original names; text and colour items are kept as they are.
+#[tauri::command]
+pub async fn save_moodboard_as_template(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    name: String,
+    description: Option<String>,
+) -> Result<MoodboardTemplate, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    let board = read_moodboards(&state)?
+        .into_iter()
+        .find(|m| m.id == moodboard_id)
+        .ok_or_else(|| "Moodboard not found".to_string())?;
+    if board.items.is_empty() {
+        return Err("Moodboard is empty".to_string());
+    }
+
+    let items = copy_items(&board.items, &board.id, &board.id)
+        .into_iter()
+        .map(|item| match item.item_type.as_str() {
+            "text" | "color" | "placeholder" => item,
+            _ => {
+                let label = item
+                    .content
+                    .as_deref()
+                    .map(|c| c.rsplit_once('.').map_or(c, |(stem, _)| stem).to_string())
+                    .unwrap_or_default();
+                let mut slot = placeholder(&label, item.x, item.y, item.width, item.height).with_layout_of(&item);
+                slot.id = item.id;
+                slot
+            }
+        })
+        .collect();
+
+    let template = MoodboardTemplate {
+        id: Uuid::new_v4().to_string(),
+        name,
+        description: description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()),
+        built_in: false,
+        items,
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let mut templates = read_templates(&state)?;
+    templates.insert(0, template.clone());
+    write_templates(&state, &templates)?;
+    Ok(template)
+}
+
+#[tauri::command]
+pub async fn delete_moodboard_template(
+    state: State<'_, AppState>,
+    template_id: String,
+) -> Result<(), String> {
+    if built_in_templates().iter().any(|t| t.id == template_id) {
+        return Err("Built-in templates can't be deleted".to_string());
+    }
+    let mut templates = read_templates(&state)?;
+    let before = templates.len();
+    templates.retain(|t| t.id != template_id);
+    if templates.len() == before {
+        return Err("Template not found".to_string());
+    }
+    write_templates(&state, &templates)
+}
+
+/// New board laid out like the template; its placeholders wait for images
+/// (`fill_moodboard_placeholder`).
+#[tauri::command]
+pub async fn create_moodboard_from_template(
+    state: State<'_, AppState>,
+    template_id: String,
+    title: Option<String>,
+) -> Result<Moodboard, String> {
+    let template = find_template(&state, &template_id)?;
+    modify_moodboards(&state, false, |moodboards| {
+        let now = chrono::Utc::now().timestamp_millis();
+        let id = new_moodboard_id(moodboards);
+        let moodboard = Moodboard {
+            items: copy_items(&template.items, "", &id),
+            id,
+            title: title
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| template.name.clone()),
+            created_at: now,
+            updated_at: Some(now),
+        };
+        moodboards.insert(0, moodboard.clone());
+        Ok(moodboard)
+    })
+}
+
+/// Put an image into a placeholder. The image is fitted inside the placeholder's
+/// box and keeps its layer, rotation and group.
+#[tauri::command]
+pub async fn fill_moodboard_placeholder(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    item_id: String,
+    filename: String,
+    original_name: String,
+    data: Vec<u8>,
+) -> Result<Moodboard, String> {
+    let is_placeholder = read_moodboards(&state)?
+        .iter()
+        .find(|m| m.id == moodboard_id)
+        .ok_or_else(|| "Moodboard not found".to_string())?
+        .items
+        .iter()
+        .any(|i| i.id == item_id && i.item_type == "placeholder");
+    if !is_placeholder {
+        return Err("Placeholder not found".to_string());
+    }
+
+    let mut image = store_moodboard_image(&state, &moodboard_id, filename, original_name, &data, &[])?;
+    let stored_file = image.url.clone();
+    let result = modify_moodboard(&state, &moodboard_id, |board| {
+        let slot = board
+            .items
+            .iter_mut()
+            .find(|i| i.id == item_id && i.item_type == "placeholder")
+            .ok_or_else(|| "Placeholder not found".to_string())?;
+        let (mut width, mut height) = (slot.width, slot.height);
+        if let Some((w, h)) = image_dimensions(&data) {
+            let aspect = w as f64 / h.max(1) as f64;
+            image.original_width = Some(w as f64);
+            image.original_height = Some(h as f64);
+            image.aspect_ratio = Some(aspect);
+            if aspect > slot.width / slot.height {
+                height = slot.width / aspect;
+            } else {
+                width = slot.height * aspect;
+            }
+        }
+        image.x = slot.x + (slot.width - width) / 2.0;
+        image.y = slot.y + (slot.height - height) / 2.0;
+        image.width = width;
+        image.height = height;
+        image.id = slot.id.clone();
+        *slot = image.with_layout_of(slot);
+        Ok(())
+    });
+    if result.is_err() {
+        if let Some(sub) = stored_file.as_deref().and_then(|u| u.strip_prefix("moodboards/")) {
+            let _ = fs::remove_file(state.library_dir.join("Moodboards").join(sub));
+        }
+    }
+    result
+}
@@ -1,22 +1,78 @@
 use std::fs;
 use tauri::State;
-use uuid::Uuid;
-use chrono::Utc;
+use crate::imaging::{extract_palette, load_oriented, to_hex_color};
 use crate::models::MoodboardItem;
 use crate::state::AppState;
+use crate::commands::moodboards::read_moodboards;
 
-#[tauri::command]
-pub async fn upload_moodboard_image(
-    state: State<'_, AppState>,
-    moodboard_id: String,
+/// Longest side of a newly uploaded image, in board pixels
+pub(crate) const INITIAL_SIZE: f64 = 240.0;
+const PALETTE_SIZE: usize = 5;
+/// Space kept between a dropped item and its neighbours
+pub(crate) const DROP_GAP: f64 = 16.0;
+/// Where the first item goes; also where rows start
+const DROP_ORIGIN: (f64, f64) = (100.0, 100.0);
+/// Rows wrap once they would pass this x (or the board's right edge, if further)
+const DROP_ROW_LIMIT: f64 = 1300.0;
+
+fn overlaps(items: &[MoodboardItem], x: f64, y: f64, width: f64, height: f64) -> bool {
+    items.iter().any(|i| {
+        x < i.x + i.width + DROP_GAP
+            && i.x < x + width + DROP_GAP
+            && y < i.y + i.height + DROP_GAP
+            && i.y < y + height + DROP_GAP
+    })
+}
+
+/// Top-left corner for a `width` x `height` item that doesn't overlap `items`:
+/// the first free spot in reading order beside or below an existing item, or a
+/// new row under everything.
+pub(crate) fn free_position(items: &[MoodboardItem], width: f64, height: f64) -> (f64, f64) {
+    if !overlaps(items, DROP_ORIGIN.0, DROP_ORIGIN.1, width, height) {
+        return DROP_ORIGIN;
+    }
+    let left = items.iter().map(|i| i.x).fold(DROP_ORIGIN.0, f64::min);
+    let right_limit = items.iter().map(|i| i.x + i.width).fold(DROP_ROW_LIMIT, f64::max);
+    let mut candidates: Vec<(f64, f64)> = items
+        .iter()
+        .flat_map(|i| [(i.x + i.width + DROP_GAP, i.y), (i.x, i.y + i.height + DROP_GAP), (left, i.y + i.height + DROP_GAP)])
+        .filter(|&(x, _)| x + width <= right_limit)
+        .collect();
+    candidates.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.total_cmp(&b.0)));
+    candidates
+        .into_iter()
+        .find(|&(x, y)| !overlaps(items, x, y, width, height))
+        .unwrap_or_else(|| {
+            let bottom = items.iter().map(|i| i.y + i.height).fold(DROP_ORIGIN.1, f64::max);
+            (left, bottom + DROP_GAP)
+        })
+}
+
+/// Pixel size of encoded image data, read from its header.
+pub(crate) fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    image::io::Reader::new(std::io::Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// Write an image into the moodboard's library folder and build its item: real size
+/// and palette from the decoded image, scaled to a starting size and placed where it
+/// doesn't cover existing items or `placed` (items not saved yet, such as earlier files
+/// of the same drop). The item is not added to the board; callers persist it.
+pub(crate) fn store_moodboard_image(
+    state: &AppState,
+    moodboard_id: &str,
     filename: String,
     original_name: String,
-    data: Vec<u8>,
+    data: &[u8],
+    placed: &[MoodboardItem],
 ) -> Result<MoodboardItem, String> {
     // Images stored in library (user-owned, persists after uninstall)
     let moodboard_dir = state.library_dir
         .join("Moodboards")
-        .join(&moodboard_id);
+        .join(moodboard_id);
     
     // Create moodboard directory if it doesn't exist
     if !moodboard_dir.exists() {
@@ -30,24 +86,60 @@ pub async fn upload_moodboard_image(
     fs::write(&file_path, data)
         .map_err(|e| format!("Failed to write image: {}", e))?;
     
+    let mut existing: Vec<MoodboardItem> = read_moodboards(state)?
+        .into_iter()
+        .find(|m| m.id == moodboard_id)
+        .map(|m| m.items)
+        .unwrap_or_default();
+    let unsaved: Vec<MoodboardItem> = placed
+        .iter()
+        .filter(|p| !existing.iter().any(|i| i.id == p.id))
+        .cloned()
+        .collect();
+    existing.extend(unsaved);
+
     // Create moodboard item
-    let item = MoodboardItem {
-        id: Uuid::new_v4().to_string(),
-        item_type: "image".to_string(),
-        content: Some(original_name.clone()),
-        x: 100.0,
-        y: 100.0,
-        width: 200.0,
-        height: 200.0,
-        created_at: Some(Utc::now().timestamp_millis()),
-        filename: Some(filename.clone()),
-        url: Some(format!("moodboards/{}/{}", moodboard_id, filename)),
-        original_width: None,
-        original_height: None,
-        aspect_ratio: None,
-        is_webp: Some(filename.ends_with(".webp")),
-        colors: None,
-    };
-    
+    let mut item = MoodboardItem::new("image", 100.0, 100.0, 200.0, 200.0);
+    item.content = Some(original_name);
+    item.url = Some(format!("moodboards/{}/{}", moodboard_id, filename));
+    item.is_webp = Some(filename.ends_with(".webp"));
+    item.filename = Some(filename);
+    // New images go on top of the board
+    item.z_index = existing.iter().map(|i| i.z_index + 1).max().unwrap_or(0);
+
+    // Files the image crate can't decode keep the plain 200x200 box
+    match load_oriented(&file_path) {
+        Ok(img) => {
+            let (w, h) = (img.width() as f64, img.height() as f64);
+            item.original_width = Some(w);
+            item.original_height = Some(h);
+            item.aspect_ratio = Some(w / h.max(1.0));
+            // Longest side INITIAL_SIZE, but small images aren't blown up
+            let fit = (INITIAL_SIZE / w.max(h).max(1.0)).min(1.0);
+            item.width = (w * fit).max(1.0).round();
+            item.height = (h * fit).max(1.0).round();
+            item.colors = Some(extract_palette(&img, PALETTE_SIZE).into_iter().map(to_hex_color).collect());
+        }
+        Err(e) => eprintln!("Warning: could not analyse moodboard image {:?}: {}", file_path, e),
+    }
+    let (x, y) = free_position(&existing, item.width, item.height);
+    item.x = x;
+    item.y = y;
+
     Ok(item)
 }
+
+/// Store an image for a board and return its item, unsaved. When several files are
+/// dropped at once, pass the items returned so far as `placedItems` so the next one
+/// is placed beside them rather than on top.
+#[tauri::command]
+pub async fn upload_moodboard_image(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    filename: String,
+    original_name: String,
+    data: Vec<u8>,
+    placed_items: Option<Vec<MoodboardItem>>,
+) -> Result<MoodboardItem, String> {
+    store_moodboard_image(&state, &moodboard_id, filename, original_name, &data, &placed_items.unwrap_or_default())
+}
@@ -1,49 +1,145 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
 use tauri::State;
+use uuid::Uuid;
 use crate::models::{Moodboard, MoodboardItem};
 use crate::state::AppState;
+use crate::commands::moodboard_layers::MoodboardItemProperties;
+pub(crate) use crate::commands::moodboard_store::modify_moodboards;
+use crate::commands::moodboard_store::flush_pending_moodboards;
+use crate::commands::moodboard_snapshots::{auto_snapshot, delete_board_snapshots, snapshot_uses_file};
 
-// Helper function to safely write moodboards with backup
-fn write_moodboards_with_backup(file_path: &Path, moodboards: &Vec<Moodboard>) -> Result<(), String> {
-    let content = serde_json::to_string_pretty(moodboards)
-        .map_err(|e| format!("Failed to serialize moodboards: {}", e))?;
-    
-    // Create backup before writing
-    let backup_path = file_path.with_extension("json.backup");
-    if file_path.exists() {
-        fs::copy(file_path, &backup_path)
-            .map_err(|e| format!("Failed to create backup: {}", e))?;
+pub(crate) use crate::commands::moodboard_store::read_moodboards;
+
+fn modify_one<F>(state: &AppState, moodboard_id: &str, deferred: bool, f: F) -> Result<Moodboard, String>
+where
+    F: FnOnce(&mut Moodboard) -> Result<(), String>,
+{
+    modify_moodboards(state, deferred, |moodboards| {
+        let moodboard = moodboards
+            .iter_mut()
+            .find(|m| m.id == moodboard_id)
+            .ok_or_else(|| "Moodboard not found".to_string())?;
+        f(moodboard)?;
+        moodboard.updated_at = Some(chrono::Utc::now().timestamp_millis());
+        Ok(moodboard.clone())
+    })
+}
+
+/// Apply `f` to one moodboard, bump its `updatedAt` and save. Nothing is written
+/// when `f` fails.
+pub(crate) fn modify_moodboard<F>(state: &AppState, moodboard_id: &str, f: F) -> Result<Moodboard, String>
+where
+    F: FnOnce(&mut Moodboard) -> Result<(), String>,
+{
+    modify_one(state, moodboard_id, false, f)
+}
+
+/// Like `modify_moodboard`, but saved by the debounced flusher; for edits that
+/// arrive in bursts (dragging, sliders).
+pub(crate) fn modify_moodboard_deferred<F>(state: &AppState, moodboard_id: &str, f: F) -> Result<Moodboard, String>
+where
+    F: FnOnce(&mut Moodboard) -> Result<(), String>,
+{
+    modify_one(state, moodboard_id, true, f)
+}
+
+/// A moodboard id not in use yet: the creation time in ms, like `create_moodboard`.
+pub(crate) fn new_moodboard_id(moodboards: &[Moodboard]) -> String {
+    let mut id = chrono::Utc::now().timestamp_millis();
+    while moodboards.iter().any(|m| m.id == id.to_string()) {
+        id += 1;
+    }
+    id.to_string()
+}
+
+/// Pick an id for a new board and create its empty folder, outside the cache lock so
+/// files can be written into it first. Ids whose folder is left over are skipped.
+pub(crate) fn create_board_dir(state: &AppState) -> Result<(String, PathBuf), String> {
+    let moodboards = read_moodboards(state)?;
+    let root = state.library_dir.join("Moodboards");
+    fs::create_dir_all(&root)
+        .map_err(|e| format!("Failed to create moodboard directory: {}", e))?;
+    let mut id = chrono::Utc::now().timestamp_millis();
+    loop {
+        let dir = root.join(id.to_string());
+        if !moodboards.iter().any(|m| m.id == id.to_string()) {
+            match fs::create_dir(&dir) {
+                Ok(()) => return Ok((id.to_string(), dir)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(format!("Failed to create moodboard directory: {}", e)),
+            }
+        }
+        id += 1;
+    }
+}
+
+/// Add a board made with `create_board_dir` to the top of the list.
+pub(crate) fn insert_new_moodboard(state: &AppState, moodboard: Moodboard) -> Result<Moodboard, String> {
+    modify_moodboards(state, false, |moodboards| {
+        if moodboards.iter().any(|m| m.id == moodboard.id) {
+            return Err("Moodboard id is already in use".to_string());
+        }
+        moodboards.insert(0, moodboard.clone());
+        Ok(moodboard)
+    })
+}
+
+/// Copies of `items` with fresh item and group ids. Image urls under
+/// `moodboards/<from>/` are pointed at board `to`.
+pub(crate) fn copy_items(items: &[MoodboardItem], from: &str, to: &str) -> Vec<MoodboardItem> {
+    let mut groups: HashMap<String, String> = HashMap::new();
+    let old_prefix = format!("moodboards/{}/", from);
+    let new_prefix = format!("moodboards/{}/", to);
+    items
+        .iter()
+        .map(|item| {
+            let mut copy = item.clone();
+            copy.id = Uuid::new_v4().to_string();
+            copy.created_at = Some(chrono::Utc::now().timestamp_millis());
+            copy.group_id = item.group_id.as_ref().map(|g| {
+                groups.entry(g.clone()).or_insert_with(|| Uuid::new_v4().to_string()).clone()
+            });
+            if let Some(rest) = item.url.as_deref().and_then(|u| u.strip_prefix(&old_prefix)) {
+                copy.url = Some(format!("{}{}", new_prefix, rest));
+            }
+            copy
+        })
+        .collect()
+}
+
+/// Copy the image files of board `from` into `dst`.
+fn copy_board_files(state: &AppState, from: &str, dst: &Path) -> Result<(), String> {
+    let src = state.library_dir.join("Moodboards").join(from);
+    if !src.exists() {
+        return Ok(());
+    }
+    let entries = fs::read_dir(&src)
+        .map_err(|e| format!("Failed to read moodboard directory: {}", e))?;
+    for entry in entries.flatten() {
+        if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            fs::copy(entry.path(), dst.join(entry.file_name()))
+                .map_err(|e| format!("Failed to copy moodboard image: {}", e))?;
+        }
     }
-    
-    // Write new content
-    fs::write(file_path, content)
-        .map_err(|e| format!("Failed to write moodboards: {}", e))?;
-    
     Ok(())
 }
 
+/// One entry of `update_moodboard_items`: an item id plus the properties to set.
+#[derive(Debug, Deserialize)]
+pub struct MoodboardItemUpdate {
+    pub id: String,
+    #[serde(flatten)]
+    pub properties: MoodboardItemProperties,
+}
+
 #[tauri::command]
 pub async fn get_moodboards(
     state: State<'_, AppState>,
 ) -> Result<Vec<Moodboard>, String> {
-    // Metadata in cache/app_data
-    let file_path = state.data_dir
-        .join("app_data")
-        .join("moodboards.json");
-
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read moodboards: {}", e))?;
-
-    // Handle empty file or whitespace-only content
-    let moodboards: Vec<Moodboard> = if content.trim().is_empty() {
-        Vec::new()
-    } else {
-        serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse moodboards: {} (content: '{}')", e, content))?
-    };
-
-    Ok(moodboards)
+    read_moodboards(&state)
 }
 
 #[tauri::command]
@@ -51,91 +147,110 @@ pub async fn create_moodboard(
     state: State<'_, AppState>,
     title: String,
 ) -> Result<Moodboard, String> {
-    // Metadata in cache/app_data
-    let app_data_dir = state.data_dir.join("app_data");
-    
-    if !app_data_dir.exists() {
-        fs::create_dir_all(&app_data_dir)
-            .map_err(|e| format!("Failed to create app_data directory: {}", e))?;
-    }
-    
-    let file_path = app_data_dir.join("moodboards.json");
-    
-    // Read existing moodboards
-    let mut moodboards = if file_path.exists() {
-        let content = fs::read_to_string(&file_path)
-            .map_err(|e| format!("Failed to read moodboards: {}", e))?;
-        
-        // Handle empty file or whitespace-only content
-        if content.trim().is_empty() {
-            Vec::new()
-        } else {
-            serde_json::from_str::<Vec<Moodboard>>(&content)
-                .map_err(|e| format!("Failed to parse moodboards: {} (content: '{}')", e, content))?
-        }
-    } else {
-        Vec::new()
-    };
-    
-    // Create new moodboard
-    let new_moodboard = Moodboard {
-        id: chrono::Utc::now().timestamp_millis().to_string(),
-        title,
-        items: Vec::new(),
-        created_at: chrono::Utc::now().timestamp_millis(),
-        updated_at: Some(chrono::Utc::now().timestamp_millis()),
-    };
-    
     // Add to beginning of list
-    moodboards.insert(0, new_moodboard.clone());
-    
-    // Save back to file with backup
-    write_moodboards_with_backup(&file_path, &moodboards)?;
-    
-    Ok(new_moodboard)
+    modify_moodboards(&state, false, |moodboards| {
+        let new_moodboard = Moodboard {
+            id: new_moodboard_id(moodboards),
+            title,
+            items: Vec::new(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            updated_at: Some(chrono::Utc::now().timestamp_millis()),
+        };
+        moodboards.insert(0, new_moodboard.clone());
+        Ok(new_moodboard)
+    })
+}
+
+/// An item as saved by the frontend. Items it creates may have no `zIndex` yet.
+#[derive(Debug, Deserialize)]
+pub struct MoodboardItemInput {
+    #[serde(flatten)]
+    pub item: MoodboardItem,
+    #[serde(rename = "zIndex")]
+    pub z_index: Option<i64>,
 }
 
+/// Replace the board's title and items. Items without a `zIndex` keep their saved
+/// layer, or go on top (in list order) if they are new. Locked items keep their saved
+/// position, size, rotation and flip unless the same save unlocks them.
 #[tauri::command]
 pub async fn update_moodboard(
     state: State<'_, AppState>,
     moodboard_id: String,
     title: String,
-    items: Vec<MoodboardItem>,
+    items: Vec<MoodboardItemInput>,
 ) -> Result<Moodboard, String> {
-    let file_path = state.data_dir
-        .join("app_data")
-        .join("moodboards.json");
-    
-    if !file_path.exists() {
-        return Err("Moodboards file not found".to_string());
-    }
-    
-    // Read existing moodboards
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read moodboards: {}", e))?;
-    let mut moodboards: Vec<Moodboard> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse moodboards: {}", e))?;
-    
-    // Find and update the moodboard
-    let mut updated_moodboard = None;
-    for moodboard in &mut moodboards {
-        if moodboard.id == moodboard_id {
-            moodboard.title = title;
-            moodboard.items = items;
-            moodboard.updated_at = Some(chrono::Utc::now().timestamp_millis());
-            updated_moodboard = Some(moodboard.clone());
-            break;
-        }
+    let drops_items = read_moodboards(&state)?
+        .iter()
+        .find(|m| m.id == moodboard_id)
+        .is_some_and(|m| m.items.iter().any(|old| !items.iter().any(|i| i.item.id == old.id)));
+    if drops_items {
+        auto_snapshot(&state, &moodboard_id, "Before removing items")?;
     }
-    
-    if let Some(moodboard) = updated_moodboard {
-        // Save back to file with backup
-        write_moodboards_with_backup(&file_path, &moodboards)?;
-        
-        Ok(moodboard)
-    } else {
-        Err("Moodboard not found".to_string())
+    modify_moodboard(&state, &moodboard_id, |moodboard| {
+        let mut top = items
+            .iter()
+            .filter_map(|i| i.z_index)
+            .chain(moodboard.items.iter().map(|i| i.z_index))
+            .max();
+        let items = items
+            .into_iter()
+            .map(|MoodboardItemInput { mut item, z_index }| {
+                let saved = moodboard.items.iter().find(|old| old.id == item.id);
+                // Locked items keep their place unless this save unlocks them
+                if let Some(old) = saved.filter(|old| old.locked && item.locked) {
+                    (item.x, item.y, item.width, item.height) = (old.x, old.y, old.width, old.height);
+                    item.rotation = old.rotation;
+                    item.flip = old.flip;
+                }
+                item.z_index = match z_index.or(saved.map(|old| old.z_index)) {
+                    Some(z) => z,
+                    None => {
+                        let z = top.map_or(0, |t| t + 1);
+                        top = Some(z);
+                        z
+                    }
+                };
+                item
+            })
+            .collect();
+        moodboard.title = title;
+        moodboard.items = items;
+        Ok(())
+    })
+}
+
+/// Copy a board with new item ids and its own copies of the image files.
+#[tauri::command]
+pub async fn duplicate_moodboard(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    title: Option<String>,
+) -> Result<Moodboard, String> {
+    let source = read_moodboards(&state)?
+        .into_iter()
+        .find(|m| m.id == moodboard_id)
+        .ok_or_else(|| "Moodboard not found".to_string())?;
+    // Files are copied before the board is added, so the cache isn't locked meanwhile
+    let (new_id, dir) = create_board_dir(&state)?;
+    let result = copy_board_files(&state, &source.id, &dir).and_then(|_| {
+        let now = chrono::Utc::now().timestamp_millis();
+        insert_new_moodboard(&state, Moodboard {
+            id: new_id.clone(),
+            title: title
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| format!("{} (copy)", source.title)),
+            items: copy_items(&source.items, &source.id, &new_id),
+            created_at: now,
+            updated_at: Some(now),
+        })
+    });
+    if result.is_err() {
+        // Don't leave half-copied files behind
+        let _ = fs::remove_dir_all(&dir);
     }
+    result
 }
 
 #[tauri::command]
@@ -143,22 +258,15 @@ pub async fn delete_moodboard(
     state: State<'_, AppState>,
     moodboard_id: String,
 ) -> Result<(), String> {
-    let file_path = state.data_dir
-        .join("app_data")
-        .join("moodboards.json");
-    
-    if !file_path.exists() {
-        return Err("Moodboards file not found".to_string());
-    }
-    
-    // Read existing moodboards
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read moodboards: {}", e))?;
-    let mut moodboards: Vec<Moodboard> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse moodboards: {}", e))?;
-    
     // Remove the moodboard and delete its directory with files
-    moodboards.retain(|m| m.id != moodboard_id);
+    modify_moodboards(&state, false, |moodboards| {
+        moodboards.retain(|m| m.id != moodboard_id);
+        Ok(())
+    })?;
+
+    if let Err(e) = delete_board_snapshots(&state, &moodboard_id) {
+        eprintln!("Warning: failed to delete snapshots of moodboard {}: {}", moodboard_id, e);
+    }
 
     // Attempt to delete the moodboard's directory from library if it exists
     let board_dir = state.library_dir
@@ -169,13 +277,12 @@ pub async fn delete_moodboard(
             eprintln!("Warning: failed to delete moodboard directory {:?}: {}", board_dir, e);
         }
     }
-    
-    // Save back to file with backup
-    write_moodboards_with_backup(&file_path, &moodboards)?;
-    
+
     Ok(())
 }
 
+/// Move or resize one item. Saved with a short delay so drags don't rewrite the
+/// file on every step.
 #[tauri::command]
 pub async fn update_moodboard_item(
     state: State<'_, AppState>,
@@ -186,114 +293,93 @@ pub async fn update_moodboard_item(
     width: Option<f64>,
     height: Option<f64>,
 ) -> Result<Moodboard, String> {
-    let file_path = state.data_dir
-        .join("app_data")
-        .join("moodboards.json");
-    
-    if !file_path.exists() {
-        return Err("Moodboards file not found".to_string());
-    }
-    
-    // Read existing moodboards
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read moodboards: {}", e))?;
-    let mut moodboards: Vec<Moodboard> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse moodboards: {}", e))?;
-    
-    // Find and update the specific item
-    let mut updated_moodboard = None;
-    for moodboard in &mut moodboards {
-        if moodboard.id == moodboard_id {
-            // Find the specific item to update
-            for item in &mut moodboard.items {
-                if item.id == item_id {
-                    // Update only the provided fields
-                    if let Some(new_x) = x {
-                        item.x = new_x;
-                    }
-                    if let Some(new_y) = y {
-                        item.y = new_y;
-                    }
-                    if let Some(new_width) = width {
-                        item.width = new_width;
-                    }
-                    if let Some(new_height) = height {
-                        item.height = new_height;
-                    }
-                    break;
-                }
+    modify_moodboard_deferred(&state, &moodboard_id, |moodboard| {
+        // An unknown item leaves the board as is
+        if let Some(item) = moodboard.items.iter_mut().find(|item| item.id == item_id) {
+            if item.locked && (x.is_some() || y.is_some() || width.is_some() || height.is_some()) {
+                return Err("Item is locked".to_string());
+            }
+            // Update only the provided fields
+            if let Some(new_x) = x {
+                item.x = new_x;
+            }
+            if let Some(new_y) = y {
+                item.y = new_y;
+            }
+            if let Some(new_width) = width {
+                item.width = new_width;
+            }
+            if let Some(new_height) = height {
+                item.height = new_height;
             }
-            moodboard.updated_at = Some(chrono::Utc::now().timestamp_millis());
-            updated_moodboard = Some(moodboard.clone());
-            break;
         }
-    }
-    
-    if let Some(moodboard) = updated_moodboard {
-        // Save back to file with backup
-        write_moodboards_with_backup(&file_path, &moodboards)?;
-        
-        Ok(moodboard)
-    } else {
-        Err("Moodboard not found".to_string())
-    }
+        Ok(())
+    })
 }
 
+/// Apply partial updates to many items in one step (multi-select drags). Either
+/// every update applies or none does. Saved like `update_moodboard_item`.
 #[tauri::command]
-pub async fn delete_moodboard_item(
+pub async fn update_moodboard_items(
     state: State<'_, AppState>,
     moodboard_id: String,
-    item_id: String,
+    updates: Vec<MoodboardItemUpdate>,
 ) -> Result<Moodboard, String> {
-    let file_path = state.data_dir
-        .join("app_data")
-        .join("moodboards.json");
-
-    if !file_path.exists() {
-        return Err("Moodboards file not found".to_string());
+    for update in &updates {
+        update.properties.validate()?;
     }
+    modify_moodboard_deferred(&state, &moodboard_id, |moodboard| {
+        for update in &updates {
+            let item = moodboard
+                .items
+                .iter_mut()
+                .find(|i| i.id == update.id)
+                .ok_or_else(|| format!("Item not found: {}", update.id))?;
+            update.properties.apply_to(item)?;
+        }
+        Ok(())
+    })
+}
 
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read moodboards: {}", e))?;
-    let mut moodboards: Vec<Moodboard> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse moodboards: {}", e))?;
+/// Save moodboard edits that are still waiting for the debounce (e.g. on drag end).
+#[tauri::command]
+pub async fn flush_moodboards() -> Result<(), String> {
+    flush_pending_moodboards()
+}
 
-    let mut updated_moodboard = None;
-    for moodboard in &mut moodboards {
-        if moodboard.id == moodboard_id {
-            // Find the item we are deleting to get its file path
-            if let Some(pos) = moodboard.items.iter().position(|item| item.id == item_id) {
-                let item = moodboard.items[pos].clone();
-                // Remove from list first
-                moodboard.items.remove(pos);
+#[tauri::command]
+pub async fn delete_moodboard_item(
+    state: State<'_, AppState>,
+    moodboard_id: String,
+    item_id: String,
+) -> Result<Moodboard, String> {
+    auto_snapshot(&state, &moodboard_id, "Before deleting an item")?;
+    let mut removed = None;
+    let moodboard = modify_moodboard(&state, &moodboard_id, |moodboard| {
+        let pos = moodboard
+            .items
+            .iter()
+            .position(|item| item.id == item_id)
+            .ok_or_else(|| "Moodboard or item not found".to_string())?;
+        removed = Some(moodboard.items.remove(pos));
+        Ok(())
+    })?;
 
-                // Attempt to delete the physical file if url present and looks like a moodboard path
-                if let Some(url) = item.url.clone() {
-                    // Expecting something like "moodboards/{id}/{filename}"
-                    if url.starts_with("moodboards/") {
-                        let subpath = url.strip_prefix("moodboards/").unwrap_or(&url);
-                        let full_path = state.library_dir
-                            .join("Moodboards")
-                            .join(subpath);
-                        if full_path.exists() {
-                            if let Err(e) = fs::remove_file(&full_path) {
-                                eprintln!("Warning: failed to delete moodboard item file {:?}: {}", full_path, e);
-                            }
-                        }
-                    }
+    // Attempt to delete the physical file if url present and looks like a moodboard path.
+    // Files a snapshot still shows are kept for restoring; gc_moodboards clears them later.
+    if let Some(url) = removed.and_then(|item| item.url).filter(|u| !snapshot_uses_file(&state, u).unwrap_or(true)) {
+        // Expecting something like "moodboards/{id}/{filename}"
+        if let Some(subpath) = url.strip_prefix("moodboards/") {
+            let full_path = state.library_dir
+                .join("Moodboards")
+                .join(subpath);
+            if full_path.exists() {
+                if let Err(e) = fs::remove_file(&full_path) {
+                    eprintln!("Warning: failed to delete moodboard item file {:?}: {}", full_path, e);
                 }
-
-                moodboard.updated_at = Some(chrono::Utc::now().timestamp_millis());
-                updated_moodboard = Some(moodboard.clone());
             }
-            break;
         }
     }
 
-    if let Some(moodboard) = updated_moodboard {
-        write_moodboards_with_backup(&file_path, &moodboards)?;
-        Ok(moodboard)
-    } else {
-        Err("Moodboard or item not found".to_string())
-    }
+    Ok(moodboard)
 }
@@ -0,0 +1,225 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::State;
+use image::{DynamicImage, ImageOutputFormat};
+
+use crate::imaging::{detect_paper, encode_image, load_oriented, validate_corners, warp_paper, white_balance_paper};
+use crate::models::{OverlayPoint, PaperCorrection, PhotoJournalImage};
+use crate::state::AppState;
+use crate::commands::journal_stats::read_journal_settings;
+use crate::commands::photo_journal::{
+    find_photo_journal_image, read_photo_journal, remove_journal_thumbnails, write_photo_journal,
+};
+use crate::commands::references::files::resolve_image_path;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaperDetection {
+    /// Normalized corners (top-left, top-right, bottom-right, bottom-left), if found
+    pub corners: Option<Vec<OverlayPoint>>,
+    /// Size of the original photo the corners refer to
+    pub width: u32,
+    pub height: u32,
+}
+
+fn journal_dir(state: &AppState) -> PathBuf {
+    state.library_dir.join("Artwork Journal")
+}
+
+/// Corrected photos are re-encoded in their upload format; other formats are left alone.
+fn output_format(filename: &str) -> Option<ImageOutputFormat> {
+    let ext = Path::new(filename).extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "jpg" | "jpeg" => Some(ImageOutputFormat::Jpeg(92)),
+        "png" => Some(ImageOutputFormat::Png),
+        "webp" => Some(ImageOutputFormat::WebP),
+        _ => None,
+    }
+}
+
+/// Keep the untouched upload under `Artwork Journal/Originals` (once) and return its url.
+fn keep_original(state: &AppState, filename: &str) -> Result<String, String> {
+    let originals_dir = journal_dir(state).join("Originals");
+    fs::create_dir_all(&originals_dir)
+        .map_err(|e| format!("Failed to create originals directory: {}", e))?;
+    let original_path = originals_dir.join(filename);
+    if !original_path.exists() {
+        fs::copy(journal_dir(state).join(filename), &original_path)
+            .map_err(|e| format!("Failed to keep original photo: {}", e))?;
+    }
+    Ok(format!("artwork_journal/Originals/{}", filename))
+}
+
+/// Straighten (and optionally white-balance) `original` and write the result over the
+/// journal image `filename`.
+fn write_corrected(
+    state: &AppState,
+    filename: &str,
+    original: &DynamicImage,
+    correction: &PaperCorrection,
+) -> Result<(), String> {
+    let format = output_format(filename)
+        .ok_or_else(|| "Only JPEG, PNG and WebP photos can be corrected".to_string())?;
+    let corners = validate_corners(&correction.corners)?;
+    let mut corrected = warp_paper(original, &corners)?;
+    if correction.white_balance {
+        corrected = white_balance_paper(&corrected);
+    }
+    let bytes = encode_image(&corrected, format)?;
+    fs::write(journal_dir(state).join(filename), bytes)
+        .map_err(|e| format!("Failed to write corrected photo: {}", e))?;
+    remove_journal_thumbnails(state, filename);
+    Ok(())
+}
+
+fn try_correct_on_ingest(state: &AppState, filename: &str) -> Result<Option<(String, PaperCorrection)>, String> {
+    let original = load_oriented(&journal_dir(state).join(filename))?;
+    let corners = match detect_paper(&original) {
+        Some(corners) => corners,
+        None => return Ok(None),
+    };
+    let correction = PaperCorrection { corners: corners.to_vec(), detected: true, white_balance: true, prior_rotation: 0 };
+    let original_url = keep_original(state, filename)?;
+    write_corrected(state, filename, &original, &correction)?;
+    Ok(Some((original_url, correction)))
+}
+
+/// Ingest step for new journal photos: when enabled (`requested`, else the journal
+/// setting) and a sheet of paper is found, keep the original and replace the upload
+/// with the straightened, white-balanced version. Returns the original's url and the
+/// correction; failures leave the upload as it is.
+pub(crate) fn correct_on_ingest(
+    state: &AppState,
+    filename: &str,
+    requested: Option<bool>,
+) -> Option<(String, PaperCorrection)> {
+    if !requested.unwrap_or_else(|| read_journal_settings(state).auto_correct_photos) {
+        return None;
+    }
+    output_format(filename)?;
+
+    match try_correct_on_ingest(state, filename) {
+        Ok(corrected) => corrected,
+        Err(e) => {
+            eprintln!("Paper correction skipped for {}: {}", filename, e);
+            None
+        }
+    }
+}
+
+/// Original photo of an entry (the kept upload, or the image itself if uncorrected).
+fn load_original(state: &AppState, image: &PhotoJournalImage) -> Result<DynamicImage, String> {
+    let url = image.original_url.as_deref().unwrap_or(&image.url);
+    let full_path = resolve_image_path(state, url);
+    if !full_path.exists() {
+        return Err(format!("Image file not found: {:?}", full_path));
+    }
+    load_oriented(&full_path)
+}
+
+fn update_entry(
+    state: &AppState,
+    id: &str,
+    update: impl FnOnce(&mut PhotoJournalImage),
+) -> Result<PhotoJournalImage, String> {
+    let mut images = read_photo_journal(state)?;
+    let image = images.iter_mut().find(|img| img.id == id)
+        .ok_or_else(|| "Image not found".to_string())?;
+    update(image);
+    image.size = journal_dir(state).join(&image.filename).metadata().map(|m| m.len()).unwrap_or(image.size);
+    let updated = image.clone();
+    write_photo_journal(state, &images)?;
+    Ok(updated)
+}
+
+/// Detect the paper in an entry's original photo without changing anything, so the
+/// corner handles can be shown for adjustment.
+#[tauri::command]
+pub async fn detect_photo_journal_paper(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<PaperDetection, String> {
+    let image = find_photo_journal_image(&state, &id)?;
+    let original = load_original(&state, &image)?;
+
+    Ok(PaperDetection {
+        corners: detect_paper(&original).map(|c| c.to_vec()),
+        width: original.width(),
+        height: original.height(),
+    })
+}
+
+/// Straighten an entry from its original photo using `corners` (normalized, top-left,
+/// top-right, bottom-right, bottom-left) or, when omitted, the detected paper edges.
+/// `whiteBalance` defaults to true. Works for entries uploaded before correction
+/// existed; the original is kept on first use.
+#[tauri::command]
+pub async fn correct_photo_journal_image(
+    state: State<'_, AppState>,
+    id: String,
+    corners: Option<Vec<OverlayPoint>>,
+    white_balance: Option<bool>,
+) -> Result<PhotoJournalImage, String> {
+    let image = find_photo_journal_image(&state, &id)?;
+    if output_format(&image.filename).is_none() {
+        return Err("Only JPEG, PNG and WebP photos can be corrected".to_string());
+    }
+    let original = load_original(&state, &image)?;
+
+    let (corners, detected) = match corners {
+        Some(corners) => (validate_corners(&corners)?.to_vec(), false),
+        None => {
+            let found = detect_paper(&original)
+                .ok_or_else(|| "Could not find the edges of the paper".to_string())?;
+            (found.to_vec(), true)
+        }
+    };
+    let correction = PaperCorrection {
+        corners,
+        detected,
+        white_balance: white_balance.unwrap_or(true),
+        // A repeated correction keeps the rotation from before the first one
+        prior_rotation: image.paper_correction.as_ref().map_or(image.rotation, |c| c.prior_rotation),
+    };
+
+    let original_url = match image.original_url.clone() {
+        Some(url) => url,
+        None => keep_original(&state, &image.filename)?,
+    };
+    write_corrected(&state, &image.filename, &original, &correction)?;
+
+    update_entry(&state, &id, |entry| {
+        entry.original_url = Some(original_url);
+        entry.paper_correction = Some(correction);
+        // Rotation was set on the uncorrected photo
+        entry.rotation = 0;
+    })
+}
+
+/// Put the original photo back, forget the correction and restore the rotation the
+/// entry had before it.
+#[tauri::command]
+pub async fn reset_photo_journal_correction(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<PhotoJournalImage, String> {
+    let image = find_photo_journal_image(&state, &id)?;
+    let original_url = match image.original_url.as_deref() {
+        Some(url) => url,
+        None => return Ok(image),
+    };
+
+    let original_path = resolve_image_path(&state, original_url);
+    fs::copy(&original_path, journal_dir(&state).join(&image.filename))
+        .map_err(|e| format!("Failed to restore original photo: {}", e))?;
+    let _ = fs::remove_file(&original_path);
+    remove_journal_thumbnails(&state, &image.filename);
+
+    update_entry(&state, &id, |entry| {
+        if let Some(correction) = entry.paper_correction.take() {
+            entry.rotation = correction.prior_rotation;
+        }
+        entry.original_url = None;
+    })
+}
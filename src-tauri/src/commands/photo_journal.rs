@@ -8,32 +8,76 @@ use image::ImageFormat;
 use crate::models::PhotoJournalImage;
 use crate::state::AppState;
 use crate::utils::apply_exif_orientation;
+use crate::commands::references::files::resolve_image_path;
+use crate::commands::references::ratings::bump_study_counters;
+use crate::commands::journal_pieces::remove_image_from_pieces;
+use crate::commands::paper_correction::correct_on_ingest;
 
-#[tauri::command]
-pub async fn get_photo_journal_images(
-    state: State<'_, AppState>,
-) -> Result<Vec<PhotoJournalImage>, String> {
-    // Metadata stored in cache/app_data
-    let file_path = state.data_dir
-        .join("app_data")
-        .join("photo_journal.json");
-
-    let content = fs::read_to_string(&file_path)
+/// Load the whole journal, with legacy single links folded into `reference_ids`.
+pub(crate) fn read_photo_journal(state: &AppState) -> Result<Vec<PhotoJournalImage>, String> {
+    let json_path = state.data_dir.join("app_data").join("photo_journal.json");
+    let content = fs::read_to_string(&json_path)
         .map_err(|e| format!("Failed to read photo journal: {}", e))?;
 
-    let images: Vec<PhotoJournalImage> = serde_json::from_str(&content)
+    let mut images: Vec<PhotoJournalImage> = serde_json::from_str(&content)
         .map_err(|e| format!("Failed to parse photo journal: {}", e))?;
-
+    for image in images.iter_mut() {
+        image.normalize_links();
+    }
     Ok(images)
 }
 
+pub(crate) fn write_photo_journal(state: &AppState, images: &[PhotoJournalImage]) -> Result<(), String> {
+    let json_path = state.data_dir.join("app_data").join("photo_journal.json");
+    let updated_content = serde_json::to_string_pretty(images)
+        .map_err(|e| format!("Failed to serialize photo journal: {}", e))?;
+
+    fs::write(&json_path, updated_content)
+        .map_err(|e| format!("Failed to write photo journal: {}", e))
+}
+
+/// Drop cached thumbnails of a journal image (after deleting or replacing it).
+pub(crate) fn remove_journal_thumbnails(state: &AppState, filename: &str) {
+    let thumb_stem = Path::new(filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown");
+    let thumbnails_dir = state.data_dir.join("thumbnails").join("artwork_journal");
+    let legacy_thumb = thumbnails_dir.join(format!("{}.thumb.png", thumb_stem));
+    let v2_thumb = thumbnails_dir.join(format!("{}.thumb.v2.360.png", thumb_stem));
+    if legacy_thumb.exists() {
+        let _ = fs::remove_file(&legacy_thumb);
+    }
+    if v2_thumb.exists() {
+        let _ = fs::remove_file(&v2_thumb);
+    }
+}
+
+/// Load one journal entry by id.
+pub(crate) fn find_photo_journal_image(state: &AppState, id: &str) -> Result<PhotoJournalImage, String> {
+    read_photo_journal(state)?
+        .into_iter()
+        .find(|img| img.id == id)
+        .ok_or_else(|| "Image not found".to_string())
+}
+
 #[tauri::command]
-pub async fn upload_photo_journal_image(
+pub async fn get_photo_journal_images(
     state: State<'_, AppState>,
+) -> Result<Vec<PhotoJournalImage>, String> {
+    // Metadata stored in cache/app_data
+    read_photo_journal(&state)
+}
+
+/// Write an image into the Artwork Journal and prepend its entry to photo_journal.json.
+/// Shared by uploads and scans; `correct` overrides the paper-correction setting.
+pub(crate) fn store_photo_journal_image(
+    state: &AppState,
     filename: String,
     original_name: String,
-    data: Vec<u8>,
+    data: &[u8],
     prompt: Option<String>,
+    correct: Option<bool>,
 ) -> Result<PhotoJournalImage, String> {
     // Original images stored in library (user-owned, persists after uninstall)
     let images_dir = state.library_dir.join("Artwork Journal");
@@ -49,6 +93,9 @@ pub async fn upload_photo_journal_image(
     fs::write(&file_path, data)
         .map_err(|e| format!("Failed to write image: {}", e))?;
 
+    // Straighten and white-balance photos of paper (keeps the original)
+    let correction = correct_on_ingest(state, &filename, correct);
+
     // Create image record
     let mime = if let Some(ext) = Path::new(&filename).extension().and_then(|s| s.to_str()) {
         match ext.to_ascii_lowercase().as_str() {
@@ -74,6 +121,18 @@ pub async fn upload_photo_journal_image(
         prompt,
         reference_id: None,
         rotation: 0,
+        comparisons: Vec::new(),
+        reference_ids: Vec::new(),
+        master_study_paths: Vec::new(),
+        tags: Vec::new(),
+        medium: None,
+        paper_size: None,
+        time_spent_minutes: None,
+        self_rating: None,
+        notes: None,
+        piece_id: None,
+        original_url: correction.as_ref().map(|(url, _)| url.clone()),
+        paper_correction: correction.map(|(_, c)| c),
     };
 
     // Update JSON file in cache/app_data
@@ -101,6 +160,18 @@ pub async fn upload_photo_journal_image(
     Ok(image)
 }
 
+#[tauri::command]
+pub async fn upload_photo_journal_image(
+    state: State<'_, AppState>,
+    filename: String,
+    original_name: String,
+    data: Vec<u8>,
+    prompt: Option<String>,
+    correct: Option<bool>,
+) -> Result<PhotoJournalImage, String> {
+    store_photo_journal_image(&state, filename, original_name, &data, prompt, correct)
+}
+
 #[tauri::command]
 pub async fn delete_photo_journal_image(
     state: State<'_, AppState>,
@@ -123,22 +194,21 @@ pub async fn delete_photo_journal_image(
         }
         
         // Also delete thumbnail if it exists
-        let thumb_stem = Path::new(&image.filename)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown");
-        let thumbnails_dir = state.data_dir.join("thumbnails").join("artwork_journal");
-        let legacy_thumb = thumbnails_dir.join(format!("{}.thumb.png", thumb_stem));
-        let v2_thumb = thumbnails_dir.join(format!("{}.thumb.v2.360.png", thumb_stem));
-        if legacy_thumb.exists() {
-            let _ = fs::remove_file(&legacy_thumb);
+        remove_journal_thumbnails(&state, &image.filename);
+
+        // The untouched upload kept by paper correction
+        if let Some(original_url) = image.original_url.as_deref() {
+            let _ = fs::remove_file(resolve_image_path(&state, original_url));
         }
-        if v2_thumb.exists() {
-            let _ = fs::remove_file(&v2_thumb);
+
+        // Saved comparison renders belong to the entry
+        for comparison in &image.comparisons {
+            let _ = fs::remove_file(resolve_image_path(&state, &comparison.url));
         }
     }
 
     images.retain(|img| img.id != id);
+    remove_image_from_pieces(&state, &id)?;
 
     let updated_content = serde_json::to_string_pretty(&images)
         .map_err(|e| format!("Failed to serialize photo journal: {}", e))?;
@@ -291,78 +361,55 @@ pub async fn clear_photo_journal_thumbnails(
     Ok(())
 }
 
+/// Remove one linked reference, or every linked reference when `referenceId` is omitted
+/// (the original single-link behaviour). The next link becomes the primary one.
 #[tauri::command]
 #[allow(non_snake_case)]
 pub async fn unlink_photo_journal_reference(
     state: State<'_, AppState>,
     photoId: String,
+    referenceId: Option<String>,
 ) -> Result<(), String> {
-    let json_path = state.data_dir.join("app_data").join("photo_journal.json");
-    let content = fs::read_to_string(&json_path)
-        .map_err(|e| format!("Failed to read photo journal: {}", e))?;
-
-    let mut data: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse photo journal: {}", e))?;
-
-    let arr = data.as_array_mut().ok_or_else(|| "Photo journal JSON is not an array".to_string())?;
-    let mut found = false;
-    for item in arr.iter_mut() {
-        if let Some(obj) = item.as_object_mut() {
-            if obj.get("id").and_then(|v| v.as_str()) == Some(&photoId) {
-                obj.insert("referenceId".to_string(), serde_json::Value::Null);
-                found = true;
-                break;
-            }
-        }
+    let mut images = read_photo_journal(&state)?;
+    let image = images.iter_mut().find(|img| img.id == photoId)
+        .ok_or_else(|| "Photo not found".to_string())?;
+
+    image.normalize_links();
+    match referenceId {
+        Some(reference_id) => image.reference_ids.retain(|id| *id != reference_id),
+        None => image.reference_ids.clear(),
     }
+    image.reference_id = None;
+    image.normalize_links();
 
-    if !found {
-        return Err("Photo not found".to_string());
-    }
-
-    let updated_content = serde_json::to_string_pretty(&data)
-        .map_err(|e| format!("Failed to serialize photo journal: {}", e))?;
-
-    fs::write(&json_path, updated_content)
-        .map_err(|e| format!("Failed to write photo journal: {}", e))?;
-
-    Ok(())
+    write_photo_journal(&state, &images)
 }
 
+/// Link a reference and make it the entry's primary one; references linked earlier
+/// stay linked behind it.
 #[tauri::command]
 pub async fn link_photo_journal_reference(
     state: State<'_, AppState>,
     photo_id: String,
     reference_id: String,
 ) -> Result<(), String> {
-    let json_path = state.data_dir.join("app_data").join("photo_journal.json");
-    let content = fs::read_to_string(&json_path)
-        .map_err(|e| format!("Failed to read photo journal: {}", e))?;
-
-    let mut data: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse photo journal: {}", e))?;
-
-    let arr = data.as_array_mut().ok_or_else(|| "Photo journal JSON is not an array".to_string())?;
-    let mut found = false;
-    for item in arr.iter_mut() {
-        if let Some(obj) = item.as_object_mut() {
-            if obj.get("id").and_then(|v| v.as_str()) == Some(&photo_id) {
-                obj.insert("referenceId".to_string(), serde_json::Value::String(reference_id.clone()));
-                found = true;
-                break;
-            }
-        }
+    let mut images = read_photo_journal(&state)?;
+    let image = images.iter_mut().find(|img| img.id == photo_id)
+        .ok_or_else(|| "Photo not found".to_string())?;
+
+    image.normalize_links();
+    let already_linked = image.reference_ids.contains(&reference_id);
+    image.reference_ids.retain(|id| *id != reference_id);
+    image.reference_ids.insert(0, reference_id.clone());
+    image.reference_id = None;
+    image.normalize_links();
+
+    write_photo_journal(&state, &images)?;
+
+    // Each new link counts as one study of the reference (re-linking the same one does not)
+    if !already_linked {
+        bump_study_counters(&state.data_dir, &reference_id)?;
     }
 
-    if !found {
-        return Err("Photo not found".to_string());
-    }
-
-    let updated_content = serde_json::to_string_pretty(&data)
-        .map_err(|e| format!("Failed to serialize photo journal: {}", e))?;
-
-    fs::write(&json_path, updated_content)
-        .map_err(|e| format!("Failed to write photo journal: {}", e))?;
-
     Ok(())
 }
@@ -0,0 +1,150 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use image::{DynamicImage, ImageOutputFormat};
+
+use crate::imaging::{
+    apply_adjustments, apply_rotation_and_crop, encode_image, fit_within, load_oriented,
+    to_data_url, validate_adjustments,
+};
+use crate::models::{ImageAdjustment, Reference};
+use crate::state::AppState;
+use super::crud::find_reference;
+use super::files::resolve_image_path;
+
+const ADJUSTED_THUMB_SIZE: u32 = 360;
+
+/// Decode a reference and apply EXIF orientation, rotation, crop and its adjustment stack.
+pub(crate) fn render_reference(state: &AppState, reference: &Reference) -> Result<DynamicImage, String> {
+    let full_path = resolve_image_path(state, &reference.url);
+    if !full_path.exists() {
+        return Err(format!("Image file not found: {:?}", full_path));
+    }
+    let img = load_oriented(&full_path)?;
+    let img = apply_rotation_and_crop(img, reference.rotation, reference.crop.as_ref());
+    Ok(apply_adjustments(img, &reference.adjustments))
+}
+
+fn adjusted_thumbnails_dir(state: &AppState) -> PathBuf {
+    state.data_dir
+        .join("thumbnails")
+        .join("references")
+}
+
+fn file_stem(filename: &str) -> &str {
+    Path::new(filename).file_stem().and_then(|s| s.to_str()).unwrap_or("unknown")
+}
+
+/// Cache key covering everything that changes the rendered pixels.
+fn adjusted_thumbnail_path(state: &AppState, reference: &Reference) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    reference.rotation.hash(&mut hasher);
+    serde_json::to_string(&reference.crop).unwrap_or_default().hash(&mut hasher);
+    serde_json::to_string(&reference.adjustments).unwrap_or_default().hash(&mut hasher);
+    adjusted_thumbnails_dir(state).join(format!(
+        "{}.adj-{:016x}.thumb-{}.jpg",
+        file_stem(&reference.filename),
+        hasher.finish(),
+        ADJUSTED_THUMB_SIZE
+    ))
+}
+
+/// Remove cached adjusted thumbnails of a reference (they are keyed by settings, so
+/// older variants would otherwise pile up).
+pub(crate) fn clear_adjusted_thumbnails(state: &AppState, filename: &str) {
+    let prefix = format!("{}.adj-", file_stem(filename));
+    if let Ok(entries) = fs::read_dir(adjusted_thumbnails_dir(state)) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn set_reference_adjustments(
+    state: State<'_, AppState>,
+    reference_id: String,
+    adjustments: Vec<ImageAdjustment>,
+) -> Result<Reference, String> {
+    validate_adjustments(&adjustments)?;
+
+    let references_file = state
+        .data_dir
+        .join("app_data")
+        .join("references.json");
+
+    let content = fs::read_to_string(&references_file)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+
+    let mut references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    let idx = references
+        .iter()
+        .position(|r| r.id == reference_id)
+        .ok_or_else(|| "Reference not found".to_string())?;
+
+    references[idx].adjustments = adjustments;
+
+    let updated_reference = references[idx].clone();
+
+    let updated_content = serde_json::to_string_pretty(&references)
+        .map_err(|e| format!("Failed to serialize references: {}", e))?;
+    fs::write(&references_file, updated_content)
+        .map_err(|e| format!("Failed to write references: {}", e))?;
+
+    clear_adjusted_thumbnails(&state, &updated_reference.filename);
+
+    Ok(updated_reference)
+}
+
+/// Render a reference with rotation, crop and adjustments baked in (JPEG data URL).
+/// Pass `adjustments` to preview a stack without saving it.
+#[tauri::command]
+pub async fn render_reference_adjusted(
+    state: State<'_, AppState>,
+    reference_id: String,
+    adjustments: Option<Vec<ImageAdjustment>>,
+    max_size: Option<u32>,
+) -> Result<String, String> {
+    let mut reference = find_reference(&state, &reference_id)?;
+    if let Some(preview) = adjustments {
+        validate_adjustments(&preview)?;
+        reference.adjustments = preview;
+    }
+
+    let img = fit_within(render_reference(&state, &reference)?, max_size);
+    let bytes = encode_image(&img, ImageOutputFormat::Jpeg(92))?;
+    Ok(to_data_url(&bytes, "image/jpeg"))
+}
+
+/// Thumbnail of the adjusted render. Unlike `get_thumbnail_data`, rotation and crop are
+/// already applied, so the frontend must not transform it again.
+#[tauri::command]
+pub async fn get_adjusted_thumbnail_data(
+    state: State<'_, AppState>,
+    reference_id: String,
+) -> Result<String, String> {
+    let reference = find_reference(&state, &reference_id)?;
+    let thumbnail_path = adjusted_thumbnail_path(&state, &reference);
+
+    if !thumbnail_path.exists() {
+        let thumbnail = fit_within(render_reference(&state, &reference)?, Some(ADJUSTED_THUMB_SIZE));
+        let bytes = encode_image(&thumbnail, ImageOutputFormat::Jpeg(85))?;
+        if let Some(parent) = thumbnail_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create thumbnails directory: {}", e))?;
+        }
+        fs::write(&thumbnail_path, &bytes)
+            .map_err(|e| format!("Failed to save thumbnail: {}", e))?;
+        return Ok(to_data_url(&bytes, "image/jpeg"));
+    }
+
+    let thumbnail_data = fs::read(&thumbnail_path)
+        .map_err(|e| format!("Failed to read thumbnail: {}", e))?;
+    Ok(to_data_url(&thumbnail_data, "image/jpeg"))
+}
@@ -4,8 +4,10 @@ use uuid::Uuid;
 use chrono::Utc;
 use base64::Engine;
 
-use crate::models::{Reference, Folder, CropRect};
+use crate::models::{Reference, Folder, CropRect, DerivedFrom};
 use crate::state::AppState;
+use super::adjustments::clear_adjusted_thumbnails;
+use crate::commands::moodboard_references::{mark_reference_items_missing, sync_reference_items};
 use std::path::Path;
 
 fn try_salvage_json_array(content: &str) -> Option<&str> {
@@ -26,6 +28,49 @@ fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Look up a single reference by id from references.json.
+pub(crate) fn find_reference(state: &AppState, reference_id: &str) -> Result<Reference, String> {
+    let references_file = state.data_dir
+        .join("app_data")
+        .join("references.json");
+
+    let content = fs::read_to_string(&references_file)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+
+    let references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    references
+        .into_iter()
+        .find(|r| r.id == reference_id)
+        .ok_or_else(|| "Reference not found".to_string())
+}
+
+/// Apply `update` to one reference in references.json and return the updated record.
+pub(crate) fn update_reference(
+    state: &AppState,
+    reference_id: &str,
+    update: impl FnOnce(&mut Reference),
+) -> Result<Reference, String> {
+    let json_path = state.data_dir.join("app_data").join("references.json");
+    let content = fs::read_to_string(&json_path)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+    let mut references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    let reference = references
+        .iter_mut()
+        .find(|r| r.id == reference_id)
+        .ok_or_else(|| "Reference not found".to_string())?;
+    update(reference);
+    let updated = reference.clone();
+
+    let updated_content = serde_json::to_string_pretty(&references)
+        .map_err(|e| format!("Failed to serialize references: {}", e))?;
+    write_atomic(&json_path, &updated_content)?;
+    Ok(updated)
+}
+
 #[tauri::command]
 pub async fn get_references(
     state: State<'_, AppState>,
@@ -162,17 +207,25 @@ pub async fn delete_reference(
     if thumb_path.exists() {
         let _ = fs::remove_file(&thumb_path);
     }
+    clear_adjusted_thumbnails(&state, &reference_filename);
+
+    // Boards showing this reference keep the item, flagged as missing
+    if let Err(e) = mark_reference_items_missing(&state, &[id]) {
+        eprintln!("Warning: failed to flag moodboard items of reference: {}", e);
+    }
 
     Ok(())
 }
 
-#[tauri::command]
-pub async fn upload_reference(
-    state: State<'_, AppState>,
+/// Write an image into References (Main or a folder) and prepend its record to
+/// references.json. Shared by uploads and generated/imported references.
+pub(crate) fn store_reference(
+    state: &AppState,
     filename: String,
     original_name: String,
-    data: Vec<u8>,
+    data: &[u8],
     folder_id: Option<String>,
+    derived_from: Option<DerivedFrom>,
 ) -> Result<Reference, String> {
     // Get physical path for folder if needed
     let physical_path = if let Some(folder_id) = &folder_id {
@@ -235,6 +288,13 @@ pub async fn upload_reference(
         image_source: None,
         rotation: 0,
         crop: None,
+        rating: 0,
+        favorite: false,
+        times_studied: 0,
+        last_studied_at: None,
+        adjustments: Vec::new(),
+        derived_from,
+        grid_overlay: None,
     };
 
     // Update JSON file in cache/app_data
@@ -255,6 +315,17 @@ pub async fn upload_reference(
     Ok(reference)
 }
 
+#[tauri::command]
+pub async fn upload_reference(
+    state: State<'_, AppState>,
+    filename: String,
+    original_name: String,
+    data: Vec<u8>,
+    folder_id: Option<String>,
+) -> Result<Reference, String> {
+    store_reference(&state, filename, original_name, &data, folder_id, None)
+}
+
 #[tauri::command]
 pub async fn set_reference_rotation(
     state: State<'_, AppState>,
@@ -279,6 +350,7 @@ pub async fn set_reference_rotation(
     let r = rotation.rem_euclid(360);
     references[idx].rotation = r;
     let updated_reference = references[idx].clone();
+    clear_adjusted_thumbnails(&state, &updated_reference.filename);
 
     let updated_content = serde_json::to_string_pretty(&references)
         .map_err(|e| format!("Failed to serialize references: {}", e))?;
@@ -322,6 +394,7 @@ pub async fn set_reference_crop(
 
     references[idx].crop = crop;
     let updated_reference = references[idx].clone();
+    clear_adjusted_thumbnails(&state, &updated_reference.filename);
 
     let updated_content = serde_json::to_string_pretty(&references)
         .map_err(|e| format!("Failed to serialize references: {}", e))?;
@@ -475,6 +548,10 @@ pub async fn move_reference(
         .ok_or_else(|| "Reference not found after update".to_string())?
         .clone();
 
+    if let Err(e) = sync_reference_items(&state, &updated_reference) {
+        eprintln!("Warning: failed to update moodboard items of moved reference: {}", e);
+    }
+
     Ok(updated_reference)
 }
 
@@ -0,0 +1,197 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use chrono::{Local, TimeZone};
+use image::ImageOutputFormat;
+
+use crate::imaging::{encode_image, fit_within};
+use crate::models::{Folder, Reference};
+use crate::state::AppState;
+use super::adjustments::render_reference;
+use super::crud::find_reference;
+
+const DEFAULT_FILENAME_TEMPLATE: &str = "{name}";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    /// "jpeg" (default), "png" or "webp"
+    pub format: Option<String>,
+    /// JPEG quality 1-100, default 90. PNG and WebP are written losslessly.
+    pub quality: Option<u8>,
+    /// Longest side in pixels; images are never upscaled
+    pub max_size: Option<u32>,
+    /// Tokens: {name} {id} {index} {date} {folder} {rating} {width} {height}
+    pub filename_template: Option<String>,
+    /// Replace files that already exist instead of adding " (2)", " (3)", ...
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedReference {
+    pub reference_id: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFailure {
+    pub reference_id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReport {
+    pub exported: Vec<ExportedReference>,
+    pub failed: Vec<ExportFailure>,
+}
+
+fn output_format(format: Option<&str>, quality: Option<u8>) -> Result<(ImageOutputFormat, &'static str), String> {
+    match format.unwrap_or("jpeg").to_lowercase().as_str() {
+        "jpeg" | "jpg" => {
+            let q = quality.unwrap_or(90);
+            if !(1..=100).contains(&q) {
+                return Err("Quality must be between 1 and 100".to_string());
+            }
+            Ok((ImageOutputFormat::Jpeg(q), "jpg"))
+        }
+        "png" => Ok((ImageOutputFormat::Png, "png")),
+        "webp" => Ok((ImageOutputFormat::WebP, "webp")),
+        other => Err(format!("Unsupported export format: {}", other)),
+    }
+}
+
+/// Strip characters that are invalid in file names on Windows, macOS or Linux.
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control() { '_' } else { c })
+        .collect();
+    let trimmed = cleaned.trim().trim_end_matches('.').trim();
+    if trimmed.is_empty() { "reference".to_string() } else { trimmed.to_string() }
+}
+
+fn folder_name(state: &AppState, folder_id: Option<&str>) -> String {
+    let folder_id = match folder_id {
+        Some(id) => id,
+        None => return "Main".to_string(),
+    };
+    let folders_file = state.data_dir.join("app_data").join("folders.json");
+    fs::read_to_string(folders_file)
+        .ok()
+        .and_then(|c| serde_json::from_str::<Vec<Folder>>(&c).ok())
+        .and_then(|folders| folders.into_iter().find(|f| f.id == folder_id))
+        .map(|f| f.name)
+        .unwrap_or_else(|| folder_id.to_string())
+}
+
+fn expand_template(
+    template: &str,
+    state: &AppState,
+    reference: &Reference,
+    index: usize,
+    total: usize,
+    size: (u32, u32),
+) -> String {
+    let name = Path::new(&reference.original_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("reference");
+    let date = Local
+        .timestamp_millis_opt(reference.created_at)
+        .single()
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    // Zero-pad the index so exported files sort in selection order
+    let width = total.to_string().len();
+
+    let mut out = template
+        .replace("{name}", name)
+        .replace("{id}", &reference.id)
+        .replace("{index}", &format!("{:0width$}", index + 1, width = width))
+        .replace("{date}", &date)
+        .replace("{rating}", &reference.rating.to_string())
+        .replace("{width}", &size.0.to_string())
+        .replace("{height}", &size.1.to_string());
+    if out.contains("{folder}") {
+        out = out.replace("{folder}", &folder_name(state, reference.folder_id.as_deref()));
+    }
+    sanitize_file_name(&out)
+}
+
+/// First free "<stem>.<ext>", "<stem> (2).<ext>", ... that is neither on disk nor
+/// already claimed by this export.
+fn unique_path(dir: &Path, stem: &str, ext: &str, claimed: &[PathBuf]) -> PathBuf {
+    let mut candidate = dir.join(format!("{}.{}", stem, ext));
+    let mut n = 2;
+    while candidate.exists() || claimed.contains(&candidate) {
+        candidate = dir.join(format!("{} ({}).{}", stem, n, ext));
+        n += 1;
+    }
+    candidate
+}
+
+/// Write the given references to `target_dir` as they are displayed: EXIF orientation,
+/// rotation, crop and adjustments applied. Failures are reported per reference so one
+/// missing file doesn't abort the whole export.
+#[tauri::command]
+pub async fn export_references(
+    state: State<'_, AppState>,
+    reference_ids: Vec<String>,
+    target_dir: String,
+    options: ExportOptions,
+) -> Result<ExportReport, String> {
+    let (format, ext) = output_format(options.format.as_deref(), options.quality)?;
+    let template = options
+        .filename_template
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(DEFAULT_FILENAME_TEMPLATE);
+
+    let target_dir = PathBuf::from(target_dir);
+    fs::create_dir_all(&target_dir)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let mut exported: Vec<ExportedReference> = Vec::new();
+    let mut failed: Vec<ExportFailure> = Vec::new();
+    let mut claimed: Vec<PathBuf> = Vec::new();
+
+    for (index, reference_id) in reference_ids.iter().enumerate() {
+        let result = (|| -> Result<PathBuf, String> {
+            let reference = find_reference(&state, reference_id)?;
+            let img = fit_within(render_reference(&state, &reference)?, options.max_size);
+            let stem = expand_template(template, &state, &reference, index, reference_ids.len(), (img.width(), img.height()));
+            let direct = target_dir.join(format!("{}.{}", stem, ext));
+            // Overwrite only files from earlier runs, never one written by this export
+            let path = if options.overwrite && !claimed.contains(&direct) {
+                direct
+            } else {
+                unique_path(&target_dir, &stem, ext, &claimed)
+            };
+            let bytes = encode_image(&img, format.clone())?;
+            fs::write(&path, bytes)
+                .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+            Ok(path)
+        })();
+
+        match result {
+            Ok(path) => {
+                claimed.push(path.clone());
+                exported.push(ExportedReference {
+                    reference_id: reference_id.clone(),
+                    path: path.to_string_lossy().to_string(),
+                });
+            }
+            Err(error) => failed.push(ExportFailure {
+                reference_id: reference_id.clone(),
+                error,
+            }),
+        }
+    }
+
+    Ok(ExportReport { exported, failed })
+}
@@ -1,5 +1,7 @@
 use std::fs;
-use tauri::State;
+use std::env;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
 use image::ImageOutputFormat;
 use image::imageops::FilterType;
 use base64::Engine;
@@ -7,21 +9,18 @@ use base64::Engine;
 use crate::state::AppState;
 use crate::utils::apply_exif_orientation;
 
-#[tauri::command]
-pub async fn get_image_data(
-    state: State<'_, AppState>,
-    image_path: String,
-) -> Result<String, String> {
-    // Resolve the full path based on the image_path prefix
-    let full_path = if image_path.starts_with("library/") {
+/// Resolve a stored image url (`references/...`, `artwork_journal/...`, `moodboards/...`,
+/// `library/...`) to its file on disk, including legacy dev locations.
+pub(crate) fn resolve_image_path(state: &AppState, image_path: &str) -> PathBuf {
+    if image_path.starts_with("library/") {
         // Bundled app content (master studies, warmups) - in cache/library
-        state.data_dir.join(&image_path)
+        state.data_dir.join(image_path)
     } else if image_path.starts_with("references/main/") || image_path.starts_with("references/Main/") || image_path.starts_with("references/") && !image_path.starts_with("references/folders/") {
         // Main references - in library (with legacy fallback)
         let filename = image_path.strip_prefix("references/main/")
             .or_else(|| image_path.strip_prefix("references/Main/"))
             .or_else(|| image_path.strip_prefix("references/"))
-            .unwrap_or(&image_path);
+            .unwrap_or(image_path);
         let new_path = state.library_dir.join("References").join("Main").join(filename);
         if new_path.exists() {
             new_path
@@ -34,7 +33,7 @@ pub async fn get_image_data(
         // Folder references - in library (with legacy fallback)
         let subpath = image_path.strip_prefix("references/folders/")
             .or_else(|| image_path.strip_prefix("references/Folders/"))
-            .unwrap_or(&image_path);
+            .unwrap_or(image_path);
         let new_path = state.library_dir.join("References").join("Folders").join(subpath);
         if new_path.exists() {
             new_path
@@ -44,7 +43,7 @@ pub async fn get_image_data(
         }
     } else if image_path.starts_with("folders/") {
         // Legacy folder path - in library (with legacy fallback)
-        let subpath = image_path.strip_prefix("folders/").unwrap_or(&image_path);
+        let subpath = image_path.strip_prefix("folders/").unwrap_or(image_path);
         let new_path = state.library_dir.join("References").join("Folders").join(subpath);
         if new_path.exists() {
             new_path
@@ -56,7 +55,7 @@ pub async fn get_image_data(
         // Artwork journal images - in library (with legacy path support)
         let filename = image_path.strip_prefix("artwork_journal/")
             .or_else(|| image_path.strip_prefix("photo_journal/images/"))
-            .unwrap_or(&image_path);
+            .unwrap_or(image_path);
         // Try new location first
         let new_path = state.library_dir.join("Artwork Journal").join(filename);
         if new_path.exists() {
@@ -72,11 +71,49 @@ pub async fn get_image_data(
         }
     } else if image_path.starts_with("moodboards/") {
         // Moodboard images - in library
-        state.library_dir.join("Moodboards").join(image_path.strip_prefix("moodboards/").unwrap_or(&image_path))
+        state.library_dir.join("Moodboards").join(image_path.strip_prefix("moodboards/").unwrap_or(image_path))
     } else {
         // Default: try as filename in References/Main
-        state.library_dir.join("References").join("Main").join(&image_path)
-    };
+        state.library_dir.join("References").join("Main").join(image_path)
+    }
+}
+
+/// Locate an existing image file for processing. Bundled `library/...` assets (master
+/// studies, warmups) are searched like `list_warmups` does: seeded cache dir, dev project
+/// root, then packaged resources.
+pub(crate) fn locate_image(app: &AppHandle, state: &AppState, image_path: &str) -> Result<PathBuf, String> {
+    let relative = image_path.trim_start_matches('/');
+    let mut candidates: Vec<PathBuf> = vec![resolve_image_path(state, relative)];
+
+    if let Some(asset) = relative.strip_prefix("library/") {
+        candidates.push(state.data_dir.join(asset));
+        if let Ok(cwd) = env::current_dir() {
+            for dir in cwd.ancestors() {
+                candidates.push(dir.join("library").join(asset));
+            }
+        }
+        if let Some(proj_root) = Path::new(env!("CARGO_MANIFEST_DIR")).parent() {
+            candidates.push(proj_root.join("library").join(asset));
+        }
+        let resolver = app.path_resolver();
+        if let Some(res_dir) = resolver.resource_dir() {
+            candidates.push(res_dir.join("library").join(asset));
+            candidates.push(res_dir.join("_up_").join("library").join(asset));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .find(|p| p.is_file())
+        .ok_or_else(|| format!("Image file not found: {}", image_path))
+}
+
+#[tauri::command]
+pub async fn get_image_data(
+    state: State<'_, AppState>,
+    image_path: String,
+) -> Result<String, String> {
+    let full_path = resolve_image_path(&state, &image_path);
     
     if !full_path.exists() {
         return Err(format!("Image file not found: {:?}", full_path));
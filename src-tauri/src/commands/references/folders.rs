@@ -5,6 +5,7 @@ use chrono::Utc;
 
 use crate::models::{Folder, Reference};
 use crate::state::AppState;
+use crate::commands::moodboard_references::mark_reference_items_missing;
 
 #[tauri::command]
 pub async fn get_folders(
@@ -150,9 +151,12 @@ pub async fn delete_folder(
             .map_err(|e| format!("Failed to parse references: {}", e))?;
 
         // Remove references that belong to this folder
-        let initial_count = references.len();
+        let removed_ids: Vec<String> = references.iter()
+            .filter(|r| r.folder_id.as_ref() == Some(&id))
+            .map(|r| r.id.clone())
+            .collect();
         references.retain(|r| r.folder_id.as_ref().map_or(true, |fid| fid != &id));
-        let removed_count = initial_count - references.len();
+        let removed_count = removed_ids.len();
 
         if removed_count > 0 {
             // Write updated references to file
@@ -163,6 +167,10 @@ pub async fn delete_folder(
                 .map_err(|e| format!("Failed to write references: {}", e))?;
             
             println!("Removed {} references from deleted folder", removed_count);
+
+            if let Err(e) = mark_reference_items_missing(&state, &removed_ids) {
+                eprintln!("Warning: failed to flag moodboard items of removed references: {}", e);
+            }
         }
     }
 
@@ -5,6 +5,12 @@ pub mod folders;
 pub mod tags;
 pub mod files;
 pub mod notes;
+pub mod ratings;
+pub mod search;
+pub mod adjustments;
+pub mod value_study;
+pub mod overlay;
+pub mod export;
 
 // Re-export all commands so callers can continue using `commands::...`
 pub use crud::*;
@@ -12,5 +18,11 @@ pub use folders::*;
 pub use tags::*;
 pub use files::*;
 pub use notes::*;
+pub use ratings::*;
+pub use search::*;
+pub use adjustments::*;
+pub use value_study::*;
+pub use overlay::*;
+pub use export::*;
 
 
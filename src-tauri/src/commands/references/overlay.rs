@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+use image::{DynamicImage, ImageOutputFormat};
+
+use crate::imaging::{draw_grid_overlay, encode_image, fit_within, to_data_url, validate_overlay};
+use crate::models::{GridOverlay, Reference};
+use crate::state::AppState;
+use super::adjustments::render_reference;
+use super::crud::find_reference;
+
+/// Render a reference (rotation, crop and adjustments applied) with an overlay as PNG.
+/// Uses `overlay` when given, otherwise the reference's saved overlay.
+fn render_with_overlay(
+    state: &AppState,
+    reference_id: &str,
+    overlay: Option<GridOverlay>,
+    max_size: Option<u32>,
+) -> Result<Vec<u8>, String> {
+    let reference = find_reference(state, reference_id)?;
+    let overlay = overlay
+        .or(reference.grid_overlay.clone())
+        .ok_or_else(|| "No grid overlay configured for this reference".to_string())?;
+    validate_overlay(&overlay)?;
+
+    let img = fit_within(render_reference(state, &reference)?, max_size);
+    let mut rgba = img.to_rgba8();
+    draw_grid_overlay(&mut rgba, &overlay);
+    encode_image(&DynamicImage::ImageRgba8(rgba), ImageOutputFormat::Png)
+}
+
+/// Save (or clear with `None`) the grid/perspective overlay of a reference.
+#[tauri::command]
+pub async fn set_reference_grid_overlay(
+    state: State<'_, AppState>,
+    reference_id: String,
+    overlay: Option<GridOverlay>,
+) -> Result<Reference, String> {
+    if let Some(o) = &overlay {
+        validate_overlay(o)?;
+    }
+
+    let references_file = state
+        .data_dir
+        .join("app_data")
+        .join("references.json");
+
+    let content = fs::read_to_string(&references_file)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+
+    let mut references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    let idx = references
+        .iter()
+        .position(|r| r.id == reference_id)
+        .ok_or_else(|| "Reference not found".to_string())?;
+
+    references[idx].grid_overlay = overlay;
+
+    let updated_reference = references[idx].clone();
+
+    let updated_content = serde_json::to_string_pretty(&references)
+        .map_err(|e| format!("Failed to serialize references: {}", e))?;
+    fs::write(&references_file, updated_content)
+        .map_err(|e| format!("Failed to write references: {}", e))?;
+
+    Ok(updated_reference)
+}
+
+/// Render the reference with its overlay and return a PNG data URL.
+/// Pass `overlay` to preview settings without saving them.
+#[tauri::command]
+pub async fn render_reference_overlay(
+    state: State<'_, AppState>,
+    reference_id: String,
+    overlay: Option<GridOverlay>,
+    max_size: Option<u32>,
+) -> Result<String, String> {
+    let png = render_with_overlay(&state, &reference_id, overlay, max_size)?;
+    Ok(to_data_url(&png, "image/png"))
+}
+
+/// Write the overlaid reference as a PNG (full resolution unless `max_size` is set)
+/// to `target_path`, e.g. one picked with the save dialog. Returns the written path.
+#[tauri::command]
+pub async fn export_reference_overlay(
+    state: State<'_, AppState>,
+    reference_id: String,
+    target_path: String,
+    overlay: Option<GridOverlay>,
+    max_size: Option<u32>,
+) -> Result<String, String> {
+    let mut path = PathBuf::from(&target_path);
+    let is_png = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("png"));
+    if !is_png {
+        path.set_extension("png");
+    }
+
+    let png = render_with_overlay(&state, &reference_id, overlay, max_size)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    fs::write(&path, png)
+        .map_err(|e| format!("Failed to write overlay image: {}", e))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
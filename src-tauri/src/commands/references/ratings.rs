@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use chrono::Utc;
+
+use crate::models::Reference;
+use crate::state::AppState;
+
+/// Increment the study counters of a reference (used when a journal entry is linked to it).
+pub(crate) fn bump_study_counters(data_dir: &Path, reference_id: &str) -> Result<Option<Reference>, String> {
+    let references_file = data_dir
+        .join("app_data")
+        .join("references.json");
+
+    let content = fs::read_to_string(&references_file)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+
+    let mut references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    // Journal entries may point at master studies or deleted references; nothing to count then
+    let idx = match references.iter().position(|r| r.id == reference_id) {
+        Some(idx) => idx,
+        None => return Ok(None),
+    };
+
+    references[idx].times_studied = references[idx].times_studied.saturating_add(1);
+    references[idx].last_studied_at = Some(Utc::now().timestamp_millis());
+
+    let updated_reference = references[idx].clone();
+
+    let updated_content = serde_json::to_string_pretty(&references)
+        .map_err(|e| format!("Failed to serialize references: {}", e))?;
+    fs::write(&references_file, updated_content)
+        .map_err(|e| format!("Failed to write references: {}", e))?;
+
+    Ok(Some(updated_reference))
+}
+
+#[tauri::command]
+pub async fn set_reference_rating(
+    state: State<'_, AppState>,
+    reference_id: String,
+    rating: u8,
+) -> Result<Reference, String> {
+    if rating > 5 {
+        return Err("Rating must be between 0 and 5".to_string());
+    }
+
+    let references_file = state
+        .data_dir
+        .join("app_data")
+        .join("references.json");
+
+    let content = fs::read_to_string(&references_file)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+
+    let mut references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    let idx = references
+        .iter()
+        .position(|r| r.id == reference_id)
+        .ok_or_else(|| "Reference not found".to_string())?;
+
+    references[idx].rating = rating;
+
+    let updated_reference = references[idx].clone();
+
+    let updated_content = serde_json::to_string_pretty(&references)
+        .map_err(|e| format!("Failed to serialize references: {}", e))?;
+    fs::write(&references_file, updated_content)
+        .map_err(|e| format!("Failed to write references: {}", e))?;
+
+    Ok(updated_reference)
+}
+
+#[tauri::command]
+pub async fn set_reference_favorite(
+    state: State<'_, AppState>,
+    reference_id: String,
+    favorite: bool,
+) -> Result<Reference, String> {
+    let references_file = state
+        .data_dir
+        .join("app_data")
+        .join("references.json");
+
+    let content = fs::read_to_string(&references_file)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+
+    let mut references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    let idx = references
+        .iter()
+        .position(|r| r.id == reference_id)
+        .ok_or_else(|| "Reference not found".to_string())?;
+
+    references[idx].favorite = favorite;
+
+    let updated_reference = references[idx].clone();
+
+    let updated_content = serde_json::to_string_pretty(&references)
+        .map_err(|e| format!("Failed to serialize references: {}", e))?;
+    fs::write(&references_file, updated_content)
+        .map_err(|e| format!("Failed to write references: {}", e))?;
+
+    Ok(updated_reference)
+}
+
+#[tauri::command]
+pub async fn record_reference_study(
+    state: State<'_, AppState>,
+    reference_id: String,
+) -> Result<Reference, String> {
+    bump_study_counters(&state.data_dir, &reference_id)?
+        .ok_or_else(|| "Reference not found".to_string())
+}
+
+#[tauri::command]
+pub async fn reset_reference_study_stats(
+    state: State<'_, AppState>,
+    reference_id: String,
+) -> Result<Reference, String> {
+    let references_file = state
+        .data_dir
+        .join("app_data")
+        .join("references.json");
+
+    let content = fs::read_to_string(&references_file)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+
+    let mut references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    let idx = references
+        .iter()
+        .position(|r| r.id == reference_id)
+        .ok_or_else(|| "Reference not found".to_string())?;
+
+    references[idx].times_studied = 0;
+    references[idx].last_studied_at = None;
+
+    let updated_reference = references[idx].clone();
+
+    let updated_content = serde_json::to_string_pretty(&references)
+        .map_err(|e| format!("Failed to serialize references: {}", e))?;
+    fs::write(&references_file, updated_content)
+        .map_err(|e| format!("Failed to write references: {}", e))?;
+
+    Ok(updated_reference)
+}
@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+use std::fs;
+use serde::Deserialize;
+use tauri::State;
+
+use crate::models::Reference;
+use crate::state::AppState;
+
+/// Filters and ordering for `search_references`. Every field is optional; an empty
+/// search returns all references newest first (same order as `get_references`).
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceSearch {
+    /// Free text matched against name, tags, note and source (case-insensitive)
+    pub query: Option<String>,
+    /// "main" or a folder id
+    pub folder_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Require every tag instead of any of them
+    #[serde(default)]
+    pub match_all_tags: bool,
+    pub favorite: Option<bool>,
+    pub min_rating: Option<u8>,
+    pub max_times_studied: Option<u32>,
+    /// Only references never studied or last studied before this timestamp (ms)
+    pub studied_before: Option<i64>,
+    /// "createdAt" (default), "name", "rating", "timesStudied" or "lastStudiedAt"
+    pub sort_by: Option<String>,
+    pub descending: Option<bool>,
+    pub limit: Option<usize>,
+}
+
+fn matches_query(reference: &Reference, needle: &str) -> bool {
+    let needle = needle.to_lowercase();
+    reference.original_name.to_lowercase().contains(&needle)
+        || reference.filename.to_lowercase().contains(&needle)
+        || reference.tags.iter().any(|t| t.to_lowercase().contains(&needle))
+        || reference.image_note.as_ref().is_some_and(|n| n.text.to_lowercase().contains(&needle))
+        || reference.image_source.as_ref().is_some_and(|s| s.text.to_lowercase().contains(&needle))
+}
+
+fn matches(reference: &Reference, search: &ReferenceSearch) -> bool {
+    if let Some(query) = search.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        if !matches_query(reference, query) {
+            return false;
+        }
+    }
+
+    if let Some(folder_id) = &search.folder_id {
+        let in_folder = if folder_id == "main" {
+            reference.folder_id.is_none()
+        } else {
+            reference.folder_id.as_deref() == Some(folder_id.as_str())
+        };
+        if !in_folder {
+            return false;
+        }
+    }
+
+    let wanted: Vec<&str> = search.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+    if !wanted.is_empty() {
+        let has = |tag: &str| reference.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+        let ok = if search.match_all_tags {
+            wanted.iter().all(|t| has(t))
+        } else {
+            wanted.iter().any(|t| has(t))
+        };
+        if !ok {
+            return false;
+        }
+    }
+
+    if let Some(favorite) = search.favorite {
+        if reference.favorite != favorite {
+            return false;
+        }
+    }
+    if let Some(min_rating) = search.min_rating {
+        if reference.rating < min_rating {
+            return false;
+        }
+    }
+    if let Some(max_times) = search.max_times_studied {
+        if reference.times_studied > max_times {
+            return false;
+        }
+    }
+    if let Some(before) = search.studied_before {
+        if reference.last_studied_at.is_some_and(|t| t >= before) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn compare(a: &Reference, b: &Reference, sort_by: &str) -> Ordering {
+    match sort_by {
+        "name" => a.original_name.to_lowercase().cmp(&b.original_name.to_lowercase()),
+        "rating" => a.rating.cmp(&b.rating),
+        "timesStudied" => a.times_studied.cmp(&b.times_studied),
+        // Never-studied references (None) sort before any timestamp
+        "lastStudiedAt" => a.last_studied_at.cmp(&b.last_studied_at),
+        _ => a.created_at.cmp(&b.created_at),
+    }
+}
+
+#[tauri::command]
+pub async fn search_references(
+    state: State<'_, AppState>,
+    search: ReferenceSearch,
+) -> Result<Vec<Reference>, String> {
+    let references_file = state
+        .data_dir
+        .join("app_data")
+        .join("references.json");
+
+    let content = fs::read_to_string(&references_file)
+        .map_err(|e| format!("Failed to read references: {}", e))?;
+
+    let references: Vec<Reference> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references: {}", e))?;
+
+    let mut results: Vec<Reference> = references
+        .into_iter()
+        .filter(|r| matches(r, &search))
+        .collect();
+
+    let sort_by = search.sort_by.as_deref().unwrap_or("createdAt");
+    // Default direction: newest first for dates, highest first for rating, A-Z for names,
+    // least studied first for study counters (to surface unused references)
+    let descending = search.descending.unwrap_or(matches!(sort_by, "createdAt" | "rating"));
+    // Stable sort keeps the stored (upload) order for ties
+    results.sort_by(|a, b| {
+        let ord = compare(a, b, sort_by);
+        if descending { ord.reverse() } else { ord }
+    });
+
+    if let Some(limit) = search.limit {
+        results.truncate(limit);
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,138 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use chrono::Utc;
+use image::{DynamicImage, ImageOutputFormat};
+
+use crate::imaging::{encode_image, fit_within, load_oriented, to_data_url, value_study};
+use crate::models::{DerivedFrom, Reference};
+use crate::state::AppState;
+use super::adjustments::render_reference;
+use super::crud::{find_reference, store_reference};
+use super::files::locate_image;
+
+/// Working resolution for value studies; notan shapes don't need full-size pixels.
+const DEFAULT_STUDY_SIZE: u32 = 1600;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueStudyOptions {
+    /// Number of flat values, 2-5
+    pub values: u8,
+    /// `values - 1` increasing luminance bounds (0-255); computed automatically when omitted
+    pub thresholds: Option<Vec<u8>>,
+    /// Gaussian pre-blur (sigma, px) to simplify shapes; default 1.5
+    pub smoothing: Option<f32>,
+    /// Regions smaller than this share of the image (%) are merged away; default 0.05
+    pub min_island_percent: Option<f32>,
+    pub max_size: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueStudyResult {
+    pub data_url: String,
+    pub width: u32,
+    pub height: u32,
+    pub thresholds: Vec<u8>,
+    /// Share of the image per value, darkest first (%)
+    pub coverage: Vec<f32>,
+    /// Set when the study was saved as a new reference
+    pub reference: Option<Reference>,
+}
+
+fn validate_options(options: &ValueStudyOptions) -> Result<(), String> {
+    if !(2..=5).contains(&options.values) {
+        return Err("Number of values must be between 2 and 5".to_string());
+    }
+    if let Some(t) = &options.thresholds {
+        if t.len() != options.values as usize - 1 {
+            return Err(format!("Expected {} thresholds for {} values", options.values - 1, options.values));
+        }
+        if t.windows(2).any(|w| w[0] >= w[1]) || t.first() == Some(&0) {
+            return Err("Thresholds must be strictly increasing and above 0".to_string());
+        }
+    }
+    if options.smoothing.is_some_and(|s| !(0.0..=50.0).contains(&s)) {
+        return Err("Smoothing must be within [0, 50]".to_string());
+    }
+    Ok(())
+}
+
+/// Generate a notan / value study from a reference (rendered with its rotation, crop and
+/// adjustments) or from any library image such as a master study (`image_path`).
+/// With `save`, the PNG is stored as a new reference linked to its source.
+#[tauri::command]
+pub async fn generate_value_study(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    reference_id: Option<String>,
+    image_path: Option<String>,
+    options: ValueStudyOptions,
+    save: Option<bool>,
+    folder_id: Option<String>,
+) -> Result<ValueStudyResult, String> {
+    validate_options(&options)?;
+
+    let (source_img, source_reference, source_name): (DynamicImage, Option<Reference>, String) =
+        match (&reference_id, &image_path) {
+            (Some(id), _) => {
+                let reference = find_reference(&state, id)?;
+                let img = render_reference(&state, &reference)?;
+                let name = reference.original_name.clone();
+                (img, Some(reference), name)
+            }
+            (None, Some(path)) => {
+                let full_path = locate_image(&app, &state, path)?;
+                let name = full_path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("image")
+                    .to_string();
+                (load_oriented(&full_path)?, None, name)
+            }
+            (None, None) => return Err("Either reference_id or image_path is required".to_string()),
+        };
+
+    let working = fit_within(source_img, Some(options.max_size.unwrap_or(DEFAULT_STUDY_SIZE)));
+    let study = value_study(
+        &working,
+        options.values as usize,
+        options.thresholds.as_deref(),
+        options.smoothing.unwrap_or(1.5),
+        options.min_island_percent.unwrap_or(0.05),
+    );
+
+    let (width, height) = study.image.dimensions();
+    let png = encode_image(&DynamicImage::ImageLuma8(study.image), ImageOutputFormat::Png)?;
+
+    let reference = if save.unwrap_or(false) {
+        let stem = Path::new(&source_name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("image")
+            .to_string();
+        let filename = format!("{}-value-study-{}v-{}.png", stem, options.values, Utc::now().timestamp_millis());
+        let original_name = format!("{} (value study, {} values).png", stem, options.values);
+        // Default to the source reference's folder so the study sits next to it
+        let target_folder = folder_id.or_else(|| source_reference.as_ref().and_then(|r| r.folder_id.clone()));
+        let derived_from = DerivedFrom {
+            kind: "valueStudy".to_string(),
+            reference_id: source_reference.as_ref().map(|r| r.id.clone()),
+            image_path: if source_reference.is_none() { image_path.clone() } else { None },
+            created_at: Utc::now().timestamp_millis(),
+        };
+        Some(store_reference(&state, filename, original_name, &png, target_folder, Some(derived_from))?)
+    } else {
+        None
+    };
+
+    Ok(ValueStudyResult {
+        data_url: to_data_url(&png, "image/png"),
+        width,
+        height,
+        thresholds: study.thresholds,
+        coverage: study.coverage,
+        reference,
+    })
+}
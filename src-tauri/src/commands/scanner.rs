@@ -0,0 +1,146 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use tauri::State;
+
+use crate::models::{PhotoJournalImage, Reference};
+use crate::scanner::{
+    encode_scan, mime_for_extension, platform_backend, run_scan, ColorMode, ScanArea, ScanOptions, ScannedImage,
+    ScannerInfo, MAX_RESOLUTION, MIN_RESOLUTION,
+};
+use crate::state::AppState;
+use crate::commands::journal_pieces::{append_piece_stages, read_journal_pieces, set_entries_piece};
+use crate::commands::photo_journal::store_photo_journal_image;
+use crate::commands::references::crud::store_reference;
+
+/// Scan and encode every page as `(filename, bytes)`. Line art is kept as PNG so
+/// edges stay crisp; colour and grey scans become JPEG.
+fn scan_pages(device_id: Option<&str>, options: &ScanOptions) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let lossless = options.color_mode == ColorMode::Lineart;
+    let stamp = Utc::now().timestamp_millis();
+    let mut pages = Vec::new();
+    for (i, data) in run_scan(device_id, options)?.iter().enumerate() {
+        let (bytes, ext) = encode_scan(data, lossless)?;
+        pages.push((format!("scan-{}-{}.{}", stamp, i + 1, ext), bytes));
+    }
+    Ok(pages)
+}
+
+fn to_scanned_image(filename: String, bytes: &[u8]) -> ScannedImage {
+    let ext = filename.rsplit('.').next().unwrap_or_default();
+    ScannedImage {
+        mime: mime_for_extension(ext).to_string(),
+        data_base64: general_purpose::STANDARD.encode(bytes),
+        filename,
+    }
+}
+
+/// Scan with the platform's own dialog (WIA) or the first SANE device, returning the
+/// pages as PNG for the frontend to handle.
+#[tauri::command]
+pub async fn scan_artwork(_state: State<'_, AppState>) -> Result<Vec<ScannedImage>, String> {
+    let pages = run_scan(None, &ScanOptions::default())?;
+    let stamp = Utc::now().timestamp_millis();
+    let mut scans = Vec::new();
+    for (i, data) in pages.iter().enumerate() {
+        let (bytes, ext) = encode_scan(data, true)?;
+        scans.push(to_scanned_image(format!("scan-{}-{}.{}", stamp, i + 1, ext), &bytes));
+    }
+    Ok(scans)
+}
+
+#[tauri::command]
+pub async fn list_scanners(_state: State<'_, AppState>) -> Result<Vec<ScannerInfo>, String> {
+    platform_backend().list_devices()
+}
+
+/// Scan one page from a device. `pageSize` and `dpi` predate `options` and are used
+/// when it leaves them unset; without options the scan area is `pageSize`, or A4
+/// when that is missing or unknown.
+#[tauri::command]
+pub async fn scan_with_device(
+    _state: State<'_, AppState>,
+    device_id: String,
+    page_size: Option<String>,
+    dpi: Option<i32>,
+    options: Option<ScanOptions>,
+) -> Result<ScannedImage, String> {
+    // As before options existed, an unknown legacy size scans A4
+    let page_size = page_size.map(|size| match ScanArea::from_page_size(&size) {
+        Some(_) => size,
+        None => "A4".to_string(),
+    });
+    let mut options = options.unwrap_or_else(|| ScanOptions {
+        page_size: page_size.clone().or(Some("A4".to_string())),
+        ..ScanOptions::default()
+    });
+    if options.page_size.is_none() {
+        options.page_size = page_size;
+    }
+    if options.resolution.is_none() {
+        options.resolution = dpi.map(|d| d.clamp(MIN_RESOLUTION as i32, MAX_RESOLUTION as i32) as u32);
+    }
+
+    let (filename, bytes) = scan_pages(Some(&device_id), &options)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "No image captured.".to_string())?;
+    Ok(to_scanned_image(filename, &bytes))
+}
+
+/// Scan straight into the Artwork Journal, optionally as new stages of a piece.
+/// Scans are already flat, so paper correction is skipped.
+#[tauri::command]
+pub async fn scan_to_journal(
+    state: State<'_, AppState>,
+    device_id: Option<String>,
+    options: Option<ScanOptions>,
+    prompt: Option<String>,
+    piece_id: Option<String>,
+    stage_label: Option<String>,
+) -> Result<Vec<PhotoJournalImage>, String> {
+    if let Some(piece_id) = piece_id.as_deref() {
+        if !read_journal_pieces(&state)?.iter().any(|p| p.id == piece_id) {
+            return Err("Piece not found".to_string());
+        }
+    }
+    let options = options.unwrap_or_default();
+
+    let mut images = Vec::new();
+    for (filename, bytes) in scan_pages(device_id.as_deref(), &options)? {
+        let image = store_photo_journal_image(
+            &state,
+            filename.clone(),
+            filename,
+            &bytes,
+            prompt.clone(),
+            Some(false),
+        )?;
+        images.push(image);
+    }
+
+    if let Some(piece_id) = piece_id.as_deref() {
+        let ids: Vec<String> = images.iter().map(|img| img.id.clone()).collect();
+        append_piece_stages(&state, piece_id, &ids, stage_label)?;
+        set_entries_piece(&state, &ids, Some(piece_id))?;
+        for image in images.iter_mut() {
+            image.piece_id = Some(piece_id.to_string());
+        }
+    }
+    Ok(images)
+}
+
+/// Scan straight into References (Main, or `folderId`).
+#[tauri::command]
+pub async fn scan_to_references(
+    state: State<'_, AppState>,
+    device_id: Option<String>,
+    options: Option<ScanOptions>,
+    folder_id: Option<String>,
+) -> Result<Vec<Reference>, String> {
+    let options = options.unwrap_or_default();
+    let mut references = Vec::new();
+    for (filename, bytes) in scan_pages(device_id.as_deref(), &options)? {
+        references.push(store_reference(&state, filename.clone(), filename, &bytes, folder_id.clone(), None)?);
+    }
+    Ok(references)
+}
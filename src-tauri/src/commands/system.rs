@@ -2,26 +2,10 @@ use std::process::Command;
 use std::fs;
 use tauri::{State, AppHandle};
 use crate::state::AppState;
-use base64::{engine::general_purpose, Engine as _};
-use image::{ImageOutputFormat};
-use std::io::Cursor;
-use serde::Serialize;
+use base64::Engine as _;
 use std::path::Path;
 use std::env;
 
-#[derive(Serialize)]
-pub struct ScannedImage {
-    pub filename: String,
-    pub data_base64: String,
-    pub mime: String,
-}
-
-#[derive(Serialize)]
-pub struct ScannerInfo {
-    pub id: String,
-    pub name: String,
-}
-
 #[tauri::command]
 pub async fn open_url_in_chrome(_state: State<'_, AppState>, url: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
@@ -85,166 +69,6 @@ pub async fn open_url_in_chrome(_state: State<'_, AppState>, url: String) -> Res
     }
 }
 
-#[tauri::command]
-pub async fn scan_artwork(_state: State<'_, AppState>) -> Result<Vec<ScannedImage>, String> {
-    #[cfg(not(target_os = "windows"))]
-    {
-        return Err("Scanning is currently supported on Windows only.".to_string());
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        let script = r#"
-            $ErrorActionPreference = 'Stop'
-            Add-Type -AssemblyName System.Drawing
-            Add-Type @"
-using System;
-using System.Runtime.InteropServices;
-public class Win {
-  [DllImport("user32.dll")]
-  public static extern bool SetForegroundWindow(IntPtr hWnd);
-  [DllImport("user32.dll")]
-  public static extern bool ShowWindowAsync(IntPtr hWnd, int nCmdShow);
-  [DllImport("user32.dll")]
-  public static extern bool SetWindowPos(IntPtr hWnd, IntPtr hWndInsertAfter, int X, int Y, int cx, int cy, uint uFlags);
-  public static readonly IntPtr HWND_TOPMOST = new IntPtr(-1);
-  public static readonly IntPtr HWND_NOTOPMOST = new IntPtr(-2);
-}
-"@
-            $null = [Win]::SetForegroundWindow((Get-Process -Id $PID).MainWindowHandle)
-            $null = [Win]::ShowWindowAsync((Get-Process -Id $PID).MainWindowHandle, 5)
-            $null = [Win]::SetWindowPos((Get-Process -Id $PID).MainWindowHandle, [Win]::HWND_TOPMOST, 0,0,0,0, 0x0003)
-            $dialog = New-Object -ComObject WIA.CommonDialog
-            $image = $dialog.ShowAcquireImage()
-            if ($null -eq $image) { exit 0 }            # Convert to PNG to avoid format mismatches
-            $convertFilterId = "{B96B3CAF-0728-11D3-9D7B-0000F81EF32E}" # PNG
-            $imgProcess = New-Object -ComObject WIA.ImageProcess
-            $imgProcess.Filters.Add($imgProcess.FilterInfos["Convert"].FilterID) | Out-Null
-            $imgProcess.Filters[1].Properties["FormatID"].Value = $convertFilterId
-            $converted = $imgProcess.Apply($image)
-
-            $ext = '.png'
-            $name = 'scan_' + [guid]::NewGuid().ToString() + $ext
-            $path = Join-Path $env:TEMP $name
-            $converted.SaveFile($path)
-            $path
-        "#;
-
-        let output = Command::new("powershell")
-            .args([
-                "-NoLogo",
-                "-NoProfile",
-                "-Sta",
-                "-ExecutionPolicy",
-                "Bypass",
-                "-Command",
-                script
-            ])
-            .output()
-            .map_err(|e| format!("Failed to launch PowerShell for scanning: {}", e))?;        if !output.status.success() {
-            return Err(format!(
-                "Scanner wizard failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut scans: Vec<ScannedImage> = Vec::new();
-
-        for line in stdout.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-            let path = std::path::Path::new(trimmed);
-            if !path.exists() {
-                continue;
-            }
-            let data = fs::read(path)
-                .map_err(|e| format!("Failed to read scanned file {}: {}", trimmed, e))?;
-            let encoded = general_purpose::STANDARD.encode(&data);
-            let mime = if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-                match ext.to_ascii_lowercase().as_str() {
-                    "jpg" | "jpeg" => "image/jpeg",
-                    "png" => "image/png",
-                    _ => "image/jpeg",
-                }
-            } else {
-                "image/jpeg"
-            }
-            .to_string();
-
-            let filename = path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .unwrap_or("scan.jpg")
-                .to_string();
-
-            scans.push(ScannedImage {
-                filename,
-                data_base64: encoded,
-                mime,
-            });
-
-            let _ = fs::remove_file(path);
-        }        if scans.is_empty() {
-            return Err("No images were returned from the scanner.".to_string());
-        }        Ok(scans)
-    }
-}
-
-#[tauri::command]
-pub async fn list_scanners(_state: State<'_, AppState>) -> Result<Vec<ScannerInfo>, String> {
-    #[cfg(not(target_os = "windows"))]
-    {
-        return Err("Listing scanners is supported on Windows only.".to_string());
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        let script = r#"
-            $ErrorActionPreference = 'Stop'
-            $dm = New-Object -ComObject WIA.DeviceManager
-            $out = @()
-            foreach ($info in $dm.DeviceInfos) {
-              if ($info.Type -eq 1) { # Scanner
-                $out += ($info.DeviceID + '|' + $info.Properties["Name"].Value)
-              }
-            }
-            $out -join "`n"
-        "#;        let output = Command::new("powershell")
-            .args([
-                "-NoLogo",
-                "-NoProfile",
-                "-Sta",
-                "-ExecutionPolicy",
-                "Bypass",
-                "-Command",
-                script
-            ])
-            .output()
-            .map_err(|e| format!("Failed to enumerate scanners: {}", e))?;
-
-        if !output.status.success() {
-            return Err(format!(
-                "Scanner listing failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut scanners = Vec::new();
-        for line in stdout.lines() {
-            if let Some((id, name)) = line.split_once('|') {
-                scanners.push(ScannerInfo {
-                    id: id.trim().to_string(),
-                    name: name.trim().to_string(),
-                });
-            }
-        }
-
-        Ok(scanners)
-    }
-}
-
 #[tauri::command]
 pub async fn list_warmups(app: AppHandle, state: State<'_, AppState>) -> Result<Vec<String>, String> {
     // Search priority:
@@ -381,147 +205,3 @@ pub async fn get_warmup_image_data(
 
     Ok(data_url)
 }
-
-#[tauri::command]
-pub async fn scan_with_device(
-    _state: State<'_, AppState>,
-    device_id: String,
-    page_size: Option<String>,
-    dpi: Option<i32>,
-) -> Result<ScannedImage, String> {
-    #[cfg(not(target_os = "windows"))]
-    {
-        return Err("Scanning is supported on Windows only.".to_string());
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        let page = page_size.unwrap_or_else(|| "A4".to_string());
-        let color = "COLOR".to_string(); // default color
-        let dpi_val = dpi.unwrap_or(300).max(75).min(1200);        let script = format!(
-            r#"
-            $ErrorActionPreference = 'Stop'
-            Add-Type -AssemblyName System.Drawing
-            Add-Type @"
-using System;
-using System.Runtime.InteropServices;
-public class Win {{
-  [DllImport("user32.dll")]
-  public static extern bool SetForegroundWindow(IntPtr hWnd);
-  [DllImport("user32.dll")]
-  public static extern bool ShowWindowAsync(IntPtr hWnd, int nCmdShow);
-  [DllImport("user32.dll")]
-  public static extern bool SetWindowPos(IntPtr hWnd, IntPtr hWndInsertAfter, int X, int Y, int cx, int cy, uint uFlags);
-  public static readonly IntPtr HWND_TOPMOST = new IntPtr(-1);
-  public static readonly IntPtr HWND_NOTOPMOST = new IntPtr(-2);
-}}
-"@
-            $null = [Win]::SetForegroundWindow((Get-Process -Id $PID).MainWindowHandle)
-            $null = [Win]::ShowWindowAsync((Get-Process -Id $PID).MainWindowHandle, 5)
-            $null = [Win]::SetWindowPos((Get-Process -Id $PID).MainWindowHandle, [Win]::HWND_TOPMOST, 0,0,0,0, 0x0003)
-            $dm = New-Object -ComObject WIA.DeviceManager
-            $dev = $dm.DeviceInfos | Where-Object {{ $_.DeviceID -eq '{device}' }}
-            if (-not $dev) {{ exit 0 }}
-            $device = $dev.Connect()
-
-            # Configure page size at 300 DPI
-            $dpi = {dpi}
-            $size = "{page}"
-            $mode = "{color}"
-            switch ($size.ToUpper()) {{
-              "A5" {{
-                $widthPx  = [int](5.83 * $dpi)   # 148mm
-                $heightPx = [int](8.27 * $dpi)   # 210mm
-              }}
-              default {{
-                $widthPx  = [int](8.27 * $dpi)   # 210mm (A4)
-                $heightPx = [int](11.69 * $dpi)  # 297mm
-              }}
-            }}
-            $item = $device.Items[1]
-            $intent = if ($mode.ToUpper() -eq "GRAYSCALE") {{ 0x2 }} else {{ 0x1 }}  # WIA_INTENT_IMAGE_TYPE_GRAYSCALE or COLOR
-            try {{
-              $item.Properties["3096"].Value = 0           # WIA_IPS_PAGE_SIZE (Custom)
-              $item.Properties["6147"].Value = $dpi   # WIA_IPS_XRES
-              $item.Properties["6148"].Value = $dpi   # WIA_IPS_YRES
-              $item.Properties["6149"].Value = 0      # WIA_IPS_XPOS
-              $item.Properties["6150"].Value = 0      # WIA_IPS_YPOS
-              $item.Properties["6151"].Value = $widthPx  # WIA_IPS_XEXTENT
-              $item.Properties["6152"].Value = $heightPx # WIA_IPS_YEXTENT
-              $item.Properties["6146"].Value = $intent # WIA_IPS_CUR_INTENT
-            }} catch {{}}
-
-            # Use CommonDialog to perform transfer with JPEG format
-            $convertFilterId = "{{B96B3CAA-0728-11D3-9D7B-0000F81EF32E}}"
-            $common = New-Object -ComObject WIA.CommonDialog
-            $image = $common.ShowTransfer($item, $convertFilterId)
-            if ($null -eq $image) {{ exit 0 }}
-
-            $ext = '.jpg'
-            $name = 'scan_' + [guid]::NewGuid().ToString() + $ext
-            $path = Join-Path $env:TEMP $name
-            $image.SaveFile($path)
-            $path
-        "#,
-            device = device_id.replace('\'', "''"),
-            dpi = dpi_val
-        );
-
-        let output = Command::new("powershell")
-            .args([
-                "-NoLogo",
-                "-NoProfile",
-                "-Sta",
-                "-ExecutionPolicy",
-                "Bypass",
-                "-Command",
-                &script
-            ])
-            .output()
-            .map_err(|e| format!("Failed to launch PowerShell for scanning: {}", e))?;
-
-        if !output.status.success() {
-            return Err(format!(
-                "Scanner capture failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let path_line = stdout.lines().next().unwrap_or_default().trim().to_string();
-        if path_line.is_empty() {
-            return Err("No image captured.".to_string());
-        }
-        let path = std::path::Path::new(&path_line);
-        if !path.exists() {
-            return Err("Scanned file not found.".to_string());
-        }
-
-        let data = fs::read(path)
-            .map_err(|e| format!("Failed to read scanned file {}: {}", path_line, e))?;
-
-        // Ensure JPEG encoding regardless of WIA output (some devices return BMP bytes even with a JPG extension)
-        let mut jpeg_buf: Vec<u8> = Vec::new();
-        let jpeg_encoded = match image::load_from_memory(&data) {
-            Ok(img) => img.write_to(&mut Cursor::new(&mut jpeg_buf), ImageOutputFormat::Jpeg(90)),
-            Err(_) => Err(image::ImageError::IoError(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "decode failed",
-            ))),
-        };        let (encoded_bytes, mime) = if jpeg_encoded.is_ok() {
-            (jpeg_buf, "image/jpeg".to_string())
-        } else {
-            // Fallback to original data
-            (data, "image/jpeg".to_string())
-        };        let encoded = general_purpose::STANDARD.encode(&encoded_bytes);
-        let filename = path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("scan.jpg")
-            .to_string();        let _ = fs::remove_file(path);        Ok(ScannedImage {
-            filename,
-            data_base64: encoded,
-            mime,
-        })
-    }
-}
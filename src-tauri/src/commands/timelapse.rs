@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use image::{DynamicImage, Rgba, RgbaImage};
+use image::imageops::FilterType;
+
+use crate::state::AppState;
+use crate::commands::comparison::render_journal_artwork;
+use crate::commands::journal_pieces::read_journal_pieces;
+use crate::commands::photo_journal::read_photo_journal;
+use crate::imaging::{
+    crossfade, draw_text, encode_gif, encode_webp_animation, fill_rect, fit_within, register_images,
+    scale_to_height, text_size, warp_to_frame, AnimationFrame,
+};
+
+const DEFAULT_MAX_SIZE: u32 = 800;
+const DEFAULT_FRAME_MS: u32 = 800;
+const DEFAULT_CROSSFADE_FRAMES: u32 = 4;
+/// Browsers stretch shorter GIF delays to 100 ms
+const MIN_FRAME_MS: u32 = 20;
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelapseFormat {
+    Gif,
+    Webp,
+}
+
+impl TimelapseFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            TimelapseFormat::Gif => "gif",
+            TimelapseFormat::Webp => "webp",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelapseOptions {
+    /// Defaults to the target path's extension, then GIF
+    pub format: Option<TimelapseFormat>,
+    /// Longest side of the animation (64-2048, default 800)
+    pub max_size: Option<u32>,
+    /// How long each stage is shown (default 800 ms)
+    pub frame_duration_ms: Option<u32>,
+    /// Per-stage overrides in stage order; missing or 0 entries use `frame_duration_ms`
+    #[serde(default)]
+    pub stage_durations_ms: Vec<u32>,
+    /// Total crossfade between consecutive stages; 0 or unset cuts
+    pub crossfade_ms: Option<u32>,
+    /// Blended frames per crossfade (1-12, default 4)
+    pub crossfade_frames: Option<u32>,
+    /// Register every stage onto the last one (camera moved between photos)
+    #[serde(default)]
+    pub auto_register: bool,
+    /// Stamp each stage's label (or "STAGE n/N") in the corner
+    #[serde(default)]
+    pub show_labels: bool,
+    /// 0 (default) loops forever
+    pub loop_count: Option<u16>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelapseResult {
+    pub path: String,
+    pub format: String,
+    pub width: u32,
+    pub height: u32,
+    pub frame_count: usize,
+    pub size: u64,
+    /// Stages shown unregistered because alignment failed (auto-register only)
+    pub unregistered_stage_ids: Vec<String>,
+}
+
+/// Scale `img` to fit the canvas and center it on white.
+fn fit_into_canvas(img: &DynamicImage, width: u32, height: u32) -> RgbaImage {
+    let fitted = img.resize(width, height, FilterType::Lanczos3).to_rgba8();
+    let mut canvas = RgbaImage::from_pixel(width, height, WHITE);
+    let x = (width - fitted.width()) / 2;
+    let y = (height - fitted.height()) / 2;
+    image::imageops::overlay(&mut canvas, &fitted, x as i64, y as i64);
+    canvas
+}
+
+fn stamp_label(frame: &mut RgbaImage, label: &str) {
+    let scale = (frame.height() / 240).max(1);
+    let (text_w, text_h) = text_size(label, scale);
+    let pad = 4 * scale;
+    let y = frame.height().saturating_sub(text_h + pad * 3) as i64;
+    fill_rect(frame, pad as i64, y, text_w + pad * 2, text_h + pad * 2, Rgba([0, 0, 0, 150]));
+    draw_text(frame, (pad * 2) as i64, y + pad as i64, label, scale, Rgba([255, 255, 255, 255]));
+}
+
+/// Encode the stages of a journal piece as an animated GIF or (lossless) WebP.
+///
+/// Every stage is brought to the last stage's frame: letterboxed to the same size, or
+/// with `autoRegister` aligned onto it (similarity transform on edge maps, as in
+/// `analyze_alignment`). Stages that can't be registered fall back to letterboxing.
+#[tauri::command]
+pub async fn export_piece_timelapse(
+    state: State<'_, AppState>,
+    piece_id: String,
+    target_path: String,
+    options: Option<TimelapseOptions>,
+) -> Result<TimelapseResult, String> {
+    let options = options.unwrap_or_default();
+    let max_size = options.max_size.unwrap_or(DEFAULT_MAX_SIZE);
+    if !(64..=2048).contains(&max_size) {
+        return Err("Max size must be between 64 and 2048".to_string());
+    }
+    let frame_ms = options.frame_duration_ms.unwrap_or(DEFAULT_FRAME_MS);
+    if !(MIN_FRAME_MS..=60_000).contains(&frame_ms) {
+        return Err(format!("Frame duration must be between {} and 60000 ms", MIN_FRAME_MS));
+    }
+    if options.stage_durations_ms.iter().any(|&d| d != 0 && !(MIN_FRAME_MS..=60_000).contains(&d)) {
+        return Err(format!("Stage durations must be between {} and 60000 ms", MIN_FRAME_MS));
+    }
+    let crossfade_ms = options.crossfade_ms.unwrap_or(0);
+    if crossfade_ms > 10_000 {
+        return Err("Crossfade must be at most 10000 ms".to_string());
+    }
+    let crossfade_frames = options.crossfade_frames.unwrap_or(DEFAULT_CROSSFADE_FRAMES);
+    if !(1..=12).contains(&crossfade_frames) {
+        return Err("Crossfade frames must be between 1 and 12".to_string());
+    }
+    // Fewer blended frames rather than delays too short to play back
+    let crossfade_frames = crossfade_frames.min(crossfade_ms / MIN_FRAME_MS);
+
+    let mut path = PathBuf::from(&target_path);
+    let format = options.format.unwrap_or_else(|| {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()) {
+            Some(ext) if ext == "webp" => TimelapseFormat::Webp,
+            _ => TimelapseFormat::Gif,
+        }
+    });
+    path.set_extension(format.extension());
+
+    let piece = read_journal_pieces(&state)?
+        .into_iter()
+        .find(|p| p.id == piece_id)
+        .ok_or_else(|| "Piece not found".to_string())?;
+    if piece.stages.len() < 2 {
+        return Err("A timelapse needs at least two stages".to_string());
+    }
+    let journal = read_photo_journal(&state)?;
+    let mut stages: Vec<DynamicImage> = Vec::with_capacity(piece.stages.len());
+    for stage in &piece.stages {
+        let entry = journal
+            .iter()
+            .find(|img| img.id == stage.image_id)
+            .ok_or_else(|| format!("Journal entry not found: {}", stage.image_id))?;
+        stages.push(render_journal_artwork(&state, entry)?);
+    }
+
+    // The finished stage defines the frame
+    let last = stages.len() - 1;
+    let anchor = fit_within(stages[last].clone(), Some(max_size));
+    let (width, height) = (anchor.width(), anchor.height());
+
+    let mut unregistered_stage_ids = Vec::new();
+    let mut frames: Vec<RgbaImage> = Vec::with_capacity(stages.len());
+    for (i, img) in stages.iter().enumerate() {
+        let frame = if i == last {
+            fit_into_canvas(&anchor, width, height)
+        } else if options.auto_register {
+            // Work near the output resolution; the transform is resolution independent
+            let moving = scale_to_height(img, height);
+            match register_images(&moving, &anchor) {
+                Ok(transform) => warp_to_frame(&moving, &transform, width, height, WHITE),
+                Err(_) => {
+                    unregistered_stage_ids.push(piece.stages[i].image_id.clone());
+                    fit_into_canvas(img, width, height)
+                }
+            }
+        } else {
+            fit_into_canvas(img, width, height)
+        };
+        frames.push(frame);
+    }
+
+    if options.show_labels {
+        let count = frames.len();
+        for (i, frame) in frames.iter_mut().enumerate() {
+            let label = piece.stages[i]
+                .label
+                .clone()
+                .unwrap_or_else(|| format!("Stage {}/{}", i + 1, count));
+            stamp_label(frame, &label.to_uppercase());
+        }
+    }
+
+    let mut animation: Vec<AnimationFrame> = Vec::new();
+    for (i, frame) in frames.iter().enumerate() {
+        let duration_ms = match options.stage_durations_ms.get(i) {
+            Some(&d) if d > 0 => d,
+            _ => frame_ms,
+        };
+        animation.push(AnimationFrame { image: frame.clone(), duration_ms });
+        if let Some(next) = frames.get(i + 1).filter(|_| crossfade_frames > 0) {
+            let step_ms = crossfade_ms / crossfade_frames;
+            for k in 1..=crossfade_frames {
+                let t = k as f32 / (crossfade_frames + 1) as f32;
+                animation.push(AnimationFrame { image: crossfade(frame, next, t), duration_ms: step_ms });
+            }
+        }
+    }
+
+    let loop_count = options.loop_count.unwrap_or(0);
+    let bytes = match format {
+        TimelapseFormat::Gif => encode_gif(&animation, loop_count)?,
+        TimelapseFormat::Webp => encode_webp_animation(&animation, loop_count)?,
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    fs::write(&path, &bytes)
+        .map_err(|e| format!("Failed to write timelapse: {}", e))?;
+
+    Ok(TimelapseResult {
+        path: path.to_string_lossy().to_string(),
+        format: format.extension().to_string(),
+        width,
+        height,
+        frame_count: animation.len(),
+        size: bytes.len() as u64,
+        unregistered_stage_ids,
+    })
+}
@@ -0,0 +1,103 @@
+//! Non-destructive adjustment stack applied on top of a rendered reference.
+
+use image::DynamicImage;
+
+use crate::models::ImageAdjustment;
+
+/// Check parameter ranges before an adjustment stack is stored.
+pub fn validate_adjustments(adjustments: &[ImageAdjustment]) -> Result<(), String> {
+    for adjustment in adjustments {
+        match adjustment {
+            ImageAdjustment::FlipHorizontal
+            | ImageAdjustment::FlipVertical
+            | ImageAdjustment::Greyscale => {}
+            ImageAdjustment::BrightnessContrast { brightness, contrast } => {
+                if !(-100.0..=100.0).contains(brightness) || !(-100.0..=100.0).contains(contrast) {
+                    return Err("Brightness/contrast must be within [-100, 100]".to_string());
+                }
+            }
+            ImageAdjustment::Levels { black, white, gamma } => {
+                if black >= white {
+                    return Err("Levels black point must be below the white point".to_string());
+                }
+                if !(0.1..=10.0).contains(gamma) {
+                    return Err("Levels gamma must be within [0.1, 10]".to_string());
+                }
+            }
+            ImageAdjustment::Blur { sigma } => {
+                if !(0.0..=100.0).contains(sigma) {
+                    return Err("Blur sigma must be within [0, 100]".to_string());
+                }
+            }
+            ImageAdjustment::Posterize { levels } => {
+                if !(2..=32).contains(levels) {
+                    return Err("Posterize levels must be within [2, 32]".to_string());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply a per-channel lookup table to the color channels (alpha untouched).
+fn apply_lut(img: DynamicImage, lut: &[u8; 256]) -> DynamicImage {
+    let mut rgba = img.to_rgba8();
+    for p in rgba.pixels_mut() {
+        p[0] = lut[p[0] as usize];
+        p[1] = lut[p[1] as usize];
+        p[2] = lut[p[2] as usize];
+    }
+    DynamicImage::ImageRgba8(rgba)
+}
+
+fn levels_lut(black: u8, white: u8, gamma: f32) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    let range = (white as f32 - black as f32).max(1.0);
+    for (i, v) in lut.iter_mut().enumerate() {
+        let t = ((i as f32 - black as f32) / range).clamp(0.0, 1.0);
+        *v = (t.powf(1.0 / gamma) * 255.0).round() as u8;
+    }
+    lut
+}
+
+/// Evenly spaced tone steps, e.g. 3 levels -> 0, 128, 255.
+pub fn posterize_lut(levels: u8) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    let steps = levels.max(2) as f32 - 1.0;
+    for (i, v) in lut.iter_mut().enumerate() {
+        let step = (i as f32 / 255.0 * steps).round();
+        *v = (step / steps * 255.0).round() as u8;
+    }
+    lut
+}
+
+fn apply_one(img: DynamicImage, adjustment: &ImageAdjustment) -> DynamicImage {
+    match adjustment {
+        ImageAdjustment::FlipHorizontal => img.fliph(),
+        ImageAdjustment::FlipVertical => img.flipv(),
+        ImageAdjustment::Greyscale => {
+            // Keep an RGBA buffer so later steps and encoders see a consistent color type
+            DynamicImage::ImageRgba8(DynamicImage::ImageLumaA8(img.to_luma_alpha8()).to_rgba8())
+        }
+        ImageAdjustment::BrightnessContrast { brightness, contrast } => {
+            let mut out = img;
+            if *contrast != 0.0 {
+                out = out.adjust_contrast(*contrast);
+            }
+            if *brightness != 0.0 {
+                out = out.brighten((*brightness * 2.55).round() as i32);
+            }
+            out
+        }
+        ImageAdjustment::Levels { black, white, gamma } => apply_lut(img, &levels_lut(*black, *white, *gamma)),
+        ImageAdjustment::Blur { sigma } => {
+            if *sigma > 0.0 { img.blur(*sigma) } else { img }
+        }
+        ImageAdjustment::Posterize { levels } => apply_lut(img, &posterize_lut(*levels)),
+    }
+}
+
+/// Apply the stack in order.
+pub fn apply_adjustments(img: DynamicImage, adjustments: &[ImageAdjustment]) -> DynamicImage {
+    adjustments.iter().fold(img, apply_one)
+}
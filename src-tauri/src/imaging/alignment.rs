@@ -0,0 +1,677 @@
+//! Drawing-to-reference alignment: similarity transform estimation on edge maps,
+//! residual heatmap and per-region proportion errors.
+//!
+//! Both images are brought to a common height and reduced to normalized Sobel edge
+//! maps, so a pencil drawing and a photo can be matched. Edge points of the artwork
+//! are mapped into the reference and scored against the (blurred) reference edges;
+//! the transform is found with a coarse grid search followed by pattern-search
+//! refinement over an image pyramid.
+
+use image::{DynamicImage, GrayImage, Rgba, RgbaImage};
+use image::imageops::FilterType;
+
+use super::transform::{flatten_onto, sample_bilinear};
+
+const PYRAMID_HEIGHTS: [u32; 3] = [64, 128, 256];
+const MAX_POINTS: usize = 2500;
+/// The exhaustive coarse search only needs a sketch of the drawing
+const COARSE_MAX_POINTS: usize = 400;
+/// Residuals above this share of the reference height count as unrelated strokes
+const RESIDUAL_CAP: f64 = 0.08;
+/// Residual (share of reference height) drawn fully red in the heatmap
+const HEAT_FULL_SCALE: f64 = 0.03;
+/// Minimum edge points in a region for a local measurement
+const MIN_REGION_POINTS: usize = 30;
+/// Displacement field resolution; each third-region covers 2x2 cells
+const CELLS: usize = 6;
+const MIN_CELL_POINTS: usize = 15;
+const LOCAL_REGULARIZATION: f64 = 0.05;
+
+const REGION_NAMES: [&str; 9] = [
+    "top-left", "top", "top-right",
+    "left", "center", "right",
+    "bottom-left", "bottom", "bottom-right",
+];
+
+/// Maps artwork pixels onto the reference once both have the same height:
+/// `q = ref_center + scale * R(rotation) * A(aspect) * (p - art_center) + t * height`.
+#[derive(Debug, Clone, Copy)]
+pub struct AlignmentTransform {
+    pub scale: f64,
+    /// Radians, clockwise in image coordinates
+    pub rotation: f64,
+    /// Translation in reference heights
+    pub tx: f64,
+    pub ty: f64,
+    /// Horizontal stretch applied to the artwork (x * sqrt(a), y / sqrt(a)); 1 for a pure similarity
+    pub aspect: f64,
+}
+
+impl AlignmentTransform {
+    const IDENTITY: AlignmentTransform = AlignmentTransform { scale: 1.0, rotation: 0.0, tx: 0.0, ty: 0.0, aspect: 1.0 };
+
+    fn params(&self) -> [f64; 5] {
+        [self.rotation, self.scale.ln(), self.tx, self.ty, self.aspect.ln()]
+    }
+
+    fn from_params(p: &[f64; 5]) -> Self {
+        AlignmentTransform { rotation: p[0], scale: p[1].exp(), tx: p[2], ty: p[3], aspect: p[4].exp() }
+    }
+
+    fn map(&self, x: f64, y: f64, level: &Level) -> (f64, f64) {
+        let stretch = self.aspect.sqrt();
+        let (ax, ay) = (x * stretch, y / stretch);
+        let (c, s) = (self.rotation.cos(), self.rotation.sin());
+        (
+            level.ref_center.0 + self.scale * (c * ax - s * ay) + self.tx * level.height,
+            level.ref_center.1 + self.scale * (s * ax + c * ay) + self.ty * level.height,
+        )
+    }
+}
+
+/// Local misfit of one third-by-third region of the reference.
+#[derive(Debug, Clone)]
+pub struct RegionError {
+    pub name: &'static str,
+    /// Edge points of the drawing that fall into the region
+    pub points: usize,
+    /// Where the drawn region sits relative to where it should (share of reference width/height)
+    pub offset_x: Option<f64>,
+    pub offset_y: Option<f64>,
+    /// Drawn size relative to the reference; 0.05 = drawn 5% too large
+    pub scale_error: Option<f64>,
+    /// Mean distance from drawn edges to reference edges (share of reference height)
+    pub mean_residual: f64,
+}
+
+pub struct AlignmentAnalysis {
+    /// Best similarity transform (aspect fixed at 1)
+    pub similarity: AlignmentTransform,
+    /// Drawing width/height ratio relative to the reference; 0.04 = 4% too wide
+    pub aspect_error: f64,
+    /// Weighted share of drawn edges that land on reference edges (0-1)
+    pub match_score: f64,
+    /// Mean distance from drawn edges to reference edges (share of reference height)
+    pub mean_residual: f64,
+    pub regions: Vec<RegionError>,
+    heat: Vec<f32>,
+    density: Vec<f32>,
+    heat_width: usize,
+    heat_height: usize,
+}
+
+struct FloatMap {
+    width: usize,
+    height: usize,
+    data: Vec<f32>,
+}
+
+impl FloatMap {
+    /// Bilinear sample with integer coordinates at pixel centers; 0 outside.
+    fn sample(&self, x: f64, y: f64) -> f32 {
+        if x < 0.0 || y < 0.0 {
+            return 0.0;
+        }
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        if x0 + 1 >= self.width || y0 + 1 >= self.height {
+            return 0.0;
+        }
+        let (fx, fy) = ((x - x0 as f64) as f32, (y - y0 as f64) as f32);
+        let i = y0 * self.width + x0;
+        let top = self.data[i] * (1.0 - fx) + self.data[i + 1] * fx;
+        let bottom = self.data[i + self.width] * (1.0 - fx) + self.data[i + self.width + 1] * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    fn get(&self, x: f64, y: f64) -> Option<f32> {
+        let (xi, yi) = (x.round(), y.round());
+        if xi < 0.0 || yi < 0.0 || xi as usize >= self.width || yi as usize >= self.height {
+            return None;
+        }
+        Some(self.data[yi as usize * self.width + xi as usize])
+    }
+}
+
+struct EdgePoint {
+    x: f64,
+    y: f64,
+    weight: f32,
+}
+
+/// One pyramid level: reference edges for scoring and artwork edge points relative
+/// to the artwork center.
+struct Level {
+    height: f64,
+    ref_center: (f64, f64),
+    ref_edges: FloatMap,
+    ref_spread: FloatMap,
+    points: Vec<EdgePoint>,
+}
+
+/// Three box-blur passes approximate a gaussian of the given radius.
+fn box_blur(data: &mut [f32], width: usize, height: usize, radius: usize) {
+    if radius == 0 || width == 0 || height == 0 {
+        return;
+    }
+    let mut line: Vec<f32> = Vec::new();
+    for _ in 0..3 {
+        for (len, stride, count, step) in [(width, 1, height, width), (height, width, width, 1)] {
+            for k in 0..count {
+                let base = k * step;
+                line.clear();
+                line.extend((0..len).map(|i| data[base + i * stride]));
+                let mut sum: f32 = 0.0;
+                let window = (2 * radius + 1) as f32;
+                // Clamp-to-edge running sum
+                for i in 0..=radius {
+                    sum += line[i.min(len - 1)];
+                }
+                sum += line[0] * radius as f32;
+                for i in 0..len {
+                    data[base + i * stride] = sum / window;
+                    let add = line[(i + radius + 1).min(len - 1)];
+                    let remove = line[i.saturating_sub(radius)];
+                    sum += add - remove;
+                }
+            }
+        }
+    }
+}
+
+fn gray_at_height(img: &DynamicImage, height: u32) -> GrayImage {
+    let flat = DynamicImage::ImageRgb8(flatten_onto(img, [255, 255, 255]));
+    let width = ((img.width() as f64 * height as f64 / img.height().max(1) as f64).round() as u32).max(8);
+    flat.resize_exact(width, height, FilterType::Triangle).to_luma8()
+}
+
+/// Sobel magnitude normalized by its 98th percentile, so contrast differences
+/// between a photo and a drawing cancel out.
+fn edge_map(gray: &GrayImage) -> FloatMap {
+    let smoothed = image::imageops::blur(gray, 1.0);
+    let (w, h) = (smoothed.width() as usize, smoothed.height() as usize);
+    let raw = smoothed.as_raw();
+    let px = |x: usize, y: usize| raw[y * w + x] as f32;
+    let mut data = vec![0f32; w * h];
+    for y in 1..h.saturating_sub(1) {
+        for x in 1..w.saturating_sub(1) {
+            let gx = (px(x + 1, y - 1) + 2.0 * px(x + 1, y) + px(x + 1, y + 1))
+                - (px(x - 1, y - 1) + 2.0 * px(x - 1, y) + px(x - 1, y + 1));
+            let gy = (px(x - 1, y + 1) + 2.0 * px(x, y + 1) + px(x + 1, y + 1))
+                - (px(x - 1, y - 1) + 2.0 * px(x, y - 1) + px(x + 1, y - 1));
+            data[y * w + x] = (gx * gx + gy * gy).sqrt();
+        }
+    }
+
+    let mut sorted = data.clone();
+    let k = ((sorted.len() as f32 * 0.98) as usize).min(sorted.len().saturating_sub(1));
+    let norm = if sorted.is_empty() {
+        1.0
+    } else {
+        *sorted
+            .select_nth_unstable_by(k, |a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .1
+    };
+    let norm = norm.max(8.0);
+    for v in data.iter_mut() {
+        *v = (*v / norm).min(1.0);
+    }
+    FloatMap { width: w, height: h, data }
+}
+
+fn edge_points(edges: &FloatMap, threshold: f32, max_points: usize) -> Vec<EdgePoint> {
+    let (cx, cy) = ((edges.width as f64 - 1.0) / 2.0, (edges.height as f64 - 1.0) / 2.0);
+    let all: Vec<EdgePoint> = edges
+        .data
+        .iter()
+        .enumerate()
+        .filter(|(_, &v)| v >= threshold)
+        .map(|(i, &v)| EdgePoint {
+            x: (i % edges.width) as f64 - cx,
+            y: (i / edges.width) as f64 - cy,
+            weight: v,
+        })
+        .collect();
+    if all.len() <= max_points {
+        return all;
+    }
+    // Even subsampling keeps the spatial distribution
+    let stride = all.len() as f64 / max_points as f64;
+    (0..max_points).map(|i| (i as f64 * stride) as usize).map(|i| {
+        let p = &all[i];
+        EdgePoint { x: p.x, y: p.y, weight: p.weight }
+    }).collect()
+}
+
+fn build_level(artwork: &DynamicImage, reference: &DynamicImage, height: u32, max_points: usize) -> Level {
+    let ref_edges = edge_map(&gray_at_height(reference, height));
+    let art_edges = edge_map(&gray_at_height(artwork, height));
+
+    // Spread reference edges so the score degrades smoothly with distance
+    let mut spread = ref_edges.data.clone();
+    box_blur(&mut spread, ref_edges.width, ref_edges.height, ((height / 96) as usize).max(1));
+    let max = spread.iter().cloned().fold(0.0f32, f32::max).max(1e-6);
+    for v in spread.iter_mut() {
+        *v /= max;
+    }
+
+    Level {
+        height: height as f64,
+        ref_center: ((ref_edges.width as f64 - 1.0) / 2.0, (ref_edges.height as f64 - 1.0) / 2.0),
+        ref_spread: FloatMap { width: ref_edges.width, height: ref_edges.height, data: spread },
+        ref_edges,
+        points: edge_points(&art_edges, 0.25, max_points),
+    }
+}
+
+fn score(level: &Level, points: &[&EdgePoint], t: &AlignmentTransform) -> f64 {
+    // Keep the search within plausible copies of the reference
+    if t.scale.ln().abs() > 0.7 || t.rotation.abs() > 0.6 || t.aspect.ln().abs() > 0.3 {
+        return f64::MIN;
+    }
+    let mut total = 0f64;
+    let mut weight = 0f64;
+    for p in points {
+        let (x, y) = t.map(p.x, p.y, level);
+        total += (p.weight * level.ref_spread.sample(x, y)) as f64;
+        weight += p.weight as f64;
+    }
+    if weight > 0.0 { total / weight } else { 0.0 }
+}
+
+/// Coordinate pattern search: try +/- step on each free parameter, halve the steps
+/// when nothing improves.
+fn pattern_search<F: Fn(&[f64; 5]) -> f64>(f: F, start: [f64; 5], initial_steps: [f64; 5]) -> ([f64; 5], f64) {
+    let mut x = start;
+    let mut best = f(&x);
+    let mut steps = initial_steps;
+    for _ in 0..400 {
+        let mut improved = false;
+        for i in 0..5 {
+            if steps[i] == 0.0 {
+                continue;
+            }
+            for dir in [1.0, -1.0] {
+                let mut candidate = x;
+                candidate[i] += dir * steps[i];
+                let s = f(&candidate);
+                if s > best {
+                    best = s;
+                    x = candidate;
+                    improved = true;
+                    break;
+                }
+            }
+        }
+        if !improved {
+            for s in steps.iter_mut() {
+                *s *= 0.5;
+            }
+            if steps.iter().zip(initial_steps.iter()).all(|(s, i)| *s <= i / 32.0) {
+                break;
+            }
+        }
+    }
+    (x, best)
+}
+
+fn coarse_search(level: &Level, points: &[&EdgePoint]) -> AlignmentTransform {
+    let mut best = (f64::MIN, AlignmentTransform::IDENTITY);
+    let shift = (level.height * 0.2).round() as i32;
+    for r in -10..=10 {
+        let rotation = (r as f64 * 2.0).to_radians();
+        for si in -4..=4 {
+            let scale = 1.06f64.powi(si);
+            for ty in (-shift..=shift).step_by(2) {
+                for tx in (-shift..=shift).step_by(2) {
+                    let t = AlignmentTransform {
+                        scale,
+                        rotation,
+                        tx: tx as f64 / level.height,
+                        ty: ty as f64 / level.height,
+                        aspect: 1.0,
+                    };
+                    let s = score(level, points, &t);
+                    if s > best.0 {
+                        best = (s, t);
+                    }
+                }
+            }
+        }
+    }
+    best.1
+}
+
+/// Approximate distance to the nearest reference edge pixel, in pixels: two passes
+/// (forward, then backward) with steps of 1 straight and √2 diagonally.
+fn distance_transform(edges: &FloatMap, threshold: f32) -> FloatMap {
+    let (w, h) = (edges.width, edges.height);
+    let mut d: Vec<f32> = edges.data.iter().map(|&v| if v >= threshold { 0.0 } else { f32::MAX / 4.0 }).collect();
+    let (straight, diagonal) = (1.0f32, std::f32::consts::SQRT_2);
+    for y in 0..h {
+        for x in 0..w {
+            let i = y * w + x;
+            let mut v = d[i];
+            if x > 0 { v = v.min(d[i - 1] + straight); }
+            if y > 0 {
+                v = v.min(d[i - w] + straight);
+                if x > 0 { v = v.min(d[i - w - 1] + diagonal); }
+                if x + 1 < w { v = v.min(d[i - w + 1] + diagonal); }
+            }
+            d[i] = v;
+        }
+    }
+    for y in (0..h).rev() {
+        for x in (0..w).rev() {
+            let i = y * w + x;
+            let mut v = d[i];
+            if x + 1 < w { v = v.min(d[i + 1] + straight); }
+            if y + 1 < h {
+                v = v.min(d[i + w] + straight);
+                if x + 1 < w { v = v.min(d[i + w + 1] + diagonal); }
+                if x > 0 { v = v.min(d[i + w - 1] + diagonal); }
+            }
+            d[i] = v;
+        }
+    }
+    FloatMap { width: w, height: h, data: d }
+}
+
+/// Index of the `n`-by-`n` grid cell of the reference containing (x, y).
+fn grid_index(x: f64, y: f64, level: &Level, n: usize) -> Option<usize> {
+    let (w, h) = (level.ref_edges.width as f64, level.ref_edges.height as f64);
+    if x < 0.0 || y < 0.0 || x >= w || y >= h {
+        return None;
+    }
+    let col = ((x / w * n as f64) as usize).min(n - 1);
+    let row = ((y / h * n as f64) as usize).min(n - 1);
+    Some(row * n + col)
+}
+
+/// Local shift (pixels) that moves one cell's drawn edges onto the reference, on top
+/// of the global fit. Translation only: scale is derived from neighbouring cells,
+/// which is far more stable than fitting it on a handful of strokes.
+fn cell_shift(level: &Level, points: &[&EdgePoint], global: &AlignmentTransform) -> (f64, f64) {
+    let mapped: Vec<(f64, f64, f32)> = points
+        .iter()
+        .map(|p| {
+            let (x, y) = global.map(p.x, p.y, level);
+            (x, y, p.weight)
+        })
+        .collect();
+    let reach = (level.height * 0.06).round() as i32;
+    let limit = reach as f64 * 1.5;
+    let shift_score = |p: &[f64; 5]| {
+        let (tx, ty) = (p[2], p[3]);
+        if tx.abs() > limit || ty.abs() > limit {
+            return f64::MIN;
+        }
+        let mut total = 0f64;
+        let mut weight = 0f64;
+        for &(x, y, w) in &mapped {
+            total += (w * level.ref_spread.sample(x + tx, y + ty)) as f64;
+            weight += w as f64;
+        }
+        // Prefer "no local error" when the evidence is ambiguous
+        let penalty = LOCAL_REGULARIZATION * (tx.abs() + ty.abs()) / level.height;
+        if weight > 0.0 { total / weight - penalty } else { 0.0 }
+    };
+
+    let mut best = (f64::MIN, [0.0; 5]);
+    for ty in (-reach..=reach).step_by(2) {
+        for tx in (-reach..=reach).step_by(2) {
+            let p = [0.0, 0.0, tx as f64, ty as f64, 0.0];
+            let s = shift_score(&p);
+            if s > best.0 {
+                best = (s, p);
+            }
+        }
+    }
+    let (p, _) = pattern_search(shift_score, best.1, [0.0, 0.0, 1.0, 1.0, 0.0]);
+    (p[2], p[3])
+}
+
+/// A grid cell's centre, its measured shift and how many points it had.
+type CellShift = ((f64, f64), (f64, f64), usize);
+
+/// Offset and scale error of one region from the shifts of its cells: the mean shift is
+/// the offset, the divergence of the shifts (least squares) is the local scale.
+fn region_from_cells(cells: &[CellShift]) -> (Option<(f64, f64)>, Option<f64>) {
+    let weight: f64 = cells.iter().map(|c| c.2 as f64).sum();
+    if cells.is_empty() || weight <= 0.0 {
+        return (None, None);
+    }
+    let mean = |f: &dyn Fn(&CellShift) -> f64| {
+        cells.iter().map(|c| f(c) * c.2 as f64).sum::<f64>() / weight
+    };
+    let (cx, cy) = (mean(&|c| c.0 .0), mean(&|c| c.0 .1));
+    let (dx, dy) = (mean(&|c| c.1 .0), mean(&|c| c.1 .1));
+    if cells.len() < 3 {
+        return (Some((dx, dy)), None);
+    }
+    let mut num = 0f64;
+    let mut den = 0f64;
+    for ((x, y), (sx, sy), n) in cells {
+        let w = *n as f64;
+        num += w * ((sx - dx) * (x - cx) + (sy - dy) * (y - cy));
+        den += w * ((x - cx).powi(2) + (y - cy).powi(2));
+    }
+    if den <= 0.0 {
+        return (Some((dx, dy)), None);
+    }
+    // Shifts grow outwards by k per pixel: the reference is (1 + k) times the drawing
+    let k = num / den;
+    (Some((dx, dy)), Some(1.0 / (1.0 + k) - 1.0))
+}
+
+fn build_pyramid(artwork: &DynamicImage, reference: &DynamicImage) -> Result<Vec<Level>, String> {
+    let levels: Vec<Level> = PYRAMID_HEIGHTS
+        .iter()
+        .enumerate()
+        .map(|(i, &h)| build_level(artwork, reference, h, if i == 0 { COARSE_MAX_POINTS } else { MAX_POINTS }))
+        .collect();
+    if levels.iter().any(|l| l.points.len() < MIN_REGION_POINTS) {
+        return Err("Not enough edge detail in the artwork to align it".to_string());
+    }
+    Ok(levels)
+}
+
+/// Similarity: grid search on the coarsest level, refine down the pyramid.
+fn estimate_similarity(levels: &[Level]) -> [f64; 5] {
+    let coarse_points: Vec<&EdgePoint> = levels[0].points.iter().collect();
+    let mut params = coarse_search(&levels[0], &coarse_points).params();
+    for level in levels {
+        let points: Vec<&EdgePoint> = level.points.iter().collect();
+        let px = 1.0 / level.height;
+        let (p, _) = pattern_search(
+            |p| score(level, &points, &AlignmentTransform::from_params(p)),
+            params,
+            [1.0f64.to_radians(), 0.02, 2.0 * px, 2.0 * px, 0.0],
+        );
+        params = p;
+    }
+    params
+}
+
+/// Similarity transform that maps `moving` onto `fixed` (e.g. an earlier stage of a
+/// piece onto the finished one).
+pub fn register_images(moving: &DynamicImage, fixed: &DynamicImage) -> Result<AlignmentTransform, String> {
+    let levels = build_pyramid(moving, fixed)?;
+    Ok(AlignmentTransform::from_params(&estimate_similarity(&levels)))
+}
+
+/// Resample `moving` into a `width` x `height` frame of the image it was registered
+/// against, filling uncovered pixels with `background`.
+pub fn warp_to_frame(
+    moving: &DynamicImage,
+    transform: &AlignmentTransform,
+    width: u32,
+    height: u32,
+    background: Rgba<u8>,
+) -> RgbaImage {
+    let src = moving.to_rgba8();
+    let src_height = src.height().max(1) as f64;
+    let src_center = ((src.width() as f64 - 1.0) / 2.0, (src.height() as f64 - 1.0) / 2.0);
+    let dst_height = height.max(1) as f64;
+    let dst_center = ((width as f64 - 1.0) / 2.0, (height as f64 - 1.0) / 2.0);
+    let (c, s) = (transform.rotation.cos(), transform.rotation.sin());
+    let stretch = transform.aspect.sqrt();
+
+    // Invert q = s * R * A * p + t, with both sides measured in heights from the centers
+    RgbaImage::from_fn(width, height, |x, y| {
+        let u = ((x as f64 - dst_center.0) / dst_height - transform.tx) / transform.scale;
+        let v = ((y as f64 - dst_center.1) / dst_height - transform.ty) / transform.scale;
+        let (ax, ay) = (c * u + s * v, -s * u + c * v);
+        let (px, py) = (ax / stretch, ay * stretch);
+        let p = sample_bilinear(&src, src_center.0 + px * src_height, src_center.1 + py * src_height);
+        let a = p[3] as u32;
+        let blend = |fg: u8, bg: u8| ((fg as u32 * a + bg as u32 * (255 - a) + 127) / 255) as u8;
+        Rgba([blend(p[0], background[0]), blend(p[1], background[1]), blend(p[2], background[2]), 255])
+    })
+}
+
+/// Align `artwork` to `reference` and measure where the drawing deviates.
+pub fn analyze_alignment(artwork: &DynamicImage, reference: &DynamicImage) -> Result<AlignmentAnalysis, String> {
+    let levels = build_pyramid(artwork, reference)?;
+    let params = estimate_similarity(&levels);
+    let similarity = AlignmentTransform::from_params(&params);
+
+    let level = levels.last().expect("pyramid has levels");
+    let points: Vec<&EdgePoint> = level.points.iter().collect();
+    let match_score = score(level, &points, &similarity);
+
+    // Overall proportions: let the aspect ratio float on top of the similarity
+    let (with_aspect, _) = pattern_search(
+        |p| score(level, &points, &AlignmentTransform::from_params(p)),
+        params,
+        [0.0, 0.01, 1.0 / level.height, 1.0 / level.height, 0.03],
+    );
+    // Stretching the artwork by `a` to match means it was drawn 1/a as wide
+    let aspect_error = (-with_aspect[4]).exp() - 1.0;
+
+    // Residuals: distance from each drawn edge to the nearest reference edge
+    let distances = distance_transform(&level.ref_edges, 0.3);
+    let cap = RESIDUAL_CAP * level.height;
+    let (hw, hh) = (level.ref_edges.width, level.ref_edges.height);
+    let mut heat_sum = vec![0f32; hw * hh];
+    let mut density = vec![0f32; hw * hh];
+    let mut cell_points: Vec<Vec<&EdgePoint>> = vec![Vec::new(); CELLS * CELLS];
+    let mut region_residual = [(0f64, 0f64); 9];
+    let mut residual_total = (0f64, 0f64);
+
+    for p in &points {
+        let (x, y) = similarity.map(p.x, p.y, level);
+        let d = match distances.get(x, y) {
+            Some(d) => (d as f64).min(cap),
+            None => continue,
+        };
+        let w = p.weight as f64;
+        let i = y.round() as usize * hw + x.round() as usize;
+        heat_sum[i] += (d / (HEAT_FULL_SCALE * level.height)).min(1.0) as f32 * p.weight;
+        density[i] += p.weight;
+        residual_total.0 += d * w;
+        residual_total.1 += w;
+        if let Some(c) = grid_index(x, y, level, CELLS) {
+            cell_points[c].push(p);
+        }
+        if let Some(r) = grid_index(x, y, level, 3) {
+            region_residual[r].0 += d * w;
+            region_residual[r].1 += w;
+        }
+    }
+    let mean_residual = if residual_total.1 > 0.0 { residual_total.0 / residual_total.1 / level.height } else { 0.0 };
+
+    let radius = (level.height / 64.0).round().max(2.0) as usize;
+    box_blur(&mut heat_sum, hw, hh, radius);
+    box_blur(&mut density, hw, hh, radius);
+    let heat: Vec<f32> = heat_sum
+        .iter()
+        .zip(density.iter())
+        .map(|(s, d)| if *d > 1e-6 { (s / d).min(1.0) } else { 0.0 })
+        .collect();
+    let peak = density.iter().cloned().fold(0.0f32, f32::max).max(1e-6);
+    let density: Vec<f32> = density.iter().map(|d| (d / (peak * 0.3)).min(1.0)).collect();
+
+    let (w, h) = (hw as f64, hh as f64);
+    let (cell_w, cell_h) = (w / CELLS as f64, h / CELLS as f64);
+    let shifts: Vec<Option<CellShift>> = cell_points
+        .iter()
+        .enumerate()
+        .map(|(c, pts)| {
+            if pts.len() < MIN_CELL_POINTS {
+                return None;
+            }
+            let center = (((c % CELLS) as f64 + 0.5) * cell_w, ((c / CELLS) as f64 + 0.5) * cell_h);
+            Some((center, cell_shift(level, pts, &similarity), pts.len()))
+        })
+        .collect();
+
+    let per_region = CELLS / 3;
+    let regions = (0..9)
+        .map(|r| {
+            let (row, col) = (r / 3, r % 3);
+            let cells: Vec<CellShift> = shifts
+                .iter()
+                .enumerate()
+                .filter(|(c, _)| (c / CELLS) / per_region == row && (c % CELLS) / per_region == col)
+                .filter_map(|(_, s)| *s)
+                .collect();
+            let count = cells.iter().map(|c| c.2).sum();
+            let (sum, weight) = region_residual[r];
+            let mean = if weight > 0.0 { sum / weight / level.height } else { 0.0 };
+            let (offset, scale_error) = if count >= MIN_REGION_POINTS { region_from_cells(&cells) } else { (None, None) };
+            RegionError {
+                name: REGION_NAMES[r],
+                points: count,
+                // The shift moves the drawing onto the reference; the error is the opposite
+                offset_x: offset.map(|o| -o.0 / w),
+                offset_y: offset.map(|o| -o.1 / h),
+                scale_error,
+                mean_residual: mean,
+            }
+        })
+        .collect();
+
+    Ok(AlignmentAnalysis {
+        similarity,
+        aspect_error,
+        match_score,
+        mean_residual,
+        regions,
+        heat,
+        density,
+        heat_width: hw,
+        heat_height: hh,
+    })
+}
+
+/// Green (on the reference) through yellow to red (far off).
+fn heat_color(v: f32) -> [f32; 3] {
+    let v = v.clamp(0.0, 1.0);
+    if v < 0.5 {
+        let t = v * 2.0;
+        [40.0 + t * 215.0, 190.0 + t * 20.0, 80.0 - t * 80.0]
+    } else {
+        let t = (v - 0.5) * 2.0;
+        [255.0 - t * 25.0, 210.0 - t * 180.0, t * 30.0]
+    }
+}
+
+/// Residual heatmap over a faded greyscale copy of the reference. `reference` should
+/// be the same framing that was analysed, at any size.
+pub fn render_alignment_heatmap(reference: &DynamicImage, analysis: &AlignmentAnalysis) -> RgbaImage {
+    let base = reference.to_luma8();
+    let (w, h) = base.dimensions();
+    let heat = FloatMap { width: analysis.heat_width, height: analysis.heat_height, data: analysis.heat.clone() };
+    let density = FloatMap { width: analysis.heat_width, height: analysis.heat_height, data: analysis.density.clone() };
+    let sx = analysis.heat_width as f64 / w.max(1) as f64;
+    let sy = analysis.heat_height as f64 / h.max(1) as f64;
+
+    RgbaImage::from_fn(w, h, |x, y| {
+        let grey = 140.0 + base.get_pixel(x, y)[0] as f32 * 0.45;
+        let (hx, hy) = ((x as f64 + 0.5) * sx - 0.5, (y as f64 + 0.5) * sy - 0.5);
+        let alpha = density.sample(hx, hy) * 0.8;
+        let color = heat_color(heat.sample(hx, hy));
+        let mix = |c: f32| (grey * (1.0 - alpha) + c * alpha).round().clamp(0.0, 255.0) as u8;
+        Rgba([mix(color[0]), mix(color[1]), mix(color[2]), 255])
+    })
+}
@@ -0,0 +1,108 @@
+//! Animated GIF / WebP encoding for timelapses.
+//!
+//! GIF goes through the `image` crate's encoder (NeuQuant palette per frame). The
+//! crate only writes still, lossless WebP, so animated WebP wraps one VP8L
+//! bitstream per frame in an extended RIFF container (VP8X + ANIM + ANMF chunks).
+
+use std::io::Cursor;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::codecs::webp::WebPEncoder;
+use image::{ColorType, Delay, DynamicImage, Frame, Rgba, RgbaImage};
+
+use super::transform::flatten_onto;
+
+pub struct AnimationFrame {
+    pub image: RgbaImage,
+    pub duration_ms: u32,
+}
+
+/// Linear blend from `a` to `b` (`t` = 0 is `a`).
+pub fn crossfade(a: &RgbaImage, b: &RgbaImage, t: f32) -> RgbaImage {
+    let t = t.clamp(0.0, 1.0);
+    RgbaImage::from_fn(a.width(), a.height(), |x, y| {
+        let (pa, pb) = (a.get_pixel(x, y), b.get_pixel(x, y));
+        let mix = |i: usize| (pa[i] as f32 + (pb[i] as f32 - pa[i] as f32) * t).round() as u8;
+        Rgba([mix(0), mix(1), mix(2), mix(3)])
+    })
+}
+
+/// `loop_count` 0 repeats forever.
+pub fn encode_gif(frames: &[AnimationFrame], loop_count: u16) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    {
+        // Speed 10 keeps palette quality close to the slowest setting at a fraction of the time
+        let mut encoder = GifEncoder::new_with_speed(&mut output, 10);
+        let repeat = if loop_count == 0 { Repeat::Infinite } else { Repeat::Finite(loop_count) };
+        encoder.set_repeat(repeat)
+            .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+        for frame in frames {
+            let delay = Delay::from_numer_denom_ms(frame.duration_ms, 1);
+            encoder.encode_frame(Frame::from_parts(frame.image.clone(), 0, 0, delay))
+                .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+        }
+    }
+    Ok(output)
+}
+
+fn push_u24(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes()[..3]);
+}
+
+fn push_chunk(out: &mut Vec<u8>, fourcc: &[u8; 4], payload: &[u8]) {
+    out.extend_from_slice(fourcc);
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(payload);
+    if payload.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Lossless animated WebP; frames are flattened onto white and must share a size.
+/// `loop_count` 0 repeats forever.
+pub fn encode_webp_animation(frames: &[AnimationFrame], loop_count: u16) -> Result<Vec<u8>, String> {
+    let first = frames.first().ok_or_else(|| "No frames to encode".to_string())?;
+    let (width, height) = first.image.dimensions();
+    if frames.iter().any(|f| f.image.dimensions() != (width, height)) {
+        return Err("All animation frames must have the same size".to_string());
+    }
+
+    let mut body = b"WEBP".to_vec();
+
+    let mut vp8x = vec![0x02, 0, 0, 0]; // animation flag, no alpha/ICC/metadata
+    push_u24(&mut vp8x, width - 1);
+    push_u24(&mut vp8x, height - 1);
+    push_chunk(&mut body, b"VP8X", &vp8x);
+
+    // Background colour (BGRA) then loop count
+    let mut anim = vec![255, 255, 255, 255];
+    anim.extend_from_slice(&loop_count.to_le_bytes());
+    push_chunk(&mut body, b"ANIM", &anim);
+
+    for frame in frames {
+        let rgb = flatten_onto(&DynamicImage::ImageRgba8(frame.image.clone()), [255, 255, 255]);
+        let mut still = Vec::new();
+        WebPEncoder::new_lossless(Cursor::new(&mut still))
+            .encode(rgb.as_raw(), width, height, ColorType::Rgb8)
+            .map_err(|e| format!("Failed to encode WebP frame: {}", e))?;
+        // Still file: "RIFF" size "WEBP" then the VP8L chunk, which ANMF embeds as is
+        if still.len() < 20 || &still[12..16] != b"VP8L" {
+            return Err("Unexpected WebP encoder output".to_string());
+        }
+
+        let mut anmf = Vec::with_capacity(still.len());
+        push_u24(&mut anmf, 0); // x offset / 2
+        push_u24(&mut anmf, 0); // y offset / 2
+        push_u24(&mut anmf, width - 1);
+        push_u24(&mut anmf, height - 1);
+        push_u24(&mut anmf, frame.duration_ms.min(0xFF_FFFF));
+        anmf.push(0x02); // no blending, keep the frame (each one covers the canvas)
+        anmf.extend_from_slice(&still[12..]);
+        push_chunk(&mut body, b"ANMF", &anmf);
+    }
+
+    let mut output = Vec::with_capacity(body.len() + 8);
+    output.extend_from_slice(b"RIFF");
+    output.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    output.extend_from_slice(&body);
+    Ok(output)
+}
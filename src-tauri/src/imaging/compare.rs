@@ -0,0 +1,59 @@
+//! Artwork-vs-reference comparison renders: side-by-side, onion skin and difference.
+
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use image::imageops::FilterType;
+
+/// Scale to an exact height, keeping the aspect ratio.
+pub fn scale_to_height(img: &DynamicImage, height: u32) -> DynamicImage {
+    let (w, h) = (img.width().max(1), img.height().max(1));
+    if h == height {
+        return img.clone();
+    }
+    let width = ((w as f64 * height as f64 / h as f64).round() as u32).max(1);
+    img.resize_exact(width, height.max(1), FilterType::Lanczos3)
+}
+
+/// Place `img` horizontally centered on a canvas of the given width.
+fn centered_on(img: &DynamicImage, width: u32, background: Rgba<u8>) -> RgbaImage {
+    let mut canvas = RgbaImage::from_pixel(width, img.height(), background);
+    let x = (width.saturating_sub(img.width()) / 2) as i64;
+    imageops::overlay(&mut canvas, &img.to_rgba8(), x, 0);
+    canvas
+}
+
+/// `left` and `right` (same height) next to each other with a gap between them.
+pub fn side_by_side(left: &DynamicImage, right: &DynamicImage, gap: u32, background: Rgba<u8>) -> RgbaImage {
+    let height = left.height().max(right.height());
+    let mut canvas = RgbaImage::from_pixel(left.width() + gap + right.width(), height, background);
+    imageops::overlay(&mut canvas, &left.to_rgba8(), 0, 0);
+    imageops::overlay(&mut canvas, &right.to_rgba8(), (left.width() + gap) as i64, 0);
+    canvas
+}
+
+/// `top` over `base` at `opacity` (0-1); both centered on a common canvas.
+pub fn onion_skin(base: &DynamicImage, top: &DynamicImage, opacity: f32, background: Rgba<u8>) -> RgbaImage {
+    let width = base.width().max(top.width());
+    let mut canvas = centered_on(base, width, background);
+    let top = centered_on(top, width, background);
+    let alpha = opacity.clamp(0.0, 1.0);
+    for (dst, src) in canvas.pixels_mut().zip(top.pixels()) {
+        for c in 0..3 {
+            dst[c] = (dst[c] as f32 * (1.0 - alpha) + src[c] as f32 * alpha).round() as u8;
+        }
+    }
+    canvas
+}
+
+/// Per-channel absolute difference; identical areas come out black.
+pub fn difference(a: &DynamicImage, b: &DynamicImage, background: Rgba<u8>) -> RgbaImage {
+    let width = a.width().max(b.width());
+    let mut canvas = centered_on(a, width, background);
+    let other = centered_on(b, width, background);
+    for (dst, src) in canvas.pixels_mut().zip(other.pixels()) {
+        for c in 0..3 {
+            dst[c] = (dst[c] as i16 - src[c] as i16).unsigned_abs() as u8;
+        }
+        dst[3] = 255;
+    }
+    canvas
+}
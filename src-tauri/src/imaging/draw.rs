@@ -0,0 +1,176 @@
+//! Minimal anti-aliased drawing on RGBA buffers: lines, rectangles and bitmap text.
+//! Coordinates are in pixels with (0, 0) at the top-left corner of the first pixel.
+
+use image::{Rgba, RgbaImage};
+
+use super::font::{glyph, GLYPH_ADVANCE, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+/// Parse "#rgb", "#rrggbb" or "#rrggbbaa".
+pub fn parse_hex_color(value: &str) -> Result<Rgba<u8>, String> {
+    let hex = value.trim().trim_start_matches('#');
+    let channel = |s: &str| u8::from_str_radix(s, 16).map_err(|_| format!("Invalid color: {}", value));
+    // Also keeps the byte slicing below on char boundaries
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid color: {}", value));
+    }
+    match hex.len() {
+        3 => {
+            let mut out = [255u8; 4];
+            for (i, c) in hex.chars().enumerate() {
+                let v = channel(&c.to_string())?;
+                out[i] = v * 17;
+            }
+            Ok(Rgba(out))
+        }
+        6 | 8 => {
+            let alpha = if hex.len() == 8 { channel(&hex[6..8])? } else { 255 };
+            Ok(Rgba([channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?, alpha]))
+        }
+        _ => Err(format!("Invalid color: {}", value)),
+    }
+}
+
+/// Source-over blend of `color` into one pixel, scaled by `coverage` (0-1).
+pub fn blend_pixel(img: &mut RgbaImage, x: i64, y: i64, color: Rgba<u8>, coverage: f32) {
+    if x < 0 || y < 0 || x >= img.width() as i64 || y >= img.height() as i64 || coverage <= 0.0 {
+        return;
+    }
+    let a = color[3] as f32 / 255.0 * coverage.min(1.0);
+    let p = img.get_pixel_mut(x as u32, y as u32);
+    let dst_a = p[3] as f32 / 255.0;
+    let out_a = a + dst_a * (1.0 - a);
+    if out_a <= 0.0 {
+        return;
+    }
+    for c in 0..3 {
+        let v = (color[c] as f32 * a + p[c] as f32 * dst_a * (1.0 - a)) / out_a;
+        p[c] = v.round().clamp(0.0, 255.0) as u8;
+    }
+    p[3] = (out_a * 255.0).round() as u8;
+}
+
+fn distance_to_segment(px: f32, py: f32, (x0, y0): (f32, f32), (x1, y1): (f32, f32)) -> f32 {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 { (((px - x0) * dx + (py - y0) * dy) / len_sq).clamp(0.0, 1.0) } else { 0.0 };
+    let (cx, cy) = (x0 + t * dx, y0 + t * dy);
+    ((px - cx).powi(2) + (py - cy).powi(2)).sqrt()
+}
+
+/// Anti-aliased line of the given width. Segments may extend past the image; only
+/// the visible part is rasterized.
+pub fn draw_line(img: &mut RgbaImage, from: (f32, f32), to: (f32, f32), width: f32, color: Rgba<u8>) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len < 1e-3 || width <= 0.0 {
+        return;
+    }
+    let half = width / 2.0;
+    let reach = half + 1.0;
+    let (w, h) = (img.width() as i64, img.height() as i64);
+
+    // Walk the major axis and only test pixels within reach of the line across it
+    let horizontal = dx.abs() >= dy.abs();
+    let (a0, a1, b0, da, db, a_max, b_max) = if horizontal {
+        (from.0, to.0, from.1, dx, dy, w, h)
+    } else {
+        (from.1, to.1, from.0, dy, dx, h, w)
+    };
+    let start = ((a0.min(a1) - reach).floor() as i64).max(0);
+    let end = ((a0.max(a1) + reach).ceil() as i64).min(a_max - 1);
+    let span = reach * len / da.abs();
+
+    for a in start..=end {
+        let center = a as f32 + 0.5;
+        let b_center = b0 + (center - a0) / da * db;
+        let b_start = ((b_center - span).floor() as i64).max(0);
+        let b_end = ((b_center + span).ceil() as i64).min(b_max - 1);
+        for b in b_start..=b_end {
+            let (x, y) = if horizontal { (a, b) } else { (b, a) };
+            let d = distance_to_segment(x as f32 + 0.5, y as f32 + 0.5, from, to);
+            blend_pixel(img, x, y, color, half + 0.5 - d);
+        }
+    }
+}
+
+/// Draw a line through two points, extended across the whole image.
+pub fn draw_infinite_line(img: &mut RgbaImage, p: (f32, f32), q: (f32, f32), width: f32, color: Rgba<u8>) {
+    let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len < 1e-6 {
+        return;
+    }
+    // Long enough to cross the image from any point within a few diagonals of it
+    let extent = 4.0 * (img.width() as f32).hypot(img.height() as f32)
+        + (p.0.abs() + p.1.abs());
+    let (ux, uy) = (dx / len * extent, dy / len * extent);
+    draw_line(img, (p.0 - ux, p.1 - uy), (p.0 + ux, p.1 + uy), width, color);
+}
+
+pub fn fill_rect(img: &mut RgbaImage, x: i64, y: i64, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y..y + height as i64 {
+        for px in x..x + width as i64 {
+            blend_pixel(img, px, py, color, 1.0);
+        }
+    }
+}
+
+/// Size of `text` drawn at the given integer scale.
+pub fn text_size(text: &str, scale: u32) -> (u32, u32) {
+    let chars = text.chars().count() as u32;
+    let width = if chars == 0 { 0 } else { (chars * GLYPH_ADVANCE - 1) * scale };
+    (width, GLYPH_HEIGHT * scale)
+}
+
+/// Draw `text` with its top-left corner at (x, y), each font pixel a `scale` square.
+pub fn draw_text(img: &mut RgbaImage, x: i64, y: i64, text: &str, scale: u32, color: Rgba<u8>) {
+    let scale = scale.max(1);
+    for (i, c) in text.chars().enumerate() {
+        let origin_x = x + (i as u32 * GLYPH_ADVANCE * scale) as i64;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                    fill_rect(
+                        img,
+                        origin_x + (col * scale) as i64,
+                        y + (row as u32 * scale) as i64,
+                        scale,
+                        scale,
+                        color,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Break `text` into lines no wider than `max_width` at the given scale, on spaces
+/// where possible. Explicit newlines are kept.
+pub fn wrap_text(text: &str, scale: u32, max_width: u32) -> Vec<String> {
+    let max_chars = ((max_width / scale.max(1) + 1) / GLYPH_ADVANCE).max(1) as usize;
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            // Words longer than a line are split
+            while word.len() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..max_chars).collect());
+            }
+            let word: String = word.into_iter().collect();
+            let needed = if line.is_empty() { word.chars().count() } else { line.chars().count() + 1 + word.chars().count() };
+            if needed > max_chars && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    lines
+}
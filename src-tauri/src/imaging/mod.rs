@@ -0,0 +1,46 @@
+//! Image processing shared by reference, journal and moodboard commands.
+//!
+//! Everything here works on decoded `image::DynamicImage` values; commands own
+//! file lookup and persistence.
+//!
+//! ## Module Structure
+//!
+//! - `transform`: Loading with EXIF orientation, rotation/crop as stored on references, encoding
+//! - `adjustments`: Non-destructive adjustment stack (flip, greyscale, levels, blur, ...)
+//! - `value_study`: Notan / value-study posterization with island smoothing
+//! - `draw`: Anti-aliased lines, rectangles and bitmap text (`font`)
+//! - `overlay`: Grid-method and perspective overlays
+//! - `compare`: Side-by-side, onion-skin and difference renders
+//! - `alignment`: Drawing-to-reference alignment, residual heatmap and proportion errors
+//! - `values`: Luminance statistics and the value-scale comparison chart
+//! - `animation`: Crossfades and animated GIF / WebP encoding for timelapses
+//! - `paper`: Paper detection, perspective correction and white balance for journal photos
+//! - `pdf`: Minimal PDF writer for raster pages (moodboard export)
+//! - `palette`: Dominant-colour palettes (median cut)
+
+pub mod transform;
+pub mod adjustments;
+pub mod value_study;
+pub mod font;
+pub mod draw;
+pub mod overlay;
+pub mod compare;
+pub mod alignment;
+pub mod values;
+pub mod animation;
+pub mod paper;
+pub mod pdf;
+pub mod palette;
+
+pub use transform::*;
+pub use adjustments::*;
+pub use value_study::*;
+pub use draw::*;
+pub use overlay::*;
+pub use compare::*;
+pub use alignment::*;
+pub use values::*;
+pub use animation::*;
+pub use paper::*;
+pub use pdf::*;
+pub use palette::*;
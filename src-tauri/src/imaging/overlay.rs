@@ -0,0 +1,195 @@
+//! Grid-method and perspective overlays drawn onto a rendered reference.
+
+use std::f32::consts::PI;
+use image::{Rgba, RgbaImage};
+
+use crate::models::{GridOverlay, OverlayPoint, PerspectiveGuides};
+use super::draw::{draw_infinite_line, draw_line, draw_text, fill_rect, parse_hex_color, text_size};
+
+pub fn validate_overlay(overlay: &GridOverlay) -> Result<(), String> {
+    parse_hex_color(&overlay.color)?;
+    if overlay.columns > 100 || overlay.rows > 100 {
+        return Err("Grid columns and rows must be at most 100".to_string());
+    }
+    if !(0.5..=50.0).contains(&overlay.line_width) {
+        return Err("Line width must be within [0.5, 50]".to_string());
+    }
+    if let Some(p) = &overlay.perspective {
+        if !(1..=3).contains(&p.points) {
+            return Err("Perspective must use 1, 2 or 3 vanishing points".to_string());
+        }
+        if !p.vanishing_points.is_empty() && p.vanishing_points.len() != p.points as usize {
+            return Err(format!("Expected {} vanishing points", p.points));
+        }
+        if !(2..=72).contains(&p.rays) {
+            return Err("Perspective rays must be within [2, 72]".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Column label for chess-style cell names: A..Z, AA, AB, ...
+fn column_label(mut index: u32) -> String {
+    let mut label = Vec::new();
+    loop {
+        label.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap_or_default()
+}
+
+/// Vanishing points in normalized coordinates; defaults sit on the horizon
+/// (and below the image for the third point).
+fn vanishing_points(guides: &PerspectiveGuides) -> Vec<OverlayPoint> {
+    if !guides.vanishing_points.is_empty() {
+        return guides.vanishing_points.clone();
+    }
+    let h = guides.horizon.unwrap_or(0.5);
+    match guides.points {
+        1 => vec![OverlayPoint { x: 0.5, y: h }],
+        2 => vec![OverlayPoint { x: -0.25, y: h }, OverlayPoint { x: 1.25, y: h }],
+        _ => vec![
+            OverlayPoint { x: -0.25, y: h },
+            OverlayPoint { x: 1.25, y: h },
+            OverlayPoint { x: 0.5, y: 1.75 },
+        ],
+    }
+}
+
+/// Fan of rays from `vp` covering the image: a full circle when the point is inside,
+/// otherwise the angular range spanned by the image corners.
+fn draw_rays(img: &mut RgbaImage, vp: (f32, f32), rays: u32, width: f32, color: Rgba<u8>) {
+    let (w, h) = (img.width() as f32, img.height() as f32);
+    let far = 4.0 * w.hypot(h) + vp.0.abs() + vp.1.abs();
+    let inside = (0.0..=w).contains(&vp.0) && (0.0..=h).contains(&vp.1);
+
+    let (start, sweep, count) = if inside {
+        (0.0, 2.0 * PI, rays as f32)
+    } else {
+        let base = (h / 2.0 - vp.1).atan2(w / 2.0 - vp.0);
+        let offsets: Vec<f32> = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)]
+            .iter()
+            .map(|&(cx, cy)| {
+                let mut d = (cy - vp.1).atan2(cx - vp.0) - base;
+                while d > PI { d -= 2.0 * PI; }
+                while d < -PI { d += 2.0 * PI; }
+                d
+            })
+            .collect();
+        let min = offsets.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = offsets.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        // Inclusive ends so the outermost rays run through the corners
+        (base + min, max - min, (rays - 1).max(1) as f32)
+    };
+
+    for i in 0..rays {
+        let angle = start + sweep * i as f32 / count;
+        let end = (vp.0 + angle.cos() * far, vp.1 + angle.sin() * far);
+        draw_line(img, vp, end, width, color);
+    }
+}
+
+fn draw_perspective(img: &mut RgbaImage, guides: &PerspectiveGuides, width: f32, color: Rgba<u8>) {
+    let (w, h) = (img.width() as f32, img.height() as f32);
+    let points: Vec<(f32, f32)> = vanishing_points(guides)
+        .iter()
+        .map(|p| (p.x * w, p.y * h))
+        .collect();
+
+    // Horizon: through the first two vanishing points, or level through the only one
+    let horizon_color = Rgba([color[0], color[1], color[2], color[3].saturating_add(color[3] / 2)]);
+    match points.as_slice() {
+        [only] => draw_infinite_line(img, *only, (only.0 + 1.0, only.1), width * 1.5, horizon_color),
+        [a, b, ..] => draw_infinite_line(img, *a, *b, width * 1.5, horizon_color),
+        [] => {}
+    }
+
+    let ray_color = Rgba([color[0], color[1], color[2], (color[3] as f32 * 0.6).round() as u8]);
+    for vp in points {
+        draw_rays(img, vp, guides.rays, width, ray_color);
+    }
+}
+
+fn draw_cell_labels(img: &mut RgbaImage, columns: u32, rows: u32, color: Rgba<u8>) {
+    let (w, h) = (img.width() as f32, img.height() as f32);
+    let (cell_w, cell_h) = (w / columns as f32, h / rows as f32);
+    let scale = ((cell_w.min(cell_h) / 60.0).floor() as u32).clamp(1, 8);
+    let padding = 2 * scale as i64;
+    // Text on a translucent plate so labels stay readable on busy references
+    let plate = Rgba([255, 255, 255, 160]);
+
+    for row in 0..rows {
+        for col in 0..columns {
+            let label = format!("{}{}", column_label(col), row + 1);
+            let (tw, th) = text_size(&label, scale);
+            if tw as f32 + 4.0 * padding as f32 > cell_w || th as f32 + 4.0 * padding as f32 > cell_h {
+                continue;
+            }
+            let x = (col as f32 * cell_w).round() as i64 + padding * 2;
+            let y = (row as f32 * cell_h).round() as i64 + padding * 2;
+            fill_rect(img, x - padding, y - padding, tw + 2 * padding as u32, th + 2 * padding as u32, plate);
+            draw_text(img, x, y, &label, scale, Rgba([color[0], color[1], color[2], 255]));
+        }
+    }
+}
+
+/// Draw the overlay onto `img` in place. Call `validate_overlay` first; an invalid
+/// color falls back to opaque red.
+pub fn draw_grid_overlay(img: &mut RgbaImage, overlay: &GridOverlay) {
+    let color = parse_hex_color(&overlay.color).unwrap_or(Rgba([255, 59, 48, 255]));
+    let lw = overlay.line_width;
+    let (w, h) = (img.width() as f32, img.height() as f32);
+
+    if let Some(guides) = &overlay.perspective {
+        draw_perspective(img, guides, lw, color);
+    }
+
+    let has_grid = overlay.columns > 0 && overlay.rows > 0;
+    if has_grid {
+        for c in 0..=overlay.columns {
+            let x = w * c as f32 / overlay.columns as f32;
+            draw_line(img, (x, 0.0), (x, h), lw, color);
+        }
+        for r in 0..=overlay.rows {
+            let y = h * r as f32 / overlay.rows as f32;
+            draw_line(img, (0.0, y), (w, y), lw, color);
+        }
+    }
+
+    if overlay.diagonals {
+        let thin = Rgba([color[0], color[1], color[2], (color[3] as f32 * 0.7).round() as u8]);
+        let (cols, rows) = if has_grid { (overlay.columns, overlay.rows) } else { (1, 1) };
+        let (cell_w, cell_h) = (w / cols as f32, h / rows as f32);
+        for r in 0..rows {
+            for c in 0..cols {
+                let (x0, y0) = (c as f32 * cell_w, r as f32 * cell_h);
+                let (x1, y1) = (x0 + cell_w, y0 + cell_h);
+                draw_line(img, (x0, y0), (x1, y1), lw * 0.75, thin);
+                draw_line(img, (x1, y0), (x0, y1), lw * 0.75, thin);
+            }
+        }
+    }
+
+    if overlay.rule_of_thirds {
+        for i in 1..3 {
+            let (x, y) = (w * i as f32 / 3.0, h * i as f32 / 3.0);
+            draw_line(img, (x, 0.0), (x, h), lw, color);
+            draw_line(img, (0.0, y), (w, y), lw, color);
+        }
+    }
+
+    if overlay.center_cross {
+        let arm = w.min(h) * 0.05;
+        let (cx, cy) = (w / 2.0, h / 2.0);
+        draw_line(img, (cx - arm, cy), (cx + arm, cy), lw * 1.5, color);
+        draw_line(img, (cx, cy - arm), (cx, cy + arm), lw * 1.5, color);
+    }
+
+    if overlay.label_cells && has_grid {
+        draw_cell_labels(img, overlay.columns, overlay.rows, color);
+    }
+}
@@ -0,0 +1,89 @@
+//! Dominant-colour palettes by median cut.
+
+use image::{DynamicImage, Rgb};
+use image::imageops::FilterType;
+
+/// Images are shrunk to this longest side before sampling
+const SAMPLE_SIZE: u32 = 96;
+/// Pixels more transparent than this are ignored
+const MIN_ALPHA: u8 = 128;
+
+/// Up to `count` dominant colours, most common first. Near-duplicates are merged,
+/// so flat images give fewer colours.
+pub fn extract_palette(img: &DynamicImage, count: usize) -> Vec<Rgb<u8>> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let small = img.resize(SAMPLE_SIZE, SAMPLE_SIZE, FilterType::Triangle).to_rgba8();
+    let pixels: Vec<[u8; 3]> = small
+        .pixels()
+        .filter(|p| p[3] >= MIN_ALPHA)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    if pixels.is_empty() {
+        return Vec::new();
+    }
+
+    // Split the box with the widest channel range at its median until there are enough
+    let mut boxes = vec![pixels];
+    while boxes.len() < count {
+        let (index, channel, range) = boxes
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                let (channel, range) = widest_channel(b);
+                (i, channel, range)
+            })
+            .max_by_key(|&(_, _, range)| range)
+            .expect("at least one box");
+        if range == 0 {
+            break;
+        }
+        let mut split = boxes.swap_remove(index);
+        split.sort_unstable_by_key(|p| p[channel]);
+        let upper = split.split_off(split.len() / 2);
+        boxes.push(split);
+        boxes.push(upper);
+    }
+
+    // Average each box; boxes of nearly the same colour count as one
+    let mut merged: Vec<(Rgb<u8>, usize)> = Vec::new();
+    for b in &boxes {
+        let mut sum = [0u64; 3];
+        for p in b {
+            for (total, v) in sum.iter_mut().zip(p) {
+                *total += *v as u64;
+            }
+        }
+        let n = b.len().max(1) as u64;
+        let color = Rgb([(sum[0] / n) as u8, (sum[1] / n) as u8, (sum[2] / n) as u8]);
+        let close = merged.iter_mut().find(|(q, _)| {
+            (0..3).map(|c| (q[c] as i32 - color[c] as i32).abs()).sum::<i32>() <= 24
+        });
+        match close {
+            Some((_, pixels)) => *pixels += b.len(),
+            None => merged.push((color, b.len())),
+        }
+    }
+    merged.sort_by_key(|&(_, pixels)| std::cmp::Reverse(pixels));
+    merged.into_iter().map(|(color, _)| color).collect()
+}
+
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    let mut best = (0, 0);
+    for c in 0..3 {
+        let (lo, hi) = pixels
+            .iter()
+            .fold((255u8, 0u8), |(lo, hi), p| (lo.min(p[c]), hi.max(p[c])));
+        let range = hi.saturating_sub(lo);
+        if range > best.1 {
+            best = (c, range);
+        }
+    }
+    best
+}
+
+/// `#rrggbb`, as stored on moodboard colour items.
+pub fn to_hex_color(color: Rgb<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
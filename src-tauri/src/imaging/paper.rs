@@ -0,0 +1,426 @@
+//! Paper detection, perspective correction and white balance for phone photos of
+//! artwork.
+//!
+//! Detection works on a downscaled copy: pixels are scored as "paper-like" (bright,
+//! unsaturated), split with Otsu's threshold, and the connected region under the
+//! center is reduced to its convex hull. The largest quadrilateral on that hull gives
+//! the corners, which are rejected when the region isn't close to a quadrilateral.
+
+use std::collections::VecDeque;
+use image::imageops::FilterType;
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::models::OverlayPoint;
+use super::transform::sample_bilinear;
+
+const DETECT_SIZE: u32 = 384;
+/// Paper must cover this share of the photo...
+const MIN_PAPER_AREA: f64 = 0.15;
+/// ...and the quadrilateral must explain this much of the detected region
+const MIN_QUAD_FIT: f64 = 0.88;
+/// Paper tone after white balance
+const PAPER_TARGET: f32 = 245.0;
+
+fn otsu_threshold(hist: &[u64; 256]) -> u8 {
+    let total: u64 = hist.iter().sum();
+    let sum_all: f64 = hist.iter().enumerate().map(|(v, &c)| v as f64 * c as f64).sum();
+    let (mut weight_bg, mut sum_bg) = (0u64, 0f64);
+    let (mut best, mut best_var) = (0u8, -1f64);
+    for (t, &count) in hist.iter().enumerate() {
+        weight_bg += count;
+        if weight_bg == 0 {
+            continue;
+        }
+        let weight_fg = total - weight_bg;
+        if weight_fg == 0 {
+            break;
+        }
+        sum_bg += t as f64 * count as f64;
+        let mean_bg = sum_bg / weight_bg as f64;
+        let mean_fg = (sum_all - sum_bg) / weight_fg as f64;
+        let between = weight_bg as f64 * weight_fg as f64 * (mean_bg - mean_fg).powi(2);
+        if between > best_var {
+            best_var = between;
+            best = t as u8;
+        }
+    }
+    best
+}
+
+/// Erode then dilate with a square of the given radius (removes specks and thin
+/// bridges between the paper and bright background).
+fn open_mask(mask: &[bool], width: usize, height: usize, radius: usize) -> Vec<bool> {
+    let pass = |src: &[bool], keep_if_all: bool| -> Vec<bool> {
+        let mut out = vec![false; src.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let (x0, x1) = (x.saturating_sub(radius), (x + radius).min(width - 1));
+                let (y0, y1) = (y.saturating_sub(radius), (y + radius).min(height - 1));
+                let mut hit = keep_if_all;
+                'scan: for yy in y0..=y1 {
+                    for xx in x0..=x1 {
+                        if src[yy * width + xx] != keep_if_all {
+                            hit = !keep_if_all;
+                            break 'scan;
+                        }
+                    }
+                }
+                out[y * width + x] = hit;
+            }
+        }
+        out
+    };
+    pass(&pass(mask, true), false)
+}
+
+/// Pixels of the 4-connected region containing `start`.
+fn flood_region(mask: &[bool], width: usize, height: usize, start: usize) -> Vec<usize> {
+    let mut seen = vec![false; mask.len()];
+    let mut region = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    seen[start] = true;
+    while let Some(i) = queue.pop_front() {
+        region.push(i);
+        let (x, y) = (i % width, i / width);
+        let mut visit = |j: usize| {
+            if mask[j] && !seen[j] {
+                seen[j] = true;
+                queue.push_back(j);
+            }
+        };
+        if x > 0 { visit(i - 1); }
+        if x + 1 < width { visit(i + 1); }
+        if y > 0 { visit(i - width); }
+        if y + 1 < height { visit(i + width); }
+    }
+    region
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Monotone-chain convex hull, counter-clockwise in image coordinates.
+fn convex_hull(mut points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let mut hull: Vec<(f64, f64)> = Vec::with_capacity(points.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item = &(f64, f64)>> =
+            if pass == 0 { Box::new(points.iter()) } else { Box::new(points.iter().rev()) };
+        for &p in iter {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+fn polygon_area(points: &[(f64, f64)]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum::<f64>()
+        .abs()
+        / 2.0
+}
+
+/// Largest-area quadrilateral with vertices on the hull: start from the diagonal
+/// extremes, then move one corner at a time to the best hull vertex.
+fn largest_quad(hull: &[(f64, f64)]) -> [(f64, f64); 4] {
+    let pick = |key: &dyn Fn(&(f64, f64)) -> f64, max: bool| -> (f64, f64) {
+        let mut best = hull[0];
+        for p in hull {
+            if (max && key(p) > key(&best)) || (!max && key(p) < key(&best)) {
+                best = *p;
+            }
+        }
+        best
+    };
+    let mut quad = [
+        pick(&|p| p.0 + p.1, false),
+        pick(&|p| p.0 - p.1, true),
+        pick(&|p| p.0 + p.1, true),
+        pick(&|p| p.0 - p.1, false),
+    ];
+    for _ in 0..4 {
+        let mut changed = false;
+        for k in 0..4 {
+            let mut best = (polygon_area(&quad), quad[k]);
+            for &p in hull {
+                let mut candidate = quad;
+                candidate[k] = p;
+                let area = polygon_area(&candidate);
+                if area > best.0 + 1e-9 {
+                    best = (area, p);
+                }
+            }
+            if best.1 != quad[k] {
+                quad[k] = best.1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    quad
+}
+
+/// Top-left, top-right, bottom-right, bottom-left.
+fn order_corners(points: [(f64, f64); 4]) -> [(f64, f64); 4] {
+    let cx = points.iter().map(|p| p.0).sum::<f64>() / 4.0;
+    let cy = points.iter().map(|p| p.1).sum::<f64>() / 4.0;
+    let mut sorted = points;
+    // Clockwise on screen, starting from straight left
+    sorted.sort_by(|a, b| {
+        let angle = |p: &(f64, f64)| (p.1 - cy).atan2(p.0 - cx);
+        angle(a).partial_cmp(&angle(b)).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let start = (0..4)
+        .min_by(|&i, &j| {
+            let key = |k: usize| sorted[k].0 + sorted[k].1;
+            key(i).partial_cmp(&key(j)).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap_or(0);
+    [sorted[start], sorted[(start + 1) % 4], sorted[(start + 2) % 4], sorted[(start + 3) % 4]]
+}
+
+/// Find the sheet of paper in a photo. Returns normalized corners (top-left,
+/// top-right, bottom-right, bottom-left), or `None` when no clear sheet stands out
+/// from the background or it already fills the frame.
+pub fn detect_paper(img: &DynamicImage) -> Option<[OverlayPoint; 4]> {
+    let small = img.resize(DETECT_SIZE, DETECT_SIZE, FilterType::Triangle).to_rgba8();
+    let (width, height) = (small.width() as usize, small.height() as usize);
+    if width < 16 || height < 16 {
+        return None;
+    }
+
+    // Paper-likeness: bright and unsaturated
+    let scores: Vec<u8> = small
+        .pixels()
+        .map(|p| {
+            let (max, min) = (p[0].max(p[1]).max(p[2]) as f32, p[0].min(p[1]).min(p[2]) as f32);
+            let luma = 0.2126 * p[0] as f32 + 0.7152 * p[1] as f32 + 0.0722 * p[2] as f32;
+            (luma - 0.5 * (max - min)).clamp(0.0, 255.0) as u8
+        })
+        .collect();
+    let mut hist = [0u64; 256];
+    for &s in &scores {
+        hist[s as usize] += 1;
+    }
+    let threshold = otsu_threshold(&hist);
+    let mask: Vec<bool> = scores.iter().map(|&s| s > threshold).collect();
+    // Drawn lines split the paper; closing them first keeps the sheet in one piece
+    let inverted: Vec<bool> = mask.iter().map(|m| !m).collect();
+    let closed: Vec<bool> = open_mask(&inverted, width, height, 2).iter().map(|m| !m).collect();
+    let mask = open_mask(&closed, width, height, 2);
+
+    // The sheet is the region under the center, or failing that the largest one
+    let center = (height / 2) * width + width / 2;
+    let region = if mask[center] {
+        flood_region(&mask, width, height, center)
+    } else {
+        let mut seen = vec![false; mask.len()];
+        let mut best: Vec<usize> = Vec::new();
+        for i in 0..mask.len() {
+            if mask[i] && !seen[i] {
+                let r = flood_region(&mask, width, height, i);
+                for &j in &r {
+                    seen[j] = true;
+                }
+                if r.len() > best.len() {
+                    best = r;
+                }
+            }
+        }
+        best
+    };
+
+    let area = region.len() as f64;
+    let total = (width * height) as f64;
+    if area < MIN_PAPER_AREA * total {
+        return None;
+    }
+    let touches = |edge: &dyn Fn(usize, usize) -> bool| region.iter().any(|&i| edge(i % width, i / width));
+    if touches(&|x, _| x == 0)
+        && touches(&|x, _| x == width - 1)
+        && touches(&|_, y| y == 0)
+        && touches(&|_, y| y == height - 1)
+    {
+        // Paper (or background) fills the frame: nothing to straighten
+        return None;
+    }
+
+    // Pixel corners of the region so the hull covers whole pixels
+    let mut in_region = vec![false; mask.len()];
+    for &i in &region {
+        in_region[i] = true;
+    }
+    let mut boundary: Vec<(f64, f64)> = Vec::new();
+    for &i in &region {
+        let (x, y) = (i % width, i / width);
+        let edge = x == 0 || y == 0 || x + 1 == width || y + 1 == height
+            || !in_region[i - 1] || !in_region[i + 1] || !in_region[i - width] || !in_region[i + width];
+        if edge {
+            let (fx, fy) = (x as f64, y as f64);
+            boundary.extend_from_slice(&[(fx, fy), (fx + 1.0, fy), (fx, fy + 1.0), (fx + 1.0, fy + 1.0)]);
+        }
+    }
+    let hull = convex_hull(boundary);
+    if hull.len() < 4 {
+        return None;
+    }
+    let quad = largest_quad(&hull);
+    let quad_area = polygon_area(&quad);
+    if quad_area < MIN_QUAD_FIT * area || quad_area > area / MIN_QUAD_FIT {
+        return None;
+    }
+
+    let corners = order_corners(quad);
+    Some(corners.map(|(x, y)| OverlayPoint {
+        x: (x / width as f64) as f32,
+        y: (y / height as f64) as f32,
+    }))
+}
+
+/// Corners that are inside the image and form a convex, non-degenerate shape.
+pub fn validate_corners(corners: &[OverlayPoint]) -> Result<[OverlayPoint; 4], String> {
+    let corners: [OverlayPoint; 4] = corners
+        .try_into()
+        .map_err(|_| "Exactly four corners are required".to_string())?;
+    if corners.iter().any(|c| !(0.0..=1.0).contains(&c.x) || !(0.0..=1.0).contains(&c.y)) {
+        return Err("Corners must be within [0, 1]".to_string());
+    }
+    let points = corners.map(|c| (c.x as f64, c.y as f64));
+    let signs: Vec<f64> = (0..4)
+        .map(|i| cross(points[i], points[(i + 1) % 4], points[(i + 2) % 4]))
+        .collect();
+    let convex = signs.iter().all(|&s| s > 0.0) || signs.iter().all(|&s| s < 0.0);
+    if !convex || polygon_area(&points) < 0.01 {
+        return Err("Corners must form a convex quadrilateral (top-left, top-right, bottom-right, bottom-left)".to_string());
+    }
+    Ok(corners)
+}
+
+/// Solve the 8x8 system for the homography taking `from[i]` to `to[i]`.
+fn homography(from: &[(f64, f64); 4], to: &[(f64, f64); 4]) -> Option<[f64; 9]> {
+    let mut m = [[0f64; 9]; 8];
+    for i in 0..4 {
+        let ((x, y), (u, v)) = (from[i], to[i]);
+        m[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y, u];
+        m[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y, v];
+    }
+    // Gaussian elimination with partial pivoting
+    for col in 0..8 {
+        let pivot = (col..8).max_by(|&a, &b| m[a][col].abs().partial_cmp(&m[b][col].abs()).unwrap_or(std::cmp::Ordering::Equal))?;
+        if m[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+        for row in 0..8 {
+            if row != col {
+                let factor = m[row][col] / m[col][col];
+                let pivot_row = m[col];
+                for (value, p) in m[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                    *value -= factor * p;
+                }
+            }
+        }
+    }
+    let h: Vec<f64> = (0..8).map(|i| m[i][8] / m[i][i]).collect();
+    Some([h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7], 1.0])
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// Warp the quadrilateral given by normalized `corners` (top-left, top-right,
+/// bottom-right, bottom-left) to an upright rectangle. The output keeps the longer
+/// of each pair of opposite edges, so no resolution is lost.
+pub fn warp_paper(img: &DynamicImage, corners: &[OverlayPoint; 4]) -> Result<DynamicImage, String> {
+    let src = img.to_rgba8();
+    let (w, h) = (src.width() as f64, src.height() as f64);
+    let quad = corners.map(|c| (c.x as f64 * w, c.y as f64 * h));
+
+    let out_w = distance(quad[0], quad[1]).max(distance(quad[3], quad[2])).round().max(1.0);
+    let out_h = distance(quad[0], quad[3]).max(distance(quad[1], quad[2])).round().max(1.0);
+    let rect = [(0.0, 0.0), (out_w, 0.0), (out_w, out_h), (0.0, out_h)];
+    let hm = homography(&rect, &quad).ok_or_else(|| "Paper corners are degenerate".to_string())?;
+
+    let out = RgbaImage::from_fn(out_w as u32, out_h as u32, |x, y| {
+        // Pixel centers in, pixel indices out (sample_bilinear's convention)
+        let (u, v) = (x as f64 + 0.5, y as f64 + 0.5);
+        let d = hm[6] * u + hm[7] * v + hm[8];
+        let sx = (hm[0] * u + hm[1] * v + hm[2]) / d - 0.5;
+        let sy = (hm[3] * u + hm[4] * v + hm[5]) / d - 0.5;
+        sample_bilinear(&src, sx, sy)
+    });
+    Ok(DynamicImage::ImageRgba8(out))
+}
+
+/// Scale each channel so the paper (the bright, dominant tone) becomes neutral
+/// near-white, undoing warm lamp casts and underexposure.
+pub fn white_balance_paper(img: &DynamicImage) -> DynamicImage {
+    let mut rgba = img.to_rgba8();
+    let mut hist = [0u64; 256];
+    for p in rgba.pixels() {
+        let l = 0.2126 * p[0] as f32 + 0.7152 * p[1] as f32 + 0.0722 * p[2] as f32;
+        hist[l.round().clamp(0.0, 255.0) as usize] += 1;
+    }
+    let total: u64 = hist.iter().sum();
+    if total == 0 {
+        return img.clone();
+    }
+    let luma_at = |p: f64| -> f32 {
+        let target = (total as f64 * p) as u64;
+        let mut cumulative = 0u64;
+        for (v, &c) in hist.iter().enumerate() {
+            cumulative += c;
+            if cumulative > target {
+                return v as f32;
+            }
+        }
+        255.0
+    };
+    // Paper dominates a corrected photo; skip the brightest specks (glare)
+    let (low, high) = (luma_at(0.60), luma_at(0.98));
+    let mut sum = [0f64; 3];
+    let mut count = 0f64;
+    for p in rgba.pixels() {
+        let l = 0.2126 * p[0] as f32 + 0.7152 * p[1] as f32 + 0.0722 * p[2] as f32;
+        if l >= low && l <= high {
+            for c in 0..3 {
+                sum[c] += p[c] as f64;
+            }
+            count += 1.0;
+        }
+    }
+    if count == 0.0 {
+        return img.clone();
+    }
+    let gains: Vec<f32> = sum
+        .iter()
+        .map(|s| (PAPER_TARGET / (*s / count).max(1.0) as f32).clamp(0.6, 2.5))
+        .collect();
+    for p in rgba.pixels_mut() {
+        *p = Rgba([
+            (p[0] as f32 * gains[0]).round().min(255.0) as u8,
+            (p[1] as f32 * gains[1]).round().min(255.0) as u8,
+            (p[2] as f32 * gains[2]).round().min(255.0) as u8,
+            p[3],
+        ]);
+    }
+    DynamicImage::ImageRgba8(rgba)
+}
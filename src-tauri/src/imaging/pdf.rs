@@ -0,0 +1,99 @@
+//! Minimal PDF writer for raster pages.
+//!
+//! Each page shows one JPEG image (DCTDecode, so the encoded bytes are embedded
+//! as is) stretched over the whole page. Enough for exported boards and prints
+//! without pulling in a PDF library.
+
+use image::{DynamicImage, ImageOutputFormat, RgbImage};
+
+use super::transform::encode_image;
+
+/// One page, its image already JPEG-encoded so a long export doesn't keep every
+/// page's pixels in memory.
+pub struct PdfPage {
+    pub jpeg: Vec<u8>,
+    /// Image size in pixels
+    pub width: u32,
+    pub height: u32,
+    /// Page size in points (1/72 inch)
+    pub width_pt: f64,
+    pub height_pt: f64,
+}
+
+impl PdfPage {
+    pub fn new(image: RgbImage, width_pt: f64, height_pt: f64, jpeg_quality: u8) -> Result<Self, String> {
+        let (width, height) = image.dimensions();
+        let jpeg = encode_image(&DynamicImage::ImageRgb8(image), ImageOutputFormat::Jpeg(jpeg_quality))?;
+        Ok(PdfPage { jpeg, width, height, width_pt, height_pt })
+    }
+}
+
+fn push_object(out: &mut Vec<u8>, offsets: &mut Vec<usize>, body: &[u8]) {
+    offsets.push(out.len());
+    out.extend_from_slice(format!("{} 0 obj\n", offsets.len()).as_bytes());
+    out.extend_from_slice(body);
+    out.extend_from_slice(b"\nendobj\n");
+}
+
+fn stream(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\nendstream");
+    body
+}
+
+/// Encode `pages` as a PDF. Objects: 1 catalog, 2 page tree, then page, content
+/// stream and image for each page.
+pub fn encode_pdf(pages: &[PdfPage]) -> Result<Vec<u8>, String> {
+    if pages.is_empty() {
+        return Err("No pages to write".to_string());
+    }
+    let mut out: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets: Vec<usize> = Vec::new();
+
+    push_object(&mut out, &mut offsets, b"<< /Type /Catalog /Pages 2 0 R >>");
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 3 + i * 3)).collect();
+    push_object(
+        &mut out,
+        &mut offsets,
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).as_bytes(),
+    );
+
+    for (i, page) in pages.iter().enumerate() {
+        let page_id = 3 + i * 3;
+        let (w, h) = (page.width_pt, page.height_pt);
+        push_object(
+            &mut out,
+            &mut offsets,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+                w, h, page_id + 2, page_id + 1
+            )
+            .as_bytes(),
+        );
+        let content = format!("q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q", w, h);
+        push_object(&mut out, &mut offsets, &stream("", content.as_bytes()));
+
+        let dict = format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+            page.width,
+            page.height
+        );
+        push_object(&mut out, &mut offsets, &stream(&dict, &page.jpeg));
+    }
+
+    let xref_at = out.len();
+    out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).as_bytes());
+    for offset in &offsets {
+        out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            offsets.len() + 1,
+            xref_at
+        )
+        .as_bytes(),
+    );
+    Ok(out)
+}
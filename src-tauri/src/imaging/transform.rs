@@ -0,0 +1,152 @@
+//! Loading, geometry and encoding helpers.
+//!
+//! Rotation and crop follow the reference viewer: the image is rotated clockwise onto
+//! its rotated bounding box (the "stage") and the normalized crop rect is taken in
+//! stage coordinates.
+
+use std::io::Cursor;
+use std::path::Path;
+use base64::Engine;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageOutputFormat, Rgba, RgbImage, RgbaImage};
+
+use crate::models::CropRect;
+use crate::utils::apply_exif_orientation;
+
+/// Decode an image file and apply its EXIF orientation.
+pub fn load_oriented(path: &Path) -> Result<DynamicImage, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| format!("Failed to read image {:?}: {}", path, e))?;
+    let img = image::load_from_memory(&bytes)
+        .map_err(|e| format!("Failed to decode image {:?}: {}", path, e))?;
+    Ok(apply_exif_orientation(img, path))
+}
+
+/// Bilinearly sample an RGBA image at a fractional pixel index (`(2.0, 3.0)` is exactly
+/// pixel 2,3). Positions outside the image read as transparent. Interpolates in
+/// premultiplied alpha so edges don't pick up dark fringes.
+pub fn sample_bilinear(img: &RgbaImage, x: f64, y: f64) -> Rgba<u8> {
+    let (w, h) = (img.width() as i64, img.height() as i64);
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fx = x - x0;
+    let fy = y - y0;
+    let (x0, y0) = (x0 as i64, y0 as i64);
+
+    let mut acc = [0f64; 4];
+    for (dx, dy, weight) in [
+        (0, 0, (1.0 - fx) * (1.0 - fy)),
+        (1, 0, fx * (1.0 - fy)),
+        (0, 1, (1.0 - fx) * fy),
+        (1, 1, fx * fy),
+    ] {
+        let (px, py) = (x0 + dx, y0 + dy);
+        if weight <= 0.0 || px < 0 || py < 0 || px >= w || py >= h {
+            continue;
+        }
+        let p = img.get_pixel(px as u32, py as u32);
+        let a = p[3] as f64 / 255.0 * weight;
+        acc[0] += p[0] as f64 * a;
+        acc[1] += p[1] as f64 * a;
+        acc[2] += p[2] as f64 * a;
+        acc[3] += a;
+    }
+
+    if acc[3] <= 1e-6 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    Rgba([
+        (acc[0] / acc[3]).round().clamp(0.0, 255.0) as u8,
+        (acc[1] / acc[3]).round().clamp(0.0, 255.0) as u8,
+        (acc[2] / acc[3]).round().clamp(0.0, 255.0) as u8,
+        (acc[3] * 255.0).round().clamp(0.0, 255.0) as u8,
+    ])
+}
+
+fn rotate_arbitrary(src: &RgbaImage, degrees: f64) -> DynamicImage {
+    let theta = degrees.to_radians();
+    let (sin, cos) = theta.sin_cos();
+    let (w, h) = (src.width() as f64, src.height() as f64);
+    let out_w = (w * cos.abs() + h * sin.abs()).round().max(1.0);
+    let out_h = (w * sin.abs() + h * cos.abs()).round().max(1.0);
+
+    let out = RgbaImage::from_fn(out_w as u32, out_h as u32, |x, y| {
+        // Offset from stage center, rotated back into source space (y axis points down)
+        let dx = x as f64 + 0.5 - out_w / 2.0;
+        let dy = y as f64 + 0.5 - out_h / 2.0;
+        let sx = dx * cos + dy * sin + w / 2.0 - 0.5;
+        let sy = -dx * sin + dy * cos + h / 2.0 - 0.5;
+        sample_bilinear(src, sx, sy)
+    });
+    DynamicImage::ImageRgba8(out)
+}
+
+/// Rotate clockwise by `degrees` onto the rotated bounding box. Quarter turns are
+/// lossless; other angles are resampled with a transparent background.
+pub fn rotate_to_bounds(img: DynamicImage, degrees: i32) -> DynamicImage {
+    match degrees.rem_euclid(360) {
+        0 => img,
+        90 => img.rotate90(),
+        180 => img.rotate180(),
+        270 => img.rotate270(),
+        d => rotate_arbitrary(&img.to_rgba8(), d as f64),
+    }
+}
+
+/// Crop by a rect normalized to the image size (clamped to the image bounds).
+pub fn crop_normalized(img: &DynamicImage, crop: &CropRect) -> DynamicImage {
+    let (w, h) = (img.width() as f32, img.height() as f32);
+    let x = (crop.x.clamp(0.0, 1.0) * w).round();
+    let y = (crop.y.clamp(0.0, 1.0) * h).round();
+    let cw = (crop.w.clamp(0.0, 1.0) * w).round().min(w - x).max(1.0);
+    let ch = (crop.h.clamp(0.0, 1.0) * h).round().min(h - y).max(1.0);
+    img.crop_imm(x as u32, y as u32, cw as u32, ch as u32)
+}
+
+/// Apply a stored rotation and crop the same way the viewer displays them.
+pub fn apply_rotation_and_crop(img: DynamicImage, rotation: i32, crop: Option<&CropRect>) -> DynamicImage {
+    let rotated = rotate_to_bounds(img, rotation);
+    match crop {
+        Some(c) if c.w > 0.0 && c.h > 0.0 => crop_normalized(&rotated, c),
+        _ => rotated,
+    }
+}
+
+/// Downscale so the longest side is at most `max_size` (never upscales).
+pub fn fit_within(img: DynamicImage, max_size: Option<u32>) -> DynamicImage {
+    match max_size {
+        Some(max) if max > 0 && (img.width() > max || img.height() > max) => {
+            img.resize(max, max, FilterType::Lanczos3)
+        }
+        _ => img,
+    }
+}
+
+/// Composite onto an opaque background (used before encoding formats without alpha).
+pub fn flatten_onto(img: &DynamicImage, background: [u8; 3]) -> RgbImage {
+    let rgba = img.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let p = rgba.get_pixel(x, y);
+        let a = p[3] as u32;
+        let blend = |c: u8, bg: u8| ((c as u32 * a + bg as u32 * (255 - a) + 127) / 255) as u8;
+        image::Rgb([blend(p[0], background[0]), blend(p[1], background[1]), blend(p[2], background[2])])
+    })
+}
+
+/// Encode to bytes; JPEG output is flattened onto white first.
+pub fn encode_image(img: &DynamicImage, format: ImageOutputFormat) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    let result = match format {
+        ImageOutputFormat::Jpeg(_) => DynamicImage::ImageRgb8(flatten_onto(img, [255, 255, 255]))
+            .write_to(&mut Cursor::new(&mut output), format),
+        _ => img.write_to(&mut Cursor::new(&mut output), format),
+    };
+    result.map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(output)
+}
+
+/// Wrap encoded bytes in a base64 data URL, as returned by the image commands.
+pub fn to_data_url(bytes: &[u8], mime: &str) -> String {
+    let base64_data = base64::engine::general_purpose::STANDARD.encode(bytes);
+    format!("data:{};base64,{}", mime, base64_data)
+}
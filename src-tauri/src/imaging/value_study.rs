@@ -0,0 +1,196 @@
+//! Notan / value-study generation: posterize luminance into 2-5 flat values and
+//! merge small islands into their surroundings.
+
+use std::collections::VecDeque;
+use image::{DynamicImage, GrayImage, Luma};
+
+/// Result of `value_study`: the flat-value image plus what was used to make it.
+pub struct ValueStudy {
+    pub image: GrayImage,
+    /// Upper luminance bound (exclusive) of every value except the lightest
+    pub thresholds: Vec<u8>,
+    /// Share of the image covered by each value, darkest first (0-100)
+    pub coverage: Vec<f32>,
+}
+
+/// Rec. 709 luma, the same weighting `image` uses for greyscale conversion.
+pub fn luminance(img: &DynamicImage) -> GrayImage {
+    img.to_luma8()
+}
+
+pub fn histogram(gray: &GrayImage) -> [u64; 256] {
+    let mut hist = [0u64; 256];
+    for p in gray.pixels() {
+        hist[p[0] as usize] += 1;
+    }
+    hist
+}
+
+/// Split the histogram into `values` groups with 1-D k-means (Lloyd), seeded at
+/// equal-population quantiles. For two values this lands close to Otsu's threshold.
+pub fn auto_thresholds(hist: &[u64; 256], values: usize) -> Vec<u8> {
+    let total: u64 = hist.iter().sum();
+    if total == 0 || values < 2 {
+        return Vec::new();
+    }
+
+    // Seed centers at the middle of each equal-population band
+    let mut centers: Vec<f64> = Vec::with_capacity(values);
+    let mut cumulative = 0u64;
+    let mut band = 0usize;
+    for (v, &count) in hist.iter().enumerate() {
+        cumulative += count;
+        while band < values && cumulative as f64 >= total as f64 * (band as f64 + 0.5) / values as f64 {
+            centers.push(v as f64);
+            band += 1;
+        }
+    }
+    while centers.len() < values {
+        centers.push(255.0);
+    }
+
+    let mut thresholds: Vec<f64> = Vec::new();
+    for _ in 0..32 {
+        centers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        thresholds = centers.windows(2).map(|w| (w[0] + w[1]) / 2.0).collect();
+
+        let mut sums = vec![0f64; values];
+        let mut counts = vec![0f64; values];
+        for (v, &count) in hist.iter().enumerate() {
+            let group = thresholds.iter().filter(|&&t| v as f64 >= t).count();
+            sums[group] += v as f64 * count as f64;
+            counts[group] += count as f64;
+        }
+        let mut moved = false;
+        for g in 0..values {
+            if counts[g] > 0.0 {
+                let c = sums[g] / counts[g];
+                moved |= (c - centers[g]).abs() > 0.25;
+                centers[g] = c;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    // Thresholds must be strictly increasing and leave room for every value
+    let mut out: Vec<u8> = Vec::with_capacity(values - 1);
+    for (i, t) in thresholds.iter().enumerate() {
+        let min = out.last().map_or(1, |&p: &u8| p.saturating_add(1)) as f64;
+        let max = (255 - (values - 2 - i)) as f64;
+        out.push(t.round().clamp(min, max) as u8);
+    }
+    out
+}
+
+fn level_of(v: u8, thresholds: &[u8]) -> u8 {
+    thresholds.iter().filter(|&&t| v >= t).count() as u8
+}
+
+/// Grey tone used to draw a value: evenly spaced from black to white.
+pub fn value_tone(level: u8, values: usize) -> u8 {
+    if values < 2 {
+        return 0;
+    }
+    ((level as f32 / (values - 1) as f32) * 255.0).round() as u8
+}
+
+/// Merge 4-connected regions smaller than `min_area` into the value that surrounds
+/// most of their border.
+fn remove_islands(levels: &mut [u8], width: usize, height: usize, min_area: usize) {
+    if min_area <= 1 {
+        return;
+    }
+    let mut visited = vec![false; levels.len()];
+    let mut region: Vec<usize> = Vec::new();
+    let mut queue: VecDeque<usize> = VecDeque::new();
+
+    for start in 0..levels.len() {
+        if visited[start] {
+            continue;
+        }
+        let level = levels[start];
+        region.clear();
+        queue.push_back(start);
+        visited[start] = true;
+        let mut neighbour_counts = [0usize; 256];
+
+        while let Some(idx) = queue.pop_front() {
+            region.push(idx);
+            let (x, y) = (idx % width, idx / width);
+            let mut neighbours = [usize::MAX; 4];
+            if x > 0 { neighbours[0] = idx - 1; }
+            if x + 1 < width { neighbours[1] = idx + 1; }
+            if y > 0 { neighbours[2] = idx - width; }
+            if y + 1 < height { neighbours[3] = idx + width; }
+            for n in neighbours.into_iter().filter(|&n| n != usize::MAX) {
+                if levels[n] == level {
+                    if !visited[n] {
+                        visited[n] = true;
+                        queue.push_back(n);
+                    }
+                } else {
+                    neighbour_counts[levels[n] as usize] += 1;
+                }
+            }
+        }
+
+        if region.len() < min_area {
+            let best = neighbour_counts
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, &c)| c)
+                .filter(|(_, &c)| c > 0)
+                .map(|(l, _)| l as u8);
+            if let Some(best) = best {
+                for &idx in &region {
+                    levels[idx] = best;
+                }
+            }
+        }
+    }
+}
+
+/// Build a value study.
+///
+/// - `values`: number of flat values (2-5)
+/// - `thresholds`: explicit bounds (`values - 1` increasing luminances); computed when `None`
+/// - `smoothing`: gaussian sigma applied before thresholding to simplify shapes
+/// - `min_island_percent`: regions smaller than this share of the image are merged away
+pub fn value_study(
+    img: &DynamicImage,
+    values: usize,
+    thresholds: Option<&[u8]>,
+    smoothing: f32,
+    min_island_percent: f32,
+) -> ValueStudy {
+    let source = if smoothing > 0.0 { img.blur(smoothing) } else { img.clone() };
+    let gray = luminance(&source);
+    let (width, height) = (gray.width() as usize, gray.height() as usize);
+
+    let thresholds = match thresholds {
+        Some(t) => t.to_vec(),
+        None => auto_thresholds(&histogram(&gray), values),
+    };
+
+    let mut levels: Vec<u8> = gray.pixels().map(|p| level_of(p[0], &thresholds)).collect();
+    // Two passes: merging can create new small fragments next to larger ones
+    let min_area = ((width * height) as f32 * min_island_percent.max(0.0) / 100.0).round() as usize;
+    for _ in 0..2 {
+        remove_islands(&mut levels, width, height, min_area);
+    }
+
+    let mut counts = vec![0usize; values];
+    for &l in &levels {
+        counts[(l as usize).min(values - 1)] += 1;
+    }
+    let total = levels.len().max(1) as f32;
+    let coverage = counts.iter().map(|&c| c as f32 / total * 100.0).collect();
+
+    let image = GrayImage::from_fn(width as u32, height as u32, |x, y| {
+        Luma([value_tone(levels[y as usize * width + x as usize], values)])
+    });
+
+    ValueStudy { image, thresholds, coverage }
+}
@@ -0,0 +1,156 @@
+//! Value (luminance) statistics and the side-by-side value-scale chart used to
+//! compare an artwork's value range with its reference.
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use super::draw::{draw_line, draw_text, fill_rect, text_size};
+
+/// Number of equal value bands in the distribution, darkest first.
+pub const VALUE_STEPS: usize = 5;
+
+pub struct ValueStats {
+    pub histogram: [u64; 256],
+    pub pixels: u64,
+    /// 1st / 99th percentile, so a few specks don't define the range
+    pub darkest: u8,
+    pub lightest: u8,
+    pub mean: f32,
+    pub median: u8,
+    /// Standard deviation of luminance (global contrast)
+    pub std_dev: f32,
+    /// Share of pixels per value band (0-100), darkest first
+    pub distribution: [f32; VALUE_STEPS],
+}
+
+fn percentile(hist: &[u64; 256], total: u64, p: f64) -> u8 {
+    let target = (total as f64 * p).ceil().max(1.0) as u64;
+    let mut cumulative = 0u64;
+    for (v, &count) in hist.iter().enumerate() {
+        cumulative += count;
+        if cumulative >= target {
+            return v as u8;
+        }
+    }
+    255
+}
+
+/// Luminance statistics over the visible pixels (fully transparent corners left by
+/// rotation are ignored).
+pub fn value_stats(img: &DynamicImage) -> ValueStats {
+    let rgba = img.to_rgba8();
+    let mut histogram = [0u64; 256];
+    for p in rgba.pixels() {
+        if p[3] < 128 {
+            continue;
+        }
+        // Rec. 709 luma, matching `to_luma8`
+        let l = 0.2126 * p[0] as f32 + 0.7152 * p[1] as f32 + 0.0722 * p[2] as f32;
+        histogram[l.round().clamp(0.0, 255.0) as usize] += 1;
+    }
+
+    let pixels: u64 = histogram.iter().sum();
+    let total = pixels.max(1) as f64;
+    let mean = histogram.iter().enumerate().map(|(v, &c)| v as f64 * c as f64).sum::<f64>() / total;
+    let variance = histogram
+        .iter()
+        .enumerate()
+        .map(|(v, &c)| (v as f64 - mean).powi(2) * c as f64)
+        .sum::<f64>()
+        / total;
+
+    let mut distribution = [0f32; VALUE_STEPS];
+    for (v, &count) in histogram.iter().enumerate() {
+        let band = (v * VALUE_STEPS / 256).min(VALUE_STEPS - 1);
+        distribution[band] += count as f32;
+    }
+    for d in distribution.iter_mut() {
+        *d = *d / total as f32 * 100.0;
+    }
+
+    ValueStats {
+        histogram,
+        pixels,
+        darkest: percentile(&histogram, pixels, 0.01),
+        lightest: percentile(&histogram, pixels, 0.99),
+        mean: mean as f32,
+        median: percentile(&histogram, pixels, 0.5),
+        std_dev: variance.sqrt() as f32,
+        distribution,
+    }
+}
+
+/// Grey used to draw a value band: the middle of its luminance range.
+pub fn band_tone(band: usize) -> u8 {
+    (((band as f32 + 0.5) * 256.0 / VALUE_STEPS as f32) - 0.5).round().clamp(0.0, 255.0) as u8
+}
+
+const PANEL_WIDTH: u32 = 440;
+const MARGIN: u32 = 20;
+const SCALE: u32 = 2;
+
+fn draw_panel(chart: &mut RgbaImage, x0: u32, title: &str, stats: &ValueStats) {
+    let ink = Rgba([40, 40, 40, 255]);
+    let accent = Rgba([230, 80, 40, 255]);
+    let (_, title_h) = text_size(title, SCALE);
+    draw_text(chart, x0 as i64, MARGIN as i64, title, SCALE, ink);
+
+    // Histogram with the value ramp underneath
+    let plot_top = MARGIN + title_h + 12;
+    let plot_h = 160u32;
+    let plot_w = PANEL_WIDTH;
+    fill_rect(chart, x0 as i64, plot_top as i64, plot_w, plot_h, Rgba([245, 245, 245, 255]));
+    let peak = stats.histogram.iter().cloned().max().unwrap_or(0).max(1) as f32;
+    for (v, &count) in stats.histogram.iter().enumerate() {
+        // Square root keeps small but present values visible next to a dominant peak
+        let bar = ((count as f32 / peak).sqrt() * plot_h as f32).round() as u32;
+        let x = x0 + v as u32 * plot_w / 256;
+        let w = ((v as u32 + 1) * plot_w / 256 - v as u32 * plot_w / 256).max(1);
+        fill_rect(chart, x as i64, (plot_top + plot_h - bar) as i64, w, bar, Rgba([90, 90, 90, 255]));
+    }
+    for marker in [stats.darkest, stats.lightest] {
+        let x = x0 as f32 + (marker as f32 + 0.5) * plot_w as f32 / 256.0;
+        draw_line(chart, (x, plot_top as f32), (x, (plot_top + plot_h) as f32), 2.0, accent);
+    }
+    let ramp_top = plot_top + plot_h + 4;
+    for x in 0..plot_w {
+        let v = (x * 256 / plot_w).min(255) as u8;
+        fill_rect(chart, (x0 + x) as i64, ramp_top as i64, 1, 10, Rgba([v, v, v, 255]));
+    }
+
+    let range_label = format!("RANGE {}-{}  MEDIAN {}", stats.darkest, stats.lightest, stats.median);
+    draw_text(chart, x0 as i64, (ramp_top + 18) as i64, &range_label, SCALE, ink);
+
+    // Five-step value scale: swatch per band, bar length = share of the image
+    let scale_top = ramp_top + 18 + 7 * SCALE + 16;
+    let row_h = 30u32;
+    let swatch = row_h - 6;
+    let bar_x = x0 + swatch + 10;
+    let bar_max = plot_w - swatch - 10 - 60;
+    for band in 0..VALUE_STEPS {
+        let y = scale_top + band as u32 * row_h;
+        let tone = band_tone(band);
+        fill_rect(chart, x0 as i64, y as i64, swatch, swatch, Rgba([tone, tone, tone, 255]));
+        let share = stats.distribution[band];
+        let len = (share / 100.0 * bar_max as f32).round() as u32;
+        fill_rect(chart, bar_x as i64, (y + swatch / 4) as i64, len.max(1), swatch / 2, Rgba([120, 120, 120, 255]));
+        draw_text(
+            chart,
+            (bar_x + len + 8) as i64,
+            (y + (swatch - 7 * SCALE) / 2) as i64,
+            &format!("{:.0}%", share),
+            SCALE,
+            ink,
+        );
+    }
+}
+
+/// Reference and artwork value charts side by side: histogram with 1st/99th
+/// percentile markers, range/median and the five-step distribution.
+pub fn render_value_chart(reference: &ValueStats, artwork: &ValueStats) -> RgbaImage {
+    let width = MARGIN * 3 + PANEL_WIDTH * 2;
+    let height = 430;
+    let mut chart = RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]));
+    draw_panel(&mut chart, MARGIN, "REFERENCE", reference);
+    draw_panel(&mut chart, MARGIN * 2 + PANEL_WIDTH, "ARTWORK", artwork);
+    chart
+}
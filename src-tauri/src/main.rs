@@ -5,6 +5,8 @@
 mod models;
 mod state;
 mod utils;
+mod imaging;
+mod scanner;
 mod commands;
 mod server;
 
@@ -49,6 +51,31 @@ fn main() {
             commands::link_photo_journal_reference,
             commands::unlink_photo_journal_reference,
             commands::set_photo_journal_rotation,
+            commands::set_photo_journal_metadata,
+            commands::set_photo_journal_links,
+            commands::filter_photo_journal,
+            commands::get_photo_journal_facets,
+            commands::get_journal_pieces,
+            commands::create_journal_piece,
+            commands::rename_journal_piece,
+            commands::delete_journal_piece,
+            commands::add_journal_piece_stage,
+            commands::remove_journal_piece_stage,
+            commands::reorder_journal_piece_stages,
+            commands::set_journal_piece_stage_label,
+            commands::set_journal_piece_cover,
+            commands::export_piece_timelapse,
+            commands::detect_photo_journal_paper,
+            commands::correct_photo_journal_image,
+            commands::reset_photo_journal_correction,
+            commands::render_comparison,
+            commands::analyze_alignment,
+            commands::compare_values,
+            commands::delete_journal_comparison,
+            commands::journal_stats,
+            commands::get_journal_settings,
+            commands::set_journal_daily_goal,
+            commands::set_journal_auto_correct,
             
             // References commands
             commands::get_references,
@@ -77,6 +104,22 @@ fn main() {
             commands::delete_image_note,
             commands::set_image_source,
             commands::delete_image_source,
+            // Ratings, favorites and study counters
+            commands::set_reference_rating,
+            commands::set_reference_favorite,
+            commands::record_reference_study,
+            commands::reset_reference_study_stats,
+            commands::search_references,
+            // Adjustment stack
+            commands::set_reference_adjustments,
+            commands::render_reference_adjusted,
+            commands::get_adjusted_thumbnail_data,
+            commands::generate_value_study,
+            // Grid and perspective overlays
+            commands::set_reference_grid_overlay,
+            commands::render_reference_overlay,
+            commands::export_reference_overlay,
+            commands::export_references,
             
             // Notes commands
             commands::get_notes,
@@ -89,10 +132,33 @@ fn main() {
             commands::create_moodboard,
             commands::update_moodboard,
             commands::delete_moodboard,
+            commands::duplicate_moodboard,
             commands::update_moodboard_item,
+            commands::update_moodboard_items,
+            commands::flush_moodboards,
             commands::delete_moodboard_item,
-            commands::upload_moodboard_image
-            ,
+            commands::upload_moodboard_image,
+            commands::export_moodboard,
+            commands::reorder_moodboard_items,
+            commands::group_moodboard_items,
+            commands::ungroup_moodboard_items,
+            commands::set_moodboard_item_properties,
+            commands::get_moodboard_templates,
+            commands::save_moodboard_as_template,
+            commands::delete_moodboard_template,
+            commands::create_moodboard_from_template,
+            commands::fill_moodboard_placeholder,
+            commands::add_reference_to_moodboard,
+            commands::gc_moodboards,
+            commands::create_moodboard_snapshot,
+            commands::get_moodboard_snapshots,
+            commands::diff_moodboard_snapshot,
+            commands::preview_moodboard_snapshot,
+            commands::restore_moodboard_snapshot,
+            commands::delete_moodboard_snapshot,
+            commands::export_moodboard_archive,
+            commands::import_moodboard_archive,
+            commands::auto_layout_moodboard,
             // System commands
             commands::open_url_in_chrome,
             commands::phone_upload_status,
@@ -101,6 +167,13 @@ fn main() {
             commands::scan_artwork,
             commands::list_scanners,
             commands::scan_with_device,
+            commands::scan_to_journal,
+            commands::scan_to_references,
+            commands::get_hot_folders,
+            commands::add_hot_folder,
+            commands::update_hot_folder,
+            commands::remove_hot_folder,
+            commands::import_hot_folder_now,
             commands::list_warmups,
             commands::get_warmup_image_data,
             commands::list_warmups
@@ -120,6 +193,8 @@ fn main() {
                     eprintln!("Failed to seed default data: {}", err);
                 }
             }
+            // Import new files from registered hot folders
+            crate::commands::start_hot_folder_watcher(app.handle());
             // Start extension server for Chrome extension uploads
             {
                 let handle = app.handle();
@@ -129,6 +204,14 @@ fn main() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Save moodboard edits still waiting for the debounce
+                if let Err(err) = crate::commands::flush_pending_moodboards() {
+                    eprintln!("Failed to save moodboards on exit: {}", err);
+                }
+            }
+        });
 }
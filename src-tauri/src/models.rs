@@ -18,6 +18,150 @@ pub struct PhotoJournalImage {
     pub reference_id: Option<String>,
     #[serde(default)]
     pub rotation: i32,
+    #[serde(default)]
+    pub comparisons: Vec<JournalComparison>,
+    /// All linked references, primary first; `reference_id` mirrors the first one
+    #[serde(rename = "referenceIds")]
+    #[serde(default)]
+    pub reference_ids: Vec<String>,
+    /// Linked master studies (`library/master_studies/...` image paths)
+    #[serde(rename = "masterStudyPaths")]
+    #[serde(default)]
+    pub master_study_paths: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub medium: Option<String>,
+    #[serde(rename = "paperSize")]
+    #[serde(default)]
+    pub paper_size: Option<String>,
+    #[serde(rename = "timeSpentMinutes")]
+    #[serde(default)]
+    pub time_spent_minutes: Option<u32>,
+    /// 1-5
+    #[serde(rename = "selfRating")]
+    #[serde(default)]
+    pub self_rating: Option<u8>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Work-in-progress piece this entry is a stage of
+    #[serde(rename = "pieceId")]
+    #[serde(default)]
+    pub piece_id: Option<String>,
+    /// Untouched upload (`artwork_journal/Originals/...`) when the photo was corrected
+    #[serde(rename = "originalUrl")]
+    #[serde(default)]
+    pub original_url: Option<String>,
+    #[serde(rename = "paperCorrection")]
+    #[serde(default)]
+    pub paper_correction: Option<PaperCorrection>,
+}
+
+/// Perspective and white-balance correction applied to a journal photo.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PaperCorrection {
+    /// Paper corners in the original (EXIF-oriented) photo, normalized 0-1:
+    /// top-left, top-right, bottom-right, bottom-left
+    pub corners: Vec<OverlayPoint>,
+    /// Corners found automatically rather than placed by the user
+    #[serde(default)]
+    pub detected: bool,
+    #[serde(rename = "whiteBalance")]
+    #[serde(default)]
+    pub white_balance: bool,
+    /// Entry rotation before the correction reset it, restored on reset
+    #[serde(rename = "priorRotation")]
+    #[serde(default)]
+    pub prior_rotation: i32,
+}
+
+impl PhotoJournalImage {
+    /// Fold the legacy single `reference_id` into `reference_ids` and keep it pointing
+    /// at the primary (first) link, so clients reading only `referenceId` still work.
+    pub fn normalize_links(&mut self) {
+        if let Some(id) = self.reference_id.take() {
+            if !self.reference_ids.contains(&id) {
+                self.reference_ids.insert(0, id);
+            }
+        }
+        let mut seen: Vec<String> = Vec::new();
+        self.reference_ids.retain(|id| {
+            let keep = !seen.contains(id);
+            seen.push(id.clone());
+            keep
+        });
+        self.reference_id = self.reference_ids.first().cloned();
+    }
+}
+
+fn default_daily_goal() -> u32 {
+    1
+}
+
+/// Artwork journal preferences (app_data/journal_settings.json).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalSettings {
+    /// Artworks per day that count as meeting the goal
+    #[serde(rename = "dailyGoal")]
+    #[serde(default = "default_daily_goal")]
+    pub daily_goal: u32,
+    /// Straighten and white-balance uploaded photos when a sheet of paper is found
+    #[serde(rename = "autoCorrectPhotos")]
+    #[serde(default = "default_true")]
+    pub auto_correct_photos: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for JournalSettings {
+    fn default() -> Self {
+        JournalSettings { daily_goal: default_daily_goal(), auto_correct_photos: true }
+    }
+}
+
+/// Artwork-vs-reference comparison render saved with a journal entry.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalComparison {
+    pub id: String,
+    pub filename: String,
+    pub url: String,
+    pub mode: String, // "sideBySide" | "onionSkin" | "difference"
+    #[serde(rename = "referenceId")]
+    pub reference_id: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+/// A work-in-progress series: one artwork photographed at several stages
+/// (app_data/journal_pieces.json).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalPiece {
+    pub id: String,
+    pub title: String,
+    /// Oldest stage first
+    #[serde(default)]
+    pub stages: Vec<PieceStage>,
+    /// Journal entry shown for the piece; `None` means the latest stage
+    #[serde(rename = "coverImageId")]
+    #[serde(default)]
+    pub cover_image_id: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PieceStage {
+    #[serde(rename = "imageId")]
+    pub image_id: String,
+    /// e.g. "Sketch", "Block-in", "Final"
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(rename = "addedAt")]
+    pub added_at: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,6 +196,121 @@ pub struct Reference {
     #[serde(rename = "crop")]
     #[serde(default)]
     pub crop: Option<CropRect>,
+    #[serde(default)]
+    pub rating: u8,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(rename = "timesStudied")]
+    #[serde(default)]
+    pub times_studied: u32,
+    #[serde(rename = "lastStudiedAt")]
+    #[serde(default)]
+    pub last_studied_at: Option<i64>,
+    #[serde(default)]
+    pub adjustments: Vec<ImageAdjustment>,
+    #[serde(rename = "derivedFrom")]
+    #[serde(default)]
+    pub derived_from: Option<DerivedFrom>,
+    #[serde(rename = "gridOverlay")]
+    #[serde(default)]
+    pub grid_overlay: Option<GridOverlay>,
+}
+
+fn default_overlay_color() -> String {
+    "#ff3b30".to_string()
+}
+
+fn default_overlay_line_width() -> f32 {
+    2.0
+}
+
+/// Grid-method overlay drawn on top of a reference. Coordinates are in the
+/// rotated/cropped image as displayed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GridOverlay {
+    /// 0 columns or rows disables the grid
+    #[serde(default)]
+    pub columns: u32,
+    #[serde(default)]
+    pub rows: u32,
+    /// Diagonals through every grid cell (or the whole image without a grid)
+    #[serde(default)]
+    pub diagonals: bool,
+    #[serde(rename = "ruleOfThirds")]
+    #[serde(default)]
+    pub rule_of_thirds: bool,
+    #[serde(rename = "centerCross")]
+    #[serde(default)]
+    pub center_cross: bool,
+    #[serde(default)]
+    pub perspective: Option<PerspectiveGuides>,
+    /// "#rrggbb" or "#rrggbbaa"
+    #[serde(default = "default_overlay_color")]
+    pub color: String,
+    /// In output pixels
+    #[serde(rename = "lineWidth")]
+    #[serde(default = "default_overlay_line_width")]
+    pub line_width: f32,
+    /// Label cells chess-style: columns A, B, C..., rows 1, 2, 3...
+    #[serde(rename = "labelCells")]
+    #[serde(default)]
+    pub label_cells: bool,
+}
+
+fn default_perspective_rays() -> u32 {
+    12
+}
+
+/// 1-, 2- or 3-point perspective guides radiating from vanishing points.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PerspectiveGuides {
+    pub points: u8,
+    /// Normalized to the image (may lie outside 0..1); placed on the horizon when empty
+    #[serde(rename = "vanishingPoints")]
+    #[serde(default)]
+    pub vanishing_points: Vec<OverlayPoint>,
+    /// Normalized y of the horizon line, default 0.5
+    #[serde(default)]
+    pub horizon: Option<f32>,
+    /// Guide lines per vanishing point
+    #[serde(default = "default_perspective_rays")]
+    pub rays: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct OverlayPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Provenance of a reference generated from another image (e.g. a value study).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DerivedFrom {
+    pub kind: String, // "valueStudy"
+    #[serde(rename = "referenceId")]
+    pub reference_id: Option<String>,
+    #[serde(rename = "imagePath")]
+    pub image_path: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+/// One step of a reference's non-destructive adjustment stack, applied in order
+/// after rotation and crop. The original file is never modified.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ImageAdjustment {
+    FlipHorizontal,
+    FlipVertical,
+    Greyscale,
+    /// Both in -100..=100, 0 = unchanged
+    BrightnessContrast { brightness: f32, contrast: f32 },
+    /// Input black/white points (0-255) and midtone gamma (1.0 = linear)
+    Levels { black: u8, white: u8, gamma: f32 },
+    /// Gaussian blur radius (sigma, in pixels of the rendered image)
+    Blur { sigma: f32 },
+    /// Number of tone levels per channel (2-32)
+    Posterize { levels: u8 },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -91,7 +350,7 @@ pub struct Moodboard {
 pub struct MoodboardItem {
     pub id: String,
     #[serde(rename = "type")]
-    pub item_type: String, // "image", "text", "color"
+    pub item_type: String, // "image", "text", "color", "placeholder" (template image slot), "reference"
     pub content: Option<String>,
     pub x: f64,
     pub y: f64,
@@ -106,6 +365,142 @@ pub struct MoodboardItem {
     pub aspect_ratio: Option<f64>,
     pub is_webp: Option<bool>,
     pub colors: Option<Vec<String>>,
+    /// Text items, in board pixels
+    #[serde(rename = "fontSize")]
+    #[serde(default)]
+    pub font_size: Option<f64>,
+    /// Text colour (hex) of text items
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Stacking order, higher on top. Boards saved before layers load as 0 and
+    /// keep their list order
+    #[serde(rename = "zIndex")]
+    #[serde(default)]
+    pub z_index: i64,
+    /// Clockwise degrees around the item's centre, 0-360
+    #[serde(default)]
+    pub rotation: f64,
+    /// 0 (invisible) to 1 (opaque)
+    #[serde(default = "default_opacity")]
+    pub opacity: f64,
+    /// Locked items can't be moved, resized or rotated
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub flip: MoodboardFlip,
+    #[serde(rename = "groupId")]
+    #[serde(default)]
+    pub group_id: Option<String>,
+    /// Reference items: the library reference shown (with its crop and rotation);
+    /// `url` follows the reference's file
+    #[serde(rename = "referenceId")]
+    #[serde(default)]
+    pub reference_id: Option<String>,
+    /// Set when the reference was deleted; the item stays as a marker
+    #[serde(rename = "referenceMissing")]
+    #[serde(default)]
+    pub reference_missing: bool,
+}
+
+impl MoodboardItem {
+    /// A new item with a fresh id and default styling.
+    pub fn new(item_type: &str, x: f64, y: f64, width: f64, height: f64) -> Self {
+        MoodboardItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            item_type: item_type.to_string(),
+            content: None,
+            x,
+            y,
+            width,
+            height,
+            created_at: Some(chrono::Utc::now().timestamp_millis()),
+            filename: None,
+            url: None,
+            original_width: None,
+            original_height: None,
+            aspect_ratio: None,
+            is_webp: None,
+            colors: None,
+            font_size: None,
+            color: None,
+            z_index: 0,
+            rotation: 0.0,
+            opacity: 1.0,
+            locked: false,
+            flip: MoodboardFlip::None,
+            group_id: None,
+            reference_id: None,
+            reference_missing: false,
+        }
+    }
+
+    /// This item with `other`'s layer, rotation, opacity, lock, flip and group.
+    pub fn with_layout_of(mut self, other: &MoodboardItem) -> Self {
+        self.z_index = other.z_index;
+        self.rotation = other.rotation;
+        self.opacity = other.opacity;
+        self.locked = other.locked;
+        self.flip = other.flip;
+        self.group_id = other.group_id.clone();
+        self
+    }
+}
+
+fn default_opacity() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MoodboardFlip {
+    #[default]
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+/// A reusable board layout. Image slots are `placeholder` items whose `content`
+/// is the slot's label. Built-in templates ship with the app; the rest are saved in
+/// app_data/moodboard_templates.json.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MoodboardTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "builtIn")]
+    #[serde(default)]
+    pub built_in: bool,
+    pub items: Vec<MoodboardItem>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+/// A saved copy of a board's item list, to look back at or restore. Automatic
+/// snapshots are taken before destructive edits; stored in
+/// app_data/moodboard_snapshots.json.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MoodboardSnapshot {
+    pub id: String,
+    #[serde(rename = "moodboardId")]
+    pub moodboard_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub automatic: bool,
+    pub items: Vec<MoodboardItem>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+impl MoodboardFlip {
+    pub fn horizontal(self) -> bool {
+        matches!(self, MoodboardFlip::Horizontal | MoodboardFlip::Both)
+    }
+
+    pub fn vertical(self) -> bool {
+        matches!(self, MoodboardFlip::Vertical | MoodboardFlip::Both)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -121,3 +516,62 @@ pub struct ImageSource {
     #[serde(rename = "updatedAt")]
     pub updated_at: i64,
 }
+
+/// Where files dropped into a hot folder are imported.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum HotFolderTarget {
+    Journal,
+    /// References Main
+    References,
+    ReferenceFolder {
+        #[serde(rename = "folderId")]
+        folder_id: String,
+    },
+    Moodboard {
+        #[serde(rename = "moodboardId")]
+        moodboard_id: String,
+    },
+}
+
+/// What happens to a file in the hot folder once it has been imported.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum HotFolderPolicy {
+    /// Into `moveTo`, or an `Imported` subfolder
+    #[default]
+    Move,
+    Delete,
+    Leave,
+}
+
+/// A watched directory (e.g. a scanner's or drawing app's save folder) whose new
+/// images are imported automatically (app_data/hot_folders.json).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HotFolder {
+    pub id: String,
+    pub path: String,
+    pub target: HotFolderTarget,
+    /// Added to imported journal entries and references
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "afterImport")]
+    #[serde(default)]
+    pub after_import: HotFolderPolicy,
+    #[serde(rename = "moveTo")]
+    #[serde(default)]
+    pub move_to: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "importedCount")]
+    #[serde(default)]
+    pub imported_count: u32,
+    #[serde(rename = "lastImportAt")]
+    #[serde(default)]
+    pub last_import_at: Option<i64>,
+    #[serde(rename = "lastError")]
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
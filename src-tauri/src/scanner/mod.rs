@@ -0,0 +1,191 @@
+//! Flatbed scanner access behind a platform-neutral `ScannerBackend` trait.
+//!
+//! ## Module Structure
+//!
+//! - `wia`: Windows Image Acquisition through PowerShell scripts (Windows only)
+//! - `sane`: SANE's `scanimage` command-line frontend (Linux, macOS with sane-backends)
+//!
+//! Backends return the device's encoded image bytes; `encode_scan` turns them into
+//! the JPEG/PNG files the commands hand out or store.
+
+#[cfg(target_os = "windows")]
+pub mod wia;
+#[cfg(not(target_os = "windows"))]
+pub mod sane;
+
+use std::io::Cursor;
+use serde::{Deserialize, Serialize};
+use image::{DynamicImage, ImageOutputFormat};
+
+pub const DEFAULT_RESOLUTION: u32 = 300;
+pub const MIN_RESOLUTION: u32 = 75;
+pub const MAX_RESOLUTION: u32 = 1200;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannedImage {
+    pub filename: String,
+    pub data_base64: String,
+    pub mime: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannerInfo {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    #[default]
+    Color,
+    Gray,
+    /// Black and white (1 bit), for line drawings
+    Lineart,
+}
+
+/// Region to scan, in millimetres from the top-left corner of the glass.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ScanArea {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ScanArea {
+    /// Named paper sizes placed at the glass origin (portrait).
+    pub fn from_page_size(name: &str) -> Option<ScanArea> {
+        let (width, height) = match name.trim().to_ascii_uppercase().as_str() {
+            "A3" => (297.0, 420.0),
+            "A4" => (210.0, 297.0),
+            "A5" => (148.0, 210.0),
+            "A6" => (105.0, 148.0),
+            "LETTER" => (215.9, 279.4),
+            _ => return None,
+        };
+        Some(ScanArea { x: 0.0, y: 0.0, width, height })
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanOptions {
+    /// Dots per inch (75-1200, default 300)
+    pub resolution: Option<u32>,
+    #[serde(default)]
+    pub color_mode: ColorMode,
+    /// Explicit area; takes precedence over `page_size`
+    pub area: Option<ScanArea>,
+    /// "A3", "A4", "A5", "A6" or "Letter"; unset scans the whole glass
+    pub page_size: Option<String>,
+}
+
+impl ScanOptions {
+    pub fn resolution(&self) -> u32 {
+        self.resolution.unwrap_or(DEFAULT_RESOLUTION)
+    }
+
+    /// Area to scan, if restricted.
+    pub fn area(&self) -> Option<ScanArea> {
+        self.area.or_else(|| self.page_size.as_deref().and_then(ScanArea::from_page_size))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_RESOLUTION..=MAX_RESOLUTION).contains(&self.resolution()) {
+            return Err(format!(
+                "Resolution must be between {} and {} dpi",
+                MIN_RESOLUTION, MAX_RESOLUTION
+            ));
+        }
+        if let Some(size) = self.page_size.as_deref() {
+            if self.area.is_none() && ScanArea::from_page_size(size).is_none() {
+                return Err(format!("Unknown page size: {}", size));
+            }
+        }
+        if let Some(area) = self.area {
+            let values = [area.x, area.y, area.width, area.height];
+            if values.iter().any(|v| !v.is_finite() || *v < 0.0) || area.width <= 0.0 || area.height <= 0.0 {
+                return Err("Scan area must have a positive width and height".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+pub trait ScannerBackend {
+    fn list_devices(&self) -> Result<Vec<ScannerInfo>, String>;
+
+    /// Scan one page from `device_id` and return the encoded image.
+    fn scan(&self, device_id: &str, options: &ScanOptions) -> Result<Vec<u8>, String>;
+
+    /// Scan without a chosen device. Backends with a system dialog (WIA) let the user
+    /// pick the device and settings there; the default uses the first device found.
+    fn acquire(&self, options: &ScanOptions) -> Result<Vec<Vec<u8>>, String> {
+        let device = self
+            .list_devices()?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No scanners found".to_string())?;
+        Ok(vec![self.scan(&device.id, options)?])
+    }
+}
+
+/// Backend for the current platform.
+pub fn platform_backend() -> Box<dyn ScannerBackend> {
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(wia::WiaBackend)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Box::new(sane::SaneBackend)
+    }
+}
+
+/// Scan with `device_id`, or through `acquire` when none is given.
+pub fn run_scan(device_id: Option<&str>, options: &ScanOptions) -> Result<Vec<Vec<u8>>, String> {
+    options.validate()?;
+    let backend = platform_backend();
+    let pages = match device_id {
+        Some(id) => vec![backend.scan(id, options)?],
+        None => backend.acquire(options)?,
+    };
+    if pages.is_empty() {
+        return Err("No images were returned from the scanner.".to_string());
+    }
+    Ok(pages)
+}
+
+/// Re-encode scanner output (BMP, PNM, TIFF, ... depending on the device) as JPEG, or
+/// PNG when `lossless`. Returns the bytes and the file extension.
+pub fn encode_scan(data: &[u8], lossless: bool) -> Result<(Vec<u8>, &'static str), String> {
+    let img = image::load_from_memory(data)
+        .map_err(|e| format!("Failed to decode scanned image: {}", e))?;
+    let (format, ext) = if lossless {
+        (ImageOutputFormat::Png, "png")
+    } else {
+        (ImageOutputFormat::Jpeg(90), "jpg")
+    };
+    // JPEG takes 8-bit grey or RGB only (16-bit scans, alpha, ...)
+    let img = match img {
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_) => img,
+        DynamicImage::ImageLuma16(_) | DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLumaA16(_) if !lossless => {
+            DynamicImage::ImageLuma8(img.to_luma8())
+        }
+        other if !lossless => DynamicImage::ImageRgb8(other.to_rgb8()),
+        other => other,
+    };
+    let mut buf = Vec::new();
+    img.write_to(&mut Cursor::new(&mut buf), format)
+        .map_err(|e| format!("Failed to encode scanned image: {}", e))?;
+    Ok((buf, ext))
+}
+
+pub fn mime_for_extension(ext: &str) -> &'static str {
+    match ext {
+        "png" => "image/png",
+        _ => "image/jpeg",
+    }
+}
@@ -0,0 +1,83 @@
+//! SANE backend: drives the `scanimage` frontend from sane-backends.
+//!
+//! Devices come from `scanimage -f` (formatted `-L`); pages are read as PNM from
+//! stdout, which every `scanimage` version supports. SANE's virtual `test` device
+//! (enable `test` in `/etc/sane.d/dll.conf`) works without any hardware.
+
+use std::process::Command;
+
+use super::{ColorMode, ScanOptions, ScannerBackend, ScannerInfo};
+
+const PROGRAM: &str = "scanimage";
+
+pub struct SaneBackend;
+
+fn run(args: &[String]) -> Result<Vec<u8>, String> {
+    let output = Command::new(PROGRAM)
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                "scanimage was not found. Install SANE (sane-utils / sane-backends) to use a scanner.".to_string()
+            }
+            _ => format!("Failed to run scanimage: {}", e),
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "scanimage failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn mode_name(mode: ColorMode) -> &'static str {
+    // Standard SANE_VALUE_SCAN_MODE_* names
+    match mode {
+        ColorMode::Color => "Color",
+        ColorMode::Gray => "Gray",
+        ColorMode::Lineart => "Lineart",
+    }
+}
+
+/// `scanimage` arguments for one page; geometry options are in millimetres.
+pub fn scan_args(device_id: &str, options: &ScanOptions) -> Vec<String> {
+    let mut args = vec![
+        format!("--device-name={}", device_id),
+        "--format=pnm".to_string(),
+        format!("--resolution={}", options.resolution()),
+        format!("--mode={}", mode_name(options.color_mode)),
+    ];
+    if let Some(area) = options.area() {
+        for (flag, value) in [("-l", area.x), ("-t", area.y), ("-x", area.width), ("-y", area.height)] {
+            args.push(flag.to_string());
+            args.push(value.to_string());
+        }
+    }
+    args
+}
+
+impl ScannerBackend for SaneBackend {
+    fn list_devices(&self) -> Result<Vec<ScannerInfo>, String> {
+        let stdout = run(&["--formatted-device-list=%d|%v %m%n".to_string()])?;
+        let stdout = String::from_utf8_lossy(&stdout);
+        let mut scanners = Vec::new();
+        for line in stdout.lines() {
+            if let Some((id, name)) = line.split_once('|') {
+                scanners.push(ScannerInfo {
+                    id: id.trim().to_string(),
+                    name: name.trim().to_string(),
+                });
+            }
+        }
+        Ok(scanners)
+    }
+
+    fn scan(&self, device_id: &str, options: &ScanOptions) -> Result<Vec<u8>, String> {
+        let data = run(&scan_args(device_id, options))?;
+        if data.is_empty() {
+            return Err("No image captured.".to_string());
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,201 @@
+//! WIA backend: Windows Image Acquisition through PowerShell and the WIA COM objects.
+//!
+//! Scripts save each page to %TEMP% and print its path; the files are read back and
+//! removed here.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use super::{ColorMode, ScanOptions, ScannerBackend, ScannerInfo};
+
+pub struct WiaBackend;
+
+/// Brings the PowerShell window (and with it the WIA dialogs) to the front.
+const FOREGROUND_PRELUDE: &str = r#"
+            Add-Type -AssemblyName System.Drawing
+            Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+public class Win {
+  [DllImport("user32.dll")]
+  public static extern bool SetForegroundWindow(IntPtr hWnd);
+  [DllImport("user32.dll")]
+  public static extern bool ShowWindowAsync(IntPtr hWnd, int nCmdShow);
+  [DllImport("user32.dll")]
+  public static extern bool SetWindowPos(IntPtr hWnd, IntPtr hWndInsertAfter, int X, int Y, int cx, int cy, uint uFlags);
+  public static readonly IntPtr HWND_TOPMOST = new IntPtr(-1);
+  public static readonly IntPtr HWND_NOTOPMOST = new IntPtr(-2);
+}
+"@
+            $null = [Win]::SetForegroundWindow((Get-Process -Id $PID).MainWindowHandle)
+            $null = [Win]::ShowWindowAsync((Get-Process -Id $PID).MainWindowHandle, 5)
+            $null = [Win]::SetWindowPos((Get-Process -Id $PID).MainWindowHandle, [Win]::HWND_TOPMOST, 0,0,0,0, 0x0003)
+"#;
+
+fn run_powershell(script: &str, action: &str) -> Result<String, String> {
+    let output = Command::new("powershell")
+        .args([
+            "-NoLogo",
+            "-NoProfile",
+            "-Sta",
+            "-ExecutionPolicy",
+            "Bypass",
+            "-Command",
+            script,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to launch PowerShell for {}: {}", action, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Scanner {} failed: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Read (and delete) every file path printed by a scan script.
+fn collect_pages(stdout: &str) -> Result<Vec<Vec<u8>>, String> {
+    let mut pages = Vec::new();
+    for line in stdout.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let path = Path::new(trimmed);
+        if !path.exists() {
+            continue;
+        }
+        let data = fs::read(path)
+            .map_err(|e| format!("Failed to read scanned file {}: {}", trimmed, e))?;
+        let _ = fs::remove_file(path);
+        pages.push(data);
+    }
+    Ok(pages)
+}
+
+impl ScannerBackend for WiaBackend {
+    fn list_devices(&self) -> Result<Vec<ScannerInfo>, String> {
+        let script = r#"
+            $ErrorActionPreference = 'Stop'
+            $dm = New-Object -ComObject WIA.DeviceManager
+            $out = @()
+            foreach ($info in $dm.DeviceInfos) {
+              if ($info.Type -eq 1) { # Scanner
+                $out += ($info.DeviceID + '|' + $info.Properties["Name"].Value)
+              }
+            }
+            $out -join "`n"
+        "#;
+
+        let stdout = run_powershell(script, "listing")?;
+        let mut scanners = Vec::new();
+        for line in stdout.lines() {
+            if let Some((id, name)) = line.split_once('|') {
+                scanners.push(ScannerInfo {
+                    id: id.trim().to_string(),
+                    name: name.trim().to_string(),
+                });
+            }
+        }
+        Ok(scanners)
+    }
+
+    fn scan(&self, device_id: &str, options: &ScanOptions) -> Result<Vec<u8>, String> {
+        let dpi = options.resolution();
+        let to_px = |mm: f32| (mm / 25.4 * dpi as f32).round() as i64;
+        // Without an area, keep the device's full-bed extents
+        let area = match options.area() {
+            Some(area) => format!(
+                r#"
+              $item.Properties["3096"].Value = 0      # WIA_IPS_PAGE_SIZE (Custom)
+              $item.Properties["6149"].Value = {x}      # WIA_IPS_XPOS
+              $item.Properties["6150"].Value = {y}      # WIA_IPS_YPOS
+              $item.Properties["6151"].Value = {w}  # WIA_IPS_XEXTENT
+              $item.Properties["6152"].Value = {h} # WIA_IPS_YEXTENT"#,
+                x = to_px(area.x),
+                y = to_px(area.y),
+                w = to_px(area.width),
+                h = to_px(area.height),
+            ),
+            None => String::new(),
+        };
+        // WIA_INTENT_IMAGE_TYPE_COLOR / _GRAYSCALE / _TEXT
+        let intent = match options.color_mode {
+            ColorMode::Color => 0x1,
+            ColorMode::Gray => 0x2,
+            ColorMode::Lineart => 0x4,
+        };
+
+        let script = format!(
+            r#"
+            $ErrorActionPreference = 'Stop'
+            {prelude}
+            $dm = New-Object -ComObject WIA.DeviceManager
+            $dev = $dm.DeviceInfos | Where-Object {{ $_.DeviceID -eq '{device}' }}
+            if (-not $dev) {{ exit 0 }}
+            $device = $dev.Connect()
+
+            $dpi = {dpi}
+            $item = $device.Items[1]
+            try {{
+              $item.Properties["6146"].Value = {intent} # WIA_IPS_CUR_INTENT
+              $item.Properties["6147"].Value = $dpi   # WIA_IPS_XRES
+              $item.Properties["6148"].Value = $dpi   # WIA_IPS_YRES{area}
+            }} catch {{}}
+
+            # Transfer as BMP (what most drivers deliver anyway); re-encoded by the caller
+            $convertFilterId = "{{B96B3CAA-0728-11D3-9D7B-0000F81EF32E}}"
+            $common = New-Object -ComObject WIA.CommonDialog
+            $image = $common.ShowTransfer($item, $convertFilterId)
+            if ($null -eq $image) {{ exit 0 }}
+
+            $ext = '.bmp'
+            $name = 'scan_' + [guid]::NewGuid().ToString() + $ext
+            $path = Join-Path $env:TEMP $name
+            $image.SaveFile($path)
+            $path
+        "#,
+            prelude = FOREGROUND_PRELUDE,
+            device = device_id.replace('\'', "''"),
+        );
+
+        let stdout = run_powershell(&script, "capture")?;
+        collect_pages(&stdout)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No image captured.".to_string())
+    }
+
+    /// The WIA acquire dialog picks the device and settings itself; `options` are not used.
+    fn acquire(&self, _options: &ScanOptions) -> Result<Vec<Vec<u8>>, String> {
+        let script = format!(
+            r#"
+            $ErrorActionPreference = 'Stop'
+            {prelude}
+            $dialog = New-Object -ComObject WIA.CommonDialog
+            $image = $dialog.ShowAcquireImage()
+            if ($null -eq $image) {{ exit 0 }}
+            # Convert to PNG to avoid format mismatches
+            $convertFilterId = "{{B96B3CAF-0728-11D3-9D7B-0000F81EF32E}}" # PNG
+            $imgProcess = New-Object -ComObject WIA.ImageProcess
+            $imgProcess.Filters.Add($imgProcess.FilterInfos["Convert"].FilterID) | Out-Null
+            $imgProcess.Filters[1].Properties["FormatID"].Value = $convertFilterId
+            $converted = $imgProcess.Apply($image)
+
+            $ext = '.png'
+            $name = 'scan_' + [guid]::NewGuid().ToString() + $ext
+            $path = Join-Path $env:TEMP $name
+            $converted.SaveFile($path)
+            $path
+        "#,
+            prelude = FOREGROUND_PRELUDE,
+        );
+
+        let stdout = run_powershell(&script, "wizard")?;
+        collect_pages(&stdout)
+    }
+}
@@ -1,4 +1,5 @@
-//! Handlers for photo journal image uploads (single and batch).
+//! Handlers for photo journal image uploads (single and batch) and the piece list
+//! the phone page offers for adding stages.
 
 use axum::{
     extract::{Multipart, Query, ConnectInfo, State},
@@ -11,19 +12,87 @@ use std::fs;
 use uuid::Uuid;
 use chrono::Utc;
 
-use crate::server::types::TokenQuery;
+use crate::server::types::{PhotoJournalUploadQuery, TokenQuery};
+use crate::commands::journal_pieces::{append_piece_stages, read_journal_pieces};
+use crate::commands::paper_correction::correct_on_ingest;
 use crate::server::token::validate_token;
 
+/// Reject uploads aimed at a piece that doesn't exist before anything is written.
+fn check_piece(app_handle: &AppHandle, piece_id: Option<&str>) -> Result<(), (StatusCode, &'static str)> {
+    let piece_id = match piece_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let state = app_handle.state::<crate::AppState>();
+    match read_journal_pieces(&state) {
+        Ok(pieces) if pieces.iter().any(|p| p.id == piece_id) => Ok(()),
+        Ok(_) => Err((StatusCode::NOT_FOUND, "Piece not found")),
+        Err(e) => {
+            eprintln!("Failed reading journal pieces: {}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Pieces read error"))
+        }
+    }
+}
+
+/// Append freshly uploaded entries to a piece. The images are already saved, so a
+/// failure here is logged rather than failing the upload.
+fn add_stages(app_handle: &AppHandle, piece_id: &str, image_ids: &[String], label: Option<String>) {
+    let state = app_handle.state::<crate::AppState>();
+    match append_piece_stages(&state, piece_id, image_ids, label) {
+        Ok(piece) => {
+            let _ = app_handle.emit_all("journal_piece_updated", piece);
+        }
+        Err(e) => eprintln!("Failed adding stages to piece {}: {}", piece_id, e),
+    }
+}
+
+/// Handler for GET /api/photo-journal/pieces - lists pieces for the phone page, most
+/// recently updated first.
+pub async fn list_journal_pieces_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Query(params): Query<TokenQuery>,
+    State(app_handle): State<AppHandle>,
+) -> impl IntoResponse {
+    if let Err(err) = validate_token(addr.ip(), params.token.as_deref()) {
+        return err.into_response();
+    }
+
+    let state = app_handle.state::<crate::AppState>();
+    let mut pieces = match read_journal_pieces(&state) {
+        Ok(pieces) => pieces,
+        Err(e) => {
+            eprintln!("Failed reading journal pieces: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Pieces read error").into_response();
+        }
+    };
+    pieces.sort_by_key(|p| std::cmp::Reverse(p.updated_at));
+
+    let list: Vec<serde_json::Value> = pieces
+        .iter()
+        .map(|p| serde_json::json!({
+            "id": p.id,
+            "title": p.title,
+            "stageCount": p.stages.len(),
+            "updatedAt": p.updated_at
+        }))
+        .collect();
+
+    (StatusCode::OK, axum::Json(list)).into_response()
+}
+
 /// Handler for POST /api/photo-journal - uploads a single photo journal image.
 pub async fn upload_photo_journal_handler(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    Query(params): Query<TokenQuery>,
+    Query(params): Query<PhotoJournalUploadQuery>,
     State(app_handle): State<AppHandle>,
     mut multipart: Multipart
 ) -> impl IntoResponse {
     if let Err(err) = validate_token(addr.ip(), params.token.as_deref()) {
         return err.into_response();
     }
+    if let Err(response) = check_piece(&app_handle, params.piece_id.as_deref()) {
+        return response.into_response();
+    }
 
     // Expect a single field named 'image'
     let mut filename: Option<String> = None;
@@ -69,6 +138,12 @@ pub async fn upload_photo_journal_handler(
         return (StatusCode::INTERNAL_SERVER_ERROR, "Write error").into_response();
     }
 
+    // Straighten and white-balance photos of paper (keeps the original)
+    let (original_url, correction) = match correct_on_ingest(&state, &filename, params.correct) {
+        Some((url, correction)) => (Some(url), Some(correction)),
+        None => (None, None),
+    };
+
     let mime = match std::path::Path::new(&filename).extension().and_then(|s| s.to_str()) {
         Some("png") => "image/png",
         Some("jpg") | Some("jpeg") => "image/jpeg",
@@ -86,7 +161,14 @@ pub async fn upload_photo_journal_handler(
         "mimetype": mime,
         "prompt": serde_json::Value::Null,
         "referenceId": serde_json::Value::Null,
-        "rotation": 0
+        "rotation": 0,
+        "comparisons": [],
+        "referenceIds": [],
+        "masterStudyPaths": [],
+        "tags": [],
+        "pieceId": params.piece_id,
+        "originalUrl": original_url,
+        "paperCorrection": correction
     });
 
     let mut images: Vec<serde_json::Value> = match fs::read_to_string(&json_path) {
@@ -102,19 +184,27 @@ pub async fn upload_photo_journal_handler(
 
     let _ = app_handle.emit_all("photo_journal_updated", record.clone());
 
+    if let Some(piece_id) = params.piece_id.as_deref() {
+        let image_ids = vec![record["id"].as_str().unwrap_or_default().to_string()];
+        add_stages(&app_handle, piece_id, &image_ids, params.stage_label.clone());
+    }
+
     (StatusCode::OK, axum::Json(record)).into_response()
 }
 
 /// Handler for POST /api/photo-journal/batch - uploads multiple photo journal images.
 pub async fn upload_photo_journal_batch_handler(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    Query(params): Query<TokenQuery>,
+    Query(params): Query<PhotoJournalUploadQuery>,
     State(app_handle): State<AppHandle>,
     mut multipart: Multipart
 ) -> impl IntoResponse {
     if let Err(err) = validate_token(addr.ip(), params.token.as_deref()) {
         return err.into_response();
     }
+    if let Err(response) = check_piece(&app_handle, params.piece_id.as_deref()) {
+        return response.into_response();
+    }
 
     let state = app_handle.state::<crate::AppState>();
     let images_dir = state.library_dir.join("Artwork Journal");
@@ -182,6 +272,11 @@ pub async fn upload_photo_journal_batch_handler(
             continue;
         }
 
+        let (original_url, correction) = match correct_on_ingest(&state, &filename, params.correct) {
+            Some((url, correction)) => (Some(url), Some(correction)),
+            None => (None, None),
+        };
+
         let mime = match std::path::Path::new(&filename).extension().and_then(|s| s.to_str()) {
             Some("png") => "image/png",
             Some("jpg") | Some("jpeg") => "image/jpeg",
@@ -199,7 +294,14 @@ pub async fn upload_photo_journal_batch_handler(
             "mimetype": mime,
             "prompt": serde_json::Value::Null,
             "referenceId": serde_json::Value::Null,
-            "rotation": 0
+            "rotation": 0,
+            "comparisons": [],
+            "referenceIds": [],
+            "masterStudyPaths": [],
+            "tags": [],
+            "pieceId": params.piece_id,
+            "originalUrl": original_url,
+            "paperCorrection": correction
         });
 
         images.insert(0, record.clone());
@@ -219,6 +321,17 @@ pub async fn upload_photo_journal_batch_handler(
         return (StatusCode::INTERNAL_SERVER_ERROR, "JSON write error").into_response();
     }
 
+    if let Some(piece_id) = params.piece_id.as_deref() {
+        // Stages follow upload order
+        let image_ids: Vec<String> = successes
+            .iter()
+            .filter_map(|s| s["id"].as_str().map(str::to_string))
+            .collect();
+        if !image_ids.is_empty() {
+            add_stages(&app_handle, piece_id, &image_ids, params.stage_label.clone());
+        }
+    }
+
     let resp = serde_json::json!({
         "success": !successes.is_empty(),
         "count": successes.len(),
@@ -77,6 +77,13 @@ fn build_reference(filename: String, original_name: String) -> Reference {
         image_source: None,
         rotation: 0,
         crop: None,
+        rating: 0,
+        favorite: false,
+        times_studied: 0,
+        last_studied_at: None,
+        adjustments: Vec::new(),
+        derived_from: None,
+        grid_overlay: None,
     }
 }
 
@@ -102,6 +102,30 @@ pub const PHONE_UPLOAD_HTML: &str = r#"<!doctype html>
         accent-color: var(--accent);
       }
 
+      .piece-options {
+        display: none;
+        margin-bottom: 14px;
+        gap: 8px;
+        text-align: left;
+        font-size: 13px;
+        color: var(--text-secondary);
+      }
+
+      .piece-options.active { display: grid; }
+
+      .piece-options select,
+      .piece-options input {
+        width: 100%;
+        padding: 10px 12px;
+        border-radius: 10px;
+        border: 1px solid var(--border-default);
+        background: #111622;
+        color: var(--text-primary);
+        font: inherit;
+      }
+
+      .piece-options input:disabled { opacity: 0.5; }
+
       .progress {
         margin-top: 12px;
         width: 100%;
@@ -183,6 +207,13 @@ pub const PHONE_UPLOAD_HTML: &str = r#"<!doctype html>
         <li>Check the References grid</li>
       </ol>
       <div class="upload-area">
+        <div class="piece-options" id="piece-options">
+          <label for="piece-select">Add to</label>
+          <select id="piece-select">
+            <option value="">New journal entries</option>
+          </select>
+          <input id="stage-label" type="text" placeholder="Stage label (e.g. Block-in)" maxlength="60" disabled />
+        </div>
         <label class="upload-button" for="file-input">Choose photos</label>
         <input id="file-input" type="file" accept="image/*" multiple />
         <div class="status" id="status">No files selected.</div>
@@ -216,6 +247,45 @@ pub const PHONE_UPLOAD_HTML: &str = r#"<!doctype html>
       const subtitle = document.getElementById('page-subtitle');
       if (title) title.textContent = `Upload to ${targetLabel}`;
       if (subtitle) subtitle.textContent = `Send images from your phone directly to the ${targetLabel} tab on your desktop.`;
+      const pieceOptions = document.getElementById('piece-options');
+      const pieceSelect = document.getElementById('piece-select');
+      const stageLabel = document.getElementById('stage-label');
+
+      // Journal uploads can be appended as stages of an existing piece
+      async function loadPieces() {
+        if (target !== 'photo_journal' || !token) return;
+        pieceOptions.classList.add('active');
+        try {
+          const res = await fetch('/api/photo-journal/pieces?token=' + encodeURIComponent(token));
+          if (!res.ok) return;
+          const pieces = await res.json();
+          pieces.forEach((piece) => {
+            const option = document.createElement('option');
+            option.value = piece.id;
+            option.textContent = `${piece.title} (${piece.stageCount} stage${piece.stageCount === 1 ? '' : 's'})`;
+            pieceSelect.appendChild(option);
+          });
+          const preselected = params.get('piece');
+          if (preselected && pieces.some((piece) => piece.id === preselected)) {
+            pieceSelect.value = preselected;
+          }
+        } catch {}
+        stageLabel.disabled = !pieceSelect.value;
+      }
+
+      pieceSelect.addEventListener('change', () => {
+        stageLabel.disabled = !pieceSelect.value;
+      });
+
+      function uploadQuery() {
+        let query = '?token=' + encodeURIComponent(token);
+        if (target === 'photo_journal' && pieceSelect.value) {
+          query += '&pieceId=' + encodeURIComponent(pieceSelect.value);
+          const label = stageLabel.value.trim();
+          if (label) query += '&stageLabel=' + encodeURIComponent(label);
+        }
+        return query;
+      }
 
       function setProgress(value) {
         const pct = Math.max(0, Math.min(100, value));
@@ -292,7 +362,7 @@ pub const PHONE_UPLOAD_HTML: &str = r#"<!doctype html>
         const form = new FormData();
         form.append('image', file, file.name);
         try {
-          const res = await fetch(endpointBase + '?token=' + encodeURIComponent(token), { method: 'POST', body: form });
+          const res = await fetch(endpointBase + uploadQuery(), { method: 'POST', body: form });
           if (!res.ok) {
             if (res.status === 401) {
               showTokenExpired();
@@ -312,7 +382,7 @@ pub const PHONE_UPLOAD_HTML: &str = r#"<!doctype html>
         files.forEach((file) => form.append('image', file, file.name));
         return await new Promise((resolve, reject) => {
           const xhr = new XMLHttpRequest();
-          xhr.open('POST', endpointBase + '/batch?token=' + encodeURIComponent(token));
+          xhr.open('POST', endpointBase + '/batch' + uploadQuery());
           xhr.upload.onprogress = (event) => {
             if (event.lengthComputable) {
               const pct = Math.round((event.loaded / event.total) * 100);
@@ -416,6 +486,8 @@ pub const PHONE_UPLOAD_HTML: &str = r#"<!doctype html>
         status.textContent = 'This upload link is missing a token. Please rescan the QR code.';
         input.disabled = true;
       }
+
+      loadPieces();
     </script>
   </body>
 </html>
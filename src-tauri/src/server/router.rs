@@ -17,6 +17,7 @@ use super::handlers::{
     upload_reference_batch_handler,
     upload_photo_journal_handler,
     upload_photo_journal_batch_handler,
+    list_journal_pieces_handler,
 };
 use super::phone_page::PHONE_UPLOAD_HTML;
 
@@ -35,6 +36,7 @@ pub fn build_router(app_handle: AppHandle) -> Router {
         .route("/api/references/batch", post(upload_reference_batch_handler))
         .route("/api/photo-journal", post(upload_photo_journal_handler))
         .route("/api/photo-journal/batch", post(upload_photo_journal_batch_handler))
+        .route("/api/photo-journal/pieces", get(list_journal_pieces_handler))
         .route("/phone", get(|| async { Html(PHONE_UPLOAD_HTML) }))
         .with_state(app_handle)
         .layer(cors)
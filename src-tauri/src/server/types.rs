@@ -90,3 +90,16 @@ pub struct PhoneInfoResponse {
 pub struct TokenQuery {
     pub token: Option<String>,
 }
+
+/// Query parameters for photo journal uploads.
+#[derive(Deserialize)]
+pub struct PhotoJournalUploadQuery {
+    pub token: Option<String>,
+    /// Append the uploaded images as stages of this journal piece.
+    #[serde(rename = "pieceId")]
+    pub piece_id: Option<String>,
+    #[serde(rename = "stageLabel")]
+    pub stage_label: Option<String>,
+    /// Override the journal's automatic paper correction setting.
+    pub correct: Option<bool>,
+}